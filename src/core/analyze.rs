@@ -0,0 +1,284 @@
+//! Sequence health checks built on per-frame metadata.
+//!
+//! These analyses need a sequence scanned with `with_metadata` (see
+//! [`crate::core::ScanOptions`]); on a sequence without metadata they report
+//! nothing. They never touch the filesystem themselves.
+//!
+//! - [`Seq::suspect_frames`]: zero-byte, truncated and size-drop frames, the
+//!   usual leftovers of failed or interrupted renders.
+//! - [`Seq::stale_frames`]: frames left over from an earlier render pass,
+//!   found by mtime.
+
+use super::seq::Seq;
+use serde::Serialize;
+use std::time::{Duration, SystemTime};
+
+/// Thresholds for [`Seq::suspect_frames`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SuspectThresholds {
+    /// Neighbours taken on EACH side of a frame for its running median.
+    pub window: usize,
+    /// A frame smaller than `min_ratio * median` of its neighbours is truncated.
+    pub min_ratio: f64,
+    /// A frame whose size falls by more than this fraction relative to the
+    /// previous frame marks a sustained size drop (`0.5` = lost half its size).
+    pub max_drop: f64,
+}
+
+impl Default for SuspectThresholds {
+    fn default() -> Self {
+        SuspectThresholds { window: 3, min_ratio: 0.5, max_drop: 0.5 }
+    }
+}
+
+/// Why a frame was flagged by [`Seq::suspect_frames`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SuspectReason {
+    /// The file is 0 bytes.
+    Empty,
+    /// Far smaller than the running median of its neighbours (a one-off short write).
+    Truncated { median: u64 },
+    /// Sharply smaller than the previous frame, with the neighbours following it
+    /// down (e.g. a render setting or the disk changed mid-sequence).
+    SizeDrop { previous: u64 },
+}
+
+/// A frame flagged by [`Seq::suspect_frames`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct SuspectFrame {
+    /// Frame number
+    pub frame: i64,
+    /// File size in bytes
+    pub size: u64,
+    /// What gave it away
+    pub reason: SuspectReason,
+}
+
+impl std::fmt::Display for SuspectFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.reason {
+            SuspectReason::Empty => write!(f, "frame {}: empty (0 bytes)", self.frame),
+            SuspectReason::Truncated { median } => {
+                write!(f, "frame {}: truncated ({} bytes, neighbours ~{})", self.frame, self.size, median)
+            }
+            SuspectReason::SizeDrop { previous } => {
+                write!(f, "frame {}: size drop ({} bytes, previous {})", self.frame, self.size, previous)
+            }
+        }
+    }
+}
+
+/// Median of a small, unsorted sample. Lower median for even lengths, so a frame
+/// sitting right at a sustained drop is judged by the smaller half and reported
+/// as a drop rather than as truncated.
+fn median(sample: &mut [u64]) -> Option<u64> {
+    if sample.is_empty() {
+        return None;
+    }
+    let mid = (sample.len() - 1) / 2;
+    Some(*sample.select_nth_unstable(mid).1)
+}
+
+impl Seq {
+    /// Frames that are likely broken renders, ascending by frame. Each frame gets
+    /// at most one reason, checked in order:
+    ///
+    /// 1. [`SuspectReason::Empty`] — 0 bytes.
+    /// 2. [`SuspectReason::Truncated`] — below `min_ratio` of the median size of
+    ///    up to `window` present frames on each side.
+    /// 3. [`SuspectReason::SizeDrop`] — more than `max_drop` smaller than the last
+    ///    non-empty frame before it. Only the first frame of a sustained drop is
+    ///    reported, since the frames after it compare against an equally small one.
+    ///
+    /// Returns nothing for sequences scanned without metadata; frames without
+    /// metadata (broken links) are skipped.
+    #[must_use]
+    #[allow(dead_code)] // Public API
+    pub fn suspect_frames(&self, thresholds: &SuspectThresholds) -> Vec<SuspectFrame> {
+        let sizes: Vec<(i64, u64)> = self.frame_infos().map(|(f, info)| (f, info.size)).collect();
+        let mut out = Vec::new();
+        let mut previous: Option<u64> = None;
+        let mut sample = Vec::with_capacity(thresholds.window * 2);
+
+        for (i, &(frame, size)) in sizes.iter().enumerate() {
+            let reason = if size == 0 {
+                Some(SuspectReason::Empty)
+            } else {
+                sample.clear();
+                let lo = i.saturating_sub(thresholds.window);
+                let hi = (i + thresholds.window + 1).min(sizes.len());
+                sample.extend(sizes[lo..i].iter().chain(&sizes[i + 1..hi]).map(|&(_, s)| s));
+                let truncated = median(&mut sample)
+                    .filter(|&m| (size as f64) < m as f64 * thresholds.min_ratio)
+                    .map(|median| SuspectReason::Truncated { median });
+                truncated.or_else(|| {
+                    previous
+                        .filter(|&p| (size as f64) < p as f64 * (1.0 - thresholds.max_drop))
+                        .map(|previous| SuspectReason::SizeDrop { previous })
+                })
+            };
+            if size > 0 {
+                previous = Some(size);
+            }
+            if let Some(reason) = reason {
+                out.push(SuspectFrame { frame, size, reason });
+            }
+        }
+        out
+    }
+}
+
+/// How [`Seq::stale_frames`] decides a frame is stale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StaleRule {
+    /// Modified before this instant (e.g. when the re-render was submitted).
+    Cutoff(SystemTime),
+    /// Older than the majority cluster. Frame mtimes are split into clusters
+    /// wherever two consecutive mtimes (in time order) are more than `gap` apart;
+    /// the cluster holding the most frames is the current render pass.
+    Majority { gap: Duration },
+}
+
+impl Default for StaleRule {
+    fn default() -> Self {
+        StaleRule::Majority { gap: Duration::from_secs(3600) }
+    }
+}
+
+impl Seq {
+    /// Frames left over from an earlier render pass, ascending by frame.
+    ///
+    /// Frames without an mtime are never stale; without metadata the result is
+    /// empty. Under [`StaleRule::Majority`] a tie between clusters goes to the
+    /// newest one, so a half re-rendered sequence reports the older half.
+    #[must_use]
+    #[allow(dead_code)] // Public API
+    pub fn stale_frames(&self, rule: &StaleRule) -> Vec<i64> {
+        let mut times: Vec<(SystemTime, i64)> = self.frame_infos().filter_map(|(f, info)| Some((info.mtime?, f))).collect();
+        let cutoff = match *rule {
+            StaleRule::Cutoff(cutoff) => cutoff,
+            StaleRule::Majority { gap } => {
+                times.sort_unstable();
+                // Cluster start positions in time order
+                let mut starts = vec![0];
+                starts.extend((1..times.len()).filter(|&i| times[i].0.duration_since(times[i - 1].0).unwrap_or_default() > gap));
+                let Some(majority) = (0..starts.len()).max_by_key(|&c| (starts.get(c + 1).copied().unwrap_or(times.len()) - starts[c], c)) else {
+                    return Vec::new();
+                };
+                match times.get(starts[majority]) {
+                    Some(&(t, _)) => t,
+                    None => return Vec::new(),
+                }
+            }
+        };
+        let mut stale: Vec<i64> = times.into_iter().filter(|&(t, _)| t < cutoff).map(|(_, f)| f).collect();
+        stale.sort_unstable();
+        stale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::file::{File, FrameInfo};
+
+    /// Padded sequence with the given per-frame sizes, frames numbered from 1.
+    fn sized_seq(sizes: &[u64]) -> Seq {
+        let mut files: Vec<File> = sizes
+            .iter()
+            .enumerate()
+            .map(|(i, &size)| {
+                let mut f = File::new(format!("/r/beauty.{:04}.exr", i + 1));
+                f.meta = Some(FrameInfo { size, mtime: None, inode: None });
+                f
+            })
+            .collect();
+        Seq::group_seqs(&mut files).pop().expect("one sequence")
+    }
+
+    fn flagged(seq: &Seq) -> Vec<(i64, SuspectReason)> {
+        seq.suspect_frames(&SuspectThresholds::default()).iter().map(|s| (s.frame, s.reason)).collect()
+    }
+
+    #[test]
+    fn healthy_sequence_is_clean() {
+        let seq = sized_seq(&[1000, 1010, 990, 1005, 1020, 995]);
+        assert!(flagged(&seq).is_empty());
+    }
+
+    #[test]
+    fn empty_frame() {
+        let seq = sized_seq(&[1000, 1000, 0, 1000, 1000]);
+        assert_eq!(flagged(&seq), vec![(3, SuspectReason::Empty)]);
+    }
+
+    #[test]
+    fn truncated_frame() {
+        let seq = sized_seq(&[1000, 1000, 1000, 300, 1000, 1000, 1000]);
+        assert_eq!(flagged(&seq), vec![(4, SuspectReason::Truncated { median: 1000 })]);
+    }
+
+    #[test]
+    fn sustained_drop_flags_first_frame_only() {
+        let seq = sized_seq(&[1000, 1000, 1000, 1000, 200, 200, 200, 200, 200]);
+        assert_eq!(flagged(&seq), vec![(5, SuspectReason::SizeDrop { previous: 1000 })]);
+    }
+
+    #[test]
+    fn thresholds_are_configurable() {
+        let seq = sized_seq(&[1000, 1000, 1000, 700, 1000, 1000, 1000]);
+        assert!(flagged(&seq).is_empty());
+        let strict = SuspectThresholds { min_ratio: 0.8, ..Default::default() };
+        let got = seq.suspect_frames(&strict);
+        assert_eq!(got.len(), 1);
+        assert_eq!(got[0].frame, 4);
+    }
+
+    #[test]
+    fn no_metadata_reports_nothing() {
+        let mut files: Vec<File> = (1..=3).map(|n| File::new(format!("/r/x.{n:04}.exr"))).collect();
+        let seq = Seq::group_seqs(&mut files).pop().expect("one sequence");
+        assert!(seq.suspect_frames(&SuspectThresholds::default()).is_empty());
+        assert!(seq.stale_frames(&StaleRule::default()).is_empty());
+    }
+
+    /// Sequence with per-frame mtimes given as seconds after the epoch, frames from 1.
+    fn timed_seq(secs: &[u64]) -> Seq {
+        let mut files: Vec<File> = secs
+            .iter()
+            .enumerate()
+            .map(|(i, &s)| {
+                let mut f = File::new(format!("/r/beauty.{:04}.exr", i + 1));
+                f.meta = Some(FrameInfo { size: 1000, mtime: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(s)), inode: None });
+                f
+            })
+            .collect();
+        Seq::group_seqs(&mut files).pop().expect("one sequence")
+    }
+
+    #[test]
+    fn stale_frames_older_than_majority() {
+        // Frames 3-4 and 7 kept from a render a day earlier; the rest re-rendered
+        // over ~20 minutes with gaps well under the default hour.
+        let day = 86_400;
+        let seq = timed_seq(&[day, day + 60, 100, 160, day + 300, day + 600, 40, day + 1200]);
+        assert_eq!(seq.stale_frames(&StaleRule::default()), vec![3, 4, 7]);
+    }
+
+    #[test]
+    fn stale_frames_single_pass_is_clean() {
+        let seq = timed_seq(&[0, 600, 1200, 1800, 2400]);
+        assert!(seq.stale_frames(&StaleRule::default()).is_empty());
+        // A tight gap splits one pass into clusters; the largest wins, ties go newest
+        let tight = StaleRule::Majority { gap: Duration::from_secs(60) };
+        assert_eq!(seq.stale_frames(&tight), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn stale_frames_by_cutoff() {
+        let seq = timed_seq(&[100, 200, 300, 400]);
+        let cutoff = SystemTime::UNIX_EPOCH + Duration::from_secs(250);
+        assert_eq!(seq.stale_frames(&StaleRule::Cutoff(cutoff)), vec![1, 2]);
+    }
+}
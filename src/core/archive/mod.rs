@@ -0,0 +1,289 @@
+//! Sequences inside zip and tar archives.
+//!
+//! With [`ScanOptions::archives`](super::ScanOptions::archives) set, the scanner
+//! lists every `.zip` / `.tar` it meets and reports the members in place of the
+//! archive, under archive-qualified paths:
+//!
+//! ```text
+//! /delivery/plates.zip!/A001/plate.0001.dpx   ->   /delivery/plates.zip!/A001/plate.####.dpx
+//! ```
+//!
+//! Such paths work with every `Seq` accessor ([`Seq::get_file`] formats them like
+//! any other); [`split_archive_path`] takes them apart and [`open_path`] /
+//! [`Seq::open_frame`] read a member's bytes. Each of those lists the archive
+//! anew; to read many members, go through an [`ArchiveIndex`] or a
+//! [`PathOpener`], which list every archive once.
+//!
+//! Zip members may be stored or deflated (zip64 included); encrypted members and
+//! other compression methods are listed but cannot be opened. Tar archives must be
+//! uncompressed (ustar, GNU and pax headers). Nested archives are not descended into.
+//! Archives are read with the `zip` and `tar` crates; a member whose data would
+//! reach past the end of the archive file fails the listing.
+
+mod tar;
+mod zip;
+
+use super::error::ScanError;
+use super::inventory::days_from_civil;
+use super::seq::Seq;
+use super::vfs::{Fs, StdFs};
+use std::collections::HashMap;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+/// Separator between an archive path and a member path.
+pub const ARCHIVE_SEP: &str = "!/";
+
+/// Archive formats the scanner can look into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    Zip,
+    Tar,
+}
+
+impl ArchiveKind {
+    /// Archive kind by file extension (case-insensitive): `.zip` or `.tar`.
+    #[must_use]
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let ext = path.as_ref().extension()?.to_str()?;
+        if ext.eq_ignore_ascii_case("zip") {
+            Some(ArchiveKind::Zip)
+        } else if ext.eq_ignore_ascii_case("tar") {
+            Some(ArchiveKind::Tar)
+        } else {
+            None
+        }
+    }
+}
+
+/// Where a member's bytes live inside the archive file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Storage {
+    /// Uncompressed bytes at `offset` (tar, stored zip members)
+    Raw { offset: u64 },
+    /// Deflated zip member: `compressed` bytes at `offset`, inflating to data with `crc`
+    Deflated { offset: u64, compressed: u64, crc: u32 },
+    /// Other compressed or encrypted zip member, decoded by the zip reader
+    Zip,
+}
+
+/// A file inside an archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveMember {
+    /// Path inside the archive, `/`-separated, without a leading `./`
+    pub name: String,
+    /// Uncompressed size in bytes
+    pub size: u64,
+    pub mtime: Option<SystemTime>,
+    storage: Storage,
+}
+
+fn epoch_secs(secs: i64) -> SystemTime {
+    if secs >= 0 {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(secs as u64)
+    } else {
+        SystemTime::UNIX_EPOCH - Duration::from_secs(secs.unsigned_abs())
+    }
+}
+
+/// MS-DOS date/time as stored in zip headers. The time zone is unknown; it is
+/// taken as UTC.
+fn dos_time(t: ::zip::DateTime) -> Option<SystemTime> {
+    if !t.is_valid() {
+        return None;
+    }
+    let days = days_from_civil(i64::from(t.year()), u32::from(t.month()), u32::from(t.day()));
+    let secs = i64::from(t.hour()) * 3600 + i64::from(t.minute()) * 60 + i64::from(t.second());
+    Some(epoch_secs(days * 86_400 + secs))
+}
+
+/// Check that `size` bytes at `offset` lie within an archive of `len` bytes.
+fn check_extent(name: &str, offset: u64, size: u64, len: u64) -> Result<(), String> {
+    match offset.checked_add(size) {
+        Some(end) if end <= len => Ok(()),
+        _ => Err(format!("member {} reaches past the end of the archive ({} bytes at {}, archive is {} bytes)", name, size, offset, len)),
+    }
+}
+
+/// List the file members of the archive at `path` (kind by extension).
+pub fn list_archive(fs: &dyn Fs, path: &Path) -> Result<Vec<ArchiveMember>, ScanError> {
+    let kind = ArchiveKind::from_path(path).ok_or_else(|| ScanError::parse("not a .zip or .tar file").with_path(path))?;
+    let mut reader = fs.open(path).map_err(|e| ScanError::io(path, e))?;
+    let len = reader.seek(SeekFrom::End(0)).and_then(|len| reader.rewind().map(|_| len)).map_err(|e| ScanError::io(path, e))?;
+    let members = match kind {
+        ArchiveKind::Zip => zip::list(reader, len),
+        ArchiveKind::Tar => tar::list(reader, len),
+    };
+    members.map_err(|e| ScanError::parse(e).with_path(path))
+}
+
+/// Split `archive.zip!/member/path` into the archive path and the member name.
+/// `None` for paths that don't point into a `.zip` / `.tar`.
+#[must_use]
+pub fn split_archive_path<P: AsRef<Path>>(path: P) -> Option<(PathBuf, String)> {
+    let s = path.as_ref().to_str()?;
+    s.match_indices(ARCHIVE_SEP).map(|(i, _)| i).find_map(|i| {
+        let archive = &s[..i];
+        ArchiveKind::from_path(archive).map(|_| (PathBuf::from(archive), s[i + ARCHIVE_SEP.len()..].to_string()))
+    })
+}
+
+/// Archive-qualified path of `member` inside `archive`.
+pub(crate) fn member_path(archive: &Path, member: &str) -> PathBuf {
+    PathBuf::from(format!("{}{}{}", archive.display(), ARCHIVE_SEP, member))
+}
+
+/// A listing failure as an `io::Error` of the matching kind.
+fn listing_error(e: ScanError) -> io::Error {
+    io::Error::new(e.kind().unwrap_or(io::ErrorKind::InvalidData), e)
+}
+
+/// The members of one archive, listed once, to open any number of them
+/// without listing the archive again.
+///
+/// # Example
+/// ```ignore
+/// let index = ArchiveIndex::new(&StdFs, Path::new("/d/plates.zip"))?;
+/// for member in index.members() {
+///     let mut reader = index.open(&StdFs, &member.name)?;
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ArchiveIndex {
+    path: PathBuf,
+    members: Vec<ArchiveMember>,
+    by_name: HashMap<String, usize>,
+}
+
+impl ArchiveIndex {
+    /// List the archive at `path` (see [`list_archive`]).
+    pub fn new(fs: &dyn Fs, path: &Path) -> Result<Self, ScanError> {
+        let members = list_archive(fs, path)?;
+        let by_name = members.iter().enumerate().map(|(i, m)| (m.name.clone(), i)).collect();
+        Ok(ArchiveIndex { path: path.to_path_buf(), members, by_name })
+    }
+
+    /// Path of the archive file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// File members, in archive order.
+    #[must_use]
+    pub fn members(&self) -> &[ArchiveMember] {
+        &self.members
+    }
+
+    /// Member called `name` (its path inside the archive).
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&ArchiveMember> {
+        self.by_name.get(name).map(|&i| &self.members[i])
+    }
+
+    /// Open member `name` for reading. Compressed members are decompressed into
+    /// memory. `NotFound` if the archive has no such member.
+    pub fn open(&self, fs: &dyn Fs, name: &str) -> io::Result<Box<dyn Read + Send>> {
+        let path = member_path(&self.path, name);
+        let member = self.get(name).ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("{}: no such archive member", path.display())))?;
+        let in_member = |e: io::Error| io::Error::new(e.kind(), format!("{}: {}", path.display(), e));
+        let mut reader = fs.open(&self.path)?;
+        match member.storage {
+            Storage::Raw { offset } => {
+                reader.seek(SeekFrom::Start(offset))?;
+                Ok(Box::new(reader.take(member.size)))
+            }
+            Storage::Deflated { offset, compressed, crc } => {
+                reader.seek(SeekFrom::Start(offset))?;
+                Ok(Box::new(io::Cursor::new(zip::inflate(reader.take(compressed), member, crc).map_err(in_member)?)))
+            }
+            Storage::Zip => Ok(Box::new(io::Cursor::new(zip::read(reader, member).map_err(in_member)?))),
+        }
+    }
+}
+
+/// Opens paths like [`open_path`], but lists each archive only the first time
+/// one of its members is opened, so reading every frame of an archived
+/// sequence costs one listing. Can be shared between threads.
+#[derive(Debug)]
+pub struct PathOpener<'a> {
+    fs: &'a dyn Fs,
+    archives: Mutex<HashMap<PathBuf, Result<Arc<ArchiveIndex>, ScanError>>>,
+}
+
+impl<'a> PathOpener<'a> {
+    #[must_use]
+    pub fn new(fs: &'a dyn Fs) -> Self {
+        PathOpener { fs, archives: Mutex::new(HashMap::new()) }
+    }
+
+    /// Index of the archive at `path`, listed on first use. A failed listing
+    /// is kept too: the archive isn't read again.
+    pub fn index(&self, path: &Path) -> Result<Arc<ArchiveIndex>, ScanError> {
+        let mut archives = self.archives.lock().unwrap_or_else(|e| e.into_inner());
+        archives.entry(path.to_path_buf()).or_insert_with(|| ArchiveIndex::new(self.fs, path).map(Arc::new)).clone()
+    }
+
+    /// Open a plain path or an `archive.zip!/member` path for reading.
+    pub fn open<P: AsRef<Path>>(&self, path: P) -> io::Result<Box<dyn Read + Send>> {
+        let path = path.as_ref();
+        match split_archive_path(path) {
+            Some((archive, name)) => self.index(&archive).map_err(listing_error)?.open(self.fs, &name),
+            None => Ok(Box::new(self.fs.open(path)?)),
+        }
+    }
+
+    /// Size in bytes of a plain path (following links) or an archive member.
+    pub fn size<P: AsRef<Path>>(&self, path: P) -> io::Result<u64> {
+        let path = path.as_ref();
+        let Some((archive, name)) = split_archive_path(path) else {
+            return Ok(self.fs.metadata(path)?.size);
+        };
+        let index = self.index(&archive).map_err(listing_error)?;
+        let member = index.get(&name).ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("{}: no such archive member", path.display())))?;
+        Ok(member.size)
+    }
+}
+
+/// Open a file for reading: a plain path, or an archive member addressed as
+/// `archive.zip!/member`. Compressed zip members are decompressed into memory.
+/// Lists the archive on every call; see [`PathOpener`] to open many members.
+pub fn open_path<P: AsRef<Path>>(fs: &dyn Fs, path: P) -> io::Result<Box<dyn Read + Send>> {
+    PathOpener::new(fs).open(path)
+}
+
+impl Seq {
+    /// Open a present frame for reading, from disk or from inside an archive
+    /// (see [`open_path`]). `NotFound` if the frame is not in `indices`.
+    #[allow(dead_code)] // Public API
+    pub fn open_frame(&self, frame: i64) -> io::Result<Box<dyn Read + Send>> {
+        self.open_frame_in(&StdFs, frame)
+    }
+
+    /// [`open_frame`](Self::open_frame) on another filesystem.
+    #[allow(dead_code)] // Public API
+    pub fn open_frame_in(&self, fs: &dyn Fs, frame: i64) -> io::Result<Box<dyn Read + Send>> {
+        self.open_frame_with(&PathOpener::new(fs), frame)
+    }
+
+    /// [`open_frame`](Self::open_frame) through `opener`, which lists the
+    /// sequence's archive once for all the frames it opens.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let opener = PathOpener::new(&StdFs);
+    /// for frame in seq.indices.iter() {
+    ///     let mut reader = seq.open_frame_with(&opener, *frame)?;
+    /// }
+    /// ```
+    #[allow(dead_code)] // Public API
+    pub fn open_frame_with(&self, opener: &PathOpener, frame: i64) -> io::Result<Box<dyn Read + Send>> {
+        let path = self.get_file(frame).ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("frame {} not in {}", frame, self.pattern())))?;
+        opener.open(path)
+    }
+}
+
+#[cfg(test)]
+mod tests;
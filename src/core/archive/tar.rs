@@ -0,0 +1,33 @@
+//! Tar members through the `tar` crate: ustar, GNU long names and pax extended
+//! headers. Uncompressed only.
+
+use super::{check_extent, epoch_secs, ArchiveMember, Storage};
+use crate::core::vfs::ReadSeek;
+use ::tar::Archive;
+
+/// List the regular-file members of a tar archive of `len` bytes (directories
+/// and links are skipped).
+pub(super) fn list(r: Box<dyn ReadSeek>, len: u64) -> Result<Vec<ArchiveMember>, String> {
+    let mut archive = Archive::new(r);
+    let mut members = Vec::new();
+    for entry in archive.entries_with_seek().map_err(|e| e.to_string())? {
+        let mut entry = entry.map_err(|e| format!("not a tar archive or corrupt ({})", e))?;
+        if !entry.header().entry_type().is_file() && !entry.header().entry_type().is_contiguous() {
+            continue; // directories, links, devices, global pax headers
+        }
+        let name = String::from_utf8_lossy(&entry.path_bytes()).trim_start_matches("./").trim_start_matches('/').to_string();
+        if name.is_empty() || name.ends_with('/') {
+            continue;
+        }
+        let (offset, size) = (entry.raw_file_position(), entry.size());
+        check_extent(&name, offset, size, len)?;
+
+        // pax mtime may carry a fraction: whole seconds are enough here
+        let pax_mtime = entry.pax_extensions().ok().flatten().and_then(|mut exts| {
+            exts.find_map(|ext| ext.ok().filter(|ext| ext.key() == Ok("mtime")).and_then(|ext| ext.value().ok()?.split('.').next()?.parse::<i64>().ok()))
+        });
+        let mtime = pax_mtime.or_else(|| entry.header().mtime().ok().and_then(|s| i64::try_from(s).ok())).map(epoch_secs);
+        members.push(ArchiveMember { name, size, mtime, storage: Storage::Raw { offset } });
+    }
+    Ok(members)
+}
@@ -0,0 +1,222 @@
+use super::*;
+use crate::core::{get_seqs_with, scan_files_with, ScanOptions};
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("src/core/archive/fixtures").join(name)
+}
+
+fn read_all(mut r: Box<dyn Read + Send>) -> Vec<u8> {
+    let mut buf = Vec::new();
+    r.read_to_end(&mut buf).expect("read member");
+    buf
+}
+
+#[test]
+fn test_zip_members() {
+    let members = list_archive(&StdFs, &fixture("plates.zip")).unwrap();
+    let names: Vec<&str> = members.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(names, vec!["A001/plate.0001.dpx", "A001/plate.0002.dpx", "A001/plate.0003.dpx", "A001/plate.0005.dpx", "README.txt", "docs/lorem.txt"]);
+    assert_eq!(members[0].size, 600);
+    // 2024-03-01 10:00:00, DOS time taken as UTC
+    assert_eq!(members[0].mtime, Some(epoch_secs(1_709_287_200)));
+
+    // Deflated (1) and stored (2) members read back identically framed
+    let zip = fixture("plates.zip");
+    assert_eq!(read_all(open_path(&StdFs, member_path(&zip, "A001/plate.0001.dpx")).unwrap()), b"DPX frame 1 ".repeat(50));
+    assert_eq!(read_all(open_path(&StdFs, member_path(&zip, "A001/plate.0002.dpx")).unwrap()), b"DPX frame 2 ".repeat(50));
+    // Dynamic Huffman blocks; the zip reader checks the CRC
+    let lorem = read_all(open_path(&StdFs, member_path(&zip, "docs/lorem.txt")).unwrap());
+    assert_eq!((lorem.len() as u64, lorem.iter().filter(|&&b| b == b'\n').count()), (members[5].size, 600));
+
+    let missing = open_path(&StdFs, member_path(&zip, "A001/plate.0004.dpx")).err().unwrap();
+    assert_eq!(missing.kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn test_tar_members() {
+    let members = list_archive(&StdFs, &fixture("plates.tar")).unwrap();
+    let names: Vec<&str> = members.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(names[..3], ["B002/bg.0001.exr", "B002/bg.0002.exr", "B002/bg.0003.exr"], "./ stripped, dir skipped");
+    assert_eq!(members.len(), 5, "symlink skipped");
+    assert!(names[3].len() > 100 && names[3].ends_with("/fg.0010.exr"), "GNU long name");
+    assert_eq!((members[2].size, members[2].mtime), (12, Some(epoch_secs(1_709_287_200))));
+
+    let pax = list_archive(&StdFs, &fixture("pax.tar")).unwrap();
+    assert_eq!(pax.len(), 2);
+    assert!(pax[0].name.starts_with("C003/üü") && pax[0].name.ends_with("/mp.0001.tif"));
+
+    let tar = fixture("plates.tar");
+    assert_eq!(read_all(open_path(&StdFs, member_path(&tar, "B002/bg.0003.exr")).unwrap()), b"EXR3EXR3EXR3");
+}
+
+/// Stored zip with one 100-byte member, whose central directory sizes can be patched.
+fn small_zip() -> Vec<u8> {
+    let mut w = ::zip::ZipWriter::new(io::Cursor::new(Vec::new()));
+    w.start_file("a.0001.dpx", ::zip::write::SimpleFileOptions::default().compression_method(::zip::CompressionMethod::Stored)).unwrap();
+    io::Write::write_all(&mut w, &[7u8; 100]).unwrap();
+    w.finish().unwrap().into_inner()
+}
+
+/// Tar header block for `name` claiming `size` bytes of type `kind`.
+fn tar_header(name: &str, size: u64, kind: ::tar::EntryType) -> Vec<u8> {
+    let mut h = ::tar::Header::new_gnu();
+    h.set_path(name).unwrap();
+    h.set_size(size);
+    h.set_entry_type(kind);
+    h.set_cksum();
+    h.as_bytes().to_vec()
+}
+
+#[test]
+fn test_corrupt_archives() {
+    let tmp = tempfile::tempdir().unwrap();
+    let zip = std::fs::read(fixture("plates.zip")).unwrap();
+    let tar = std::fs::read(fixture("plates.tar")).unwrap();
+
+    // Central directory entry sizes (compressed at +20, uncompressed at +24) lying about a stored member
+    let mut lying_zip = small_zip();
+    let cd = lying_zip.windows(4).position(|w| w == b"PK\x01\x02").unwrap();
+    lying_zip[cd + 20..cd + 28].copy_from_slice(&[0xfe, 0xff, 0xff, 0x7f, 0xfe, 0xff, 0xff, 0x7f]);
+    // End record claiming 0xfffe entries in a 4 GiB central directory
+    let mut huge_cd = vec![0u8; 22];
+    huge_cd[..4].copy_from_slice(b"PK\x05\x06");
+    huge_cd[8..16].copy_from_slice(&[0xfe, 0xff, 0xfe, 0xff, 0xfe, 0xff, 0xff, 0xff]);
+    let mut huge_member = tar_header("big.0001.exr", 1 << 40, ::tar::EntryType::Regular);
+    huge_member.extend([0u8; 512]);
+    let mut huge_name = tar_header("././@LongLink", 1 << 40, ::tar::EntryType::GNULongName);
+    huge_name.extend(b"long/name".repeat(100));
+
+    let cases: [(&str, &[u8]); 9] = [
+        ("empty.zip", b""),
+        ("short.zip", b"PK\x05"),
+        ("text.tar", &[b'x'; 2000]),
+        ("cut.zip", &zip[..zip.len() / 2]),
+        ("cut.tar", &tar[..1000]),
+        ("lying.zip", &lying_zip),
+        ("huge_cd.zip", &huge_cd),
+        ("huge_member.tar", &huge_member),
+        ("huge_name.tar", &huge_name),
+    ];
+    for (name, bytes) in cases {
+        let path = tmp.path().join(name);
+        std::fs::write(&path, bytes).unwrap();
+        let err = list_archive(&StdFs, &path).expect_err(name);
+        assert_eq!(err.path(), Some(path.as_path()), "{}", name);
+    }
+    assert!(list_archive(&StdFs, &tmp.path().join("huge_member.tar")).unwrap_err().to_string().contains("past the end of the archive"));
+
+    // An empty file is an empty tar (end of archive at the first header)
+    std::fs::write(tmp.path().join("empty.tar"), b"").unwrap();
+    assert!(list_archive(&StdFs, &tmp.path().join("empty.tar")).unwrap().is_empty());
+    std::fs::write(tmp.path().join("ok.zip"), small_zip()).unwrap();
+    assert_eq!(read_all(open_path(&StdFs, member_path(&tmp.path().join("ok.zip"), "a.0001.dpx")).unwrap()), [7u8; 100]);
+
+    // None of them takes the scan down
+    let opts = ScanOptions { archives: true, ..Default::default() };
+    let files = scan_files_with(&[tmp.path()], true, &[], &opts).unwrap();
+    assert!(files.iter().any(|f| f.ends_with("ok.zip!/a.0001.dpx")));
+}
+
+/// [`StdFs`] counting the files it opens.
+#[derive(Debug, Default)]
+struct CountingFs(std::sync::atomic::AtomicUsize);
+
+impl Fs for CountingFs {
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<Result<crate::core::FsEntry, ScanError>>> {
+        StdFs.read_dir(path)
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<crate::core::FsMetadata> {
+        StdFs.metadata(path)
+    }
+
+    fn open(&self, path: &Path) -> io::Result<Box<dyn crate::core::ReadSeek>> {
+        self.0.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        StdFs.open(path)
+    }
+}
+
+#[test]
+fn test_archive_index() {
+    let zip = fixture("plates.zip");
+    let fs = CountingFs::default();
+    let opens = || fs.0.load(std::sync::atomic::Ordering::Relaxed);
+
+    // One listing, then one open per member read
+    let index = ArchiveIndex::new(&fs, &zip).unwrap();
+    assert_eq!(index.members().len(), 6);
+    assert_eq!(index.get("A001/plate.0002.dpx").map(|m| m.size), Some(600));
+    assert_eq!(read_all(index.open(&fs, "A001/plate.0001.dpx").unwrap()), b"DPX frame 1 ".repeat(50));
+    assert_eq!(index.open(&fs, "A001/plate.0004.dpx").err().map(|e| e.kind()), Some(io::ErrorKind::NotFound));
+    assert_eq!(opens(), 2);
+
+    // The opener lists each archive once, however many frames are read
+    let seq = get_seqs_with(fixture(""), true, None, 2, &ScanOptions { archives: true, ..Default::default() })
+        .unwrap()
+        .into_iter()
+        .find(|s| s.pattern().contains("plates.zip"))
+        .expect("zip sequence");
+    let opener = PathOpener::new(&fs);
+    for &frame in &seq.indices {
+        assert_eq!(read_all(seq.open_frame_with(&opener, frame).unwrap()), format!("DPX frame {frame} ").repeat(50).as_bytes());
+    }
+    assert_eq!(opens(), 2 + 1 + seq.indices.len());
+    assert!(opener.index(&fixture("nope.zip")).is_err());
+}
+
+#[test]
+fn test_deflated_member_crc() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut w = ::zip::ZipWriter::new(io::Cursor::new(Vec::new()));
+    w.start_file("a.0001.dpx", ::zip::write::SimpleFileOptions::default().compression_method(::zip::CompressionMethod::Deflated)).unwrap();
+    io::Write::write_all(&mut w, &[7u8; 1000]).unwrap();
+    let mut bytes = w.finish().unwrap().into_inner();
+    let path = tmp.path().join("crc.zip");
+    std::fs::write(&path, &bytes).unwrap();
+    assert_eq!(read_all(open_path(&StdFs, member_path(&path, "a.0001.dpx")).unwrap()), [7u8; 1000]);
+
+    // Central directory CRC (at +16) no longer matching the data
+    let cd = bytes.windows(4).position(|w| w == b"PK\x01\x02").unwrap();
+    bytes[cd + 16] ^= 0xff;
+    std::fs::write(&path, &bytes).unwrap();
+    let err = open_path(&StdFs, member_path(&path, "a.0001.dpx")).err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert!(err.to_string().contains("CRC mismatch"), "{err}");
+}
+
+#[test]
+fn test_split_archive_path() {
+    assert_eq!(split_archive_path("/d/plates.zip!/A001/p.0001.dpx"), Some((PathBuf::from("/d/plates.zip"), "A001/p.0001.dpx".to_string())));
+    assert_eq!(split_archive_path("/d/wow!/x.TAR!/p.0001.dpx"), Some((PathBuf::from("/d/wow!/x.TAR"), "p.0001.dpx".to_string())));
+    assert_eq!(split_archive_path("/d/wow!/p.0001.dpx"), None);
+    assert_eq!(dos_time(::zip::DateTime::try_from_msdos(0x5861, 0x5000).unwrap()), Some(epoch_secs(1_709_287_200)));
+}
+
+#[test]
+fn test_scan_into_archives() {
+    let tmp = tempfile::tempdir().unwrap();
+    for name in ["plates.zip", "plates.tar"] {
+        std::fs::copy(fixture(name), tmp.path().join(name)).unwrap();
+    }
+    std::fs::write(tmp.path().join("broken.zip"), b"not a zip").unwrap();
+
+    let plain = get_seqs_with(tmp.path(), true, None, 2, &ScanOptions::default()).unwrap();
+    assert!(plain.is_empty());
+
+    let opts = ScanOptions { archives: true, with_metadata: true, ..Default::default() };
+    let mut seqs = get_seqs_with(tmp.path(), true, None, 2, &opts).unwrap();
+    seqs.sort_by(|a, b| a.pattern().cmp(b.pattern()));
+    let zip_seq = seqs.iter().find(|s| s.pattern().contains("plates.zip")).expect("zip sequence");
+    assert_eq!(zip_seq.pattern(), member_path(&tmp.path().join("plates.zip"), "A001/plate.####.dpx").to_string_lossy());
+    assert_eq!(zip_seq.missed, vec![4]);
+    assert_eq!(zip_seq.total_bytes(), 4 * 600);
+    assert_eq!(read_all(zip_seq.open_frame(3).unwrap()), b"DPX frame 3 ".repeat(50));
+    assert_eq!(zip_seq.open_frame(4).err().map(|e| e.kind()), Some(io::ErrorKind::NotFound));
+    assert!(seqs.iter().any(|s| s.pattern().ends_with("plates.tar!/B002/bg.####.exr")));
+
+    let masked = get_seqs_with(tmp.path(), true, Some("*.exr"), 2, &opts).unwrap();
+    assert_eq!(masked.len(), 2, "bg and the long-named fg");
+
+    let files = scan_files_with(&[tmp.path()], true, &["dpx", "zip"], &opts).unwrap();
+    assert_eq!(files.len(), 4 + 2, "members plus both zip files themselves");
+}
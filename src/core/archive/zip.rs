@@ -0,0 +1,79 @@
+//! Zip members through the `zip` crate (stored and deflated, zip64 included).
+
+use super::{check_extent, dos_time, epoch_secs, ArchiveMember, Storage};
+use crate::core::vfs::ReadSeek;
+use ::zip::result::ZipError;
+use ::zip::{CompressionMethod, ExtraField, ZipArchive};
+use flate2::read::DeflateDecoder;
+use std::io::{self, Read};
+
+/// `ZipError` as an `io::Error` of the matching kind.
+fn io_error(e: ZipError) -> io::Error {
+    match e {
+        ZipError::Io(e) => e,
+        ZipError::FileNotFound => io::Error::new(io::ErrorKind::NotFound, "no such archive member"),
+        ZipError::UnsupportedArchive(_) | ZipError::CompressionMethodNotSupported(_) | ZipError::InvalidPassword => io::Error::new(io::ErrorKind::Unsupported, e.to_string()),
+        e => io::Error::new(io::ErrorKind::InvalidData, e.to_string()),
+    }
+}
+
+/// List the file members of a zip archive of `len` bytes (directories are skipped).
+pub(super) fn list(r: Box<dyn ReadSeek>, len: u64) -> Result<Vec<ArchiveMember>, String> {
+    let mut zip = ZipArchive::new(r).map_err(|e| e.to_string())?;
+    let mut members = Vec::with_capacity(zip.len());
+    for i in 0..zip.len() {
+        // Raw: reads the local header for the data offset, decompresses nothing
+        let file = zip.by_index_raw(i).map_err(|e| e.to_string())?;
+        if file.is_dir() {
+            continue;
+        }
+        let name = file.name().to_string();
+        let offset = file.data_start().ok_or_else(|| format!("member {}: no data offset", name))?;
+        check_extent(&name, offset, file.compressed_size(), len)?;
+
+        // Extended timestamp (UTC) wins over the DOS time
+        let unix = file.extra_data_fields().find_map(|f| match f {
+            ExtraField::ExtendedTimestamp(ts) => ts.mod_time(),
+            _ => None,
+        });
+        let mtime = unix.map(|t| epoch_secs(i64::from(t as i32))).or_else(|| file.last_modified().and_then(dos_time));
+        let storage = match file.compression() {
+            _ if file.encrypted() => Storage::Zip,
+            CompressionMethod::Stored => Storage::Raw { offset },
+            CompressionMethod::Deflated => Storage::Deflated { offset, compressed: file.compressed_size(), crc: file.crc32() },
+            _ => Storage::Zip,
+        };
+        members.push(ArchiveMember { name, size: file.size(), mtime, storage });
+    }
+    Ok(members)
+}
+
+/// Inflate the `compressed` raw deflate bytes of `member` into memory, checking
+/// its size and CRC; output past the declared size is refused rather than buffered.
+pub(super) fn inflate(compressed: impl Read, member: &ArchiveMember, crc: u32) -> io::Result<Vec<u8>> {
+    let mut data = Vec::new();
+    DeflateDecoder::new(compressed).take(member.size.saturating_add(1)).read_to_end(&mut data)?;
+    if data.len() as u64 != member.size {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("member {} inflates to {} bytes, not its declared {}", member.name, data.len(), member.size)));
+    }
+    let mut sum = flate2::Crc::new();
+    sum.update(&data);
+    if sum.sum() != crc {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("member {}: CRC mismatch", member.name)));
+    }
+    Ok(data)
+}
+
+/// Decompress `member` through the zip reader, for the methods [`inflate`]
+/// doesn't cover (it fails on encrypted and unsupported ones). Output past the
+/// declared size is refused rather than buffered.
+pub(super) fn read(r: Box<dyn ReadSeek>, member: &ArchiveMember) -> io::Result<Vec<u8>> {
+    let mut zip = ZipArchive::new(r).map_err(io_error)?;
+    let file = zip.by_name(&member.name).map_err(io_error)?;
+    let mut data = Vec::new();
+    file.take(member.size.saturating_add(1)).read_to_end(&mut data)?;
+    if data.len() as u64 > member.size {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("member {} is larger than its declared {} bytes", member.name, member.size)));
+    }
+    Ok(data)
+}
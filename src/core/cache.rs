@@ -0,0 +1,277 @@
+//! Persistent per-root scan cache keyed by folder mtime.
+//!
+//! With [`ScanOptions::cache_dir`] set, [`get_seqs_with`](super::get_seqs_with)
+//! stores every folder's grouped sequences together with the folder's mtime in
+//! one JSON file per root, and on the next scan regroups only folders whose
+//! mtime changed. Folders are still discovered by walking the tree; what the
+//! cache saves is listing, parsing and grouping every file.
+//!
+//! A folder's mtime changes when entries are created, removed or renamed in it,
+//! not when an existing file is rewritten in place. Per-frame metadata
+//! (`with_metadata`) in a cached folder can therefore be stale; call
+//! [`ScanCache::invalidate`] (or [`Scanner::invalidate_cache`](super::Scanner::invalidate_cache))
+//! after in-place edits.
+//!
+//! Robustness:
+//! - The file carries [`CACHE_VERSION`] and the scan settings that affect
+//!   grouping; a mismatch discards the whole cache.
+//! - An unreadable or corrupt file is logged and treated as empty; the next
+//!   save replaces it. Saves go through a temp file + rename, so a crash never
+//!   leaves a half-written cache behind.
+//! - Folders modified within [`RACY_WINDOW`] of the scan are stored without an
+//!   mtime, since a change in the same timestamp tick would go unnoticed.
+
+use super::error::ScanError;
+use super::scan::{LinkPaths, ScanOptions};
+use super::seq::Seq;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Cache file format version. Bump whenever [`Seq`], the grouping settings
+/// the cache is keyed on or the file layout change.
+pub const CACHE_VERSION: u32 = 2;
+
+/// Folders modified this close to the scan are not trusted to be unchanged later.
+pub const RACY_WINDOW: Duration = Duration::from_secs(2);
+
+/// Scan settings that change what a folder groups into.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct CacheKey {
+    mask: Option<String>,
+    min_len: usize,
+    follow_links: bool,
+    link_paths: LinkPaths,
+    with_metadata: bool,
+    #[serde(default)]
+    archives: bool,
+}
+
+impl CacheKey {
+    fn new(mask: Option<&str>, min_len: usize, opts: &ScanOptions) -> Self {
+        CacheKey {
+            mask: mask.map(str::to_string),
+            min_len,
+            follow_links: opts.follow_links,
+            link_paths: opts.link_paths,
+            with_metadata: opts.with_metadata,
+            archives: opts.archives,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedFolder {
+    /// Folder mtime when it was grouped; `None` = always regroup
+    mtime: Option<SystemTime>,
+    seqs: Vec<Seq>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheData {
+    version: u32,
+    root: String,
+    key: Option<CacheKey>,
+    folders: BTreeMap<String, CachedFolder>,
+}
+
+/// Scan cache of one root, stored in `cache_dir` (see the module docs).
+#[derive(Debug, Clone)]
+pub struct ScanCache {
+    file: PathBuf,
+    data: CacheData,
+}
+
+/// Stable 64-bit FNV-1a, for cache file names that survive toolchain upgrades.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |h, &b| (h ^ b as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+fn path_key(path: &Path) -> String {
+    path.to_string_lossy().into_owned()
+}
+
+impl ScanCache {
+    /// Cache file used for `root` inside `cache_dir`.
+    #[must_use]
+    pub fn file_for(cache_dir: &Path, root: &Path) -> PathBuf {
+        cache_dir.join(format!("scanseq-{:016x}.json", fnv1a(path_key(root).as_bytes())))
+    }
+
+    /// Load the cache of `root`. Never fails: a missing, corrupt, foreign or
+    /// outdated file yields an empty cache (logged), to be overwritten on save.
+    #[must_use]
+    pub fn open(cache_dir: &Path, root: &Path) -> Self {
+        let file = Self::file_for(cache_dir, root);
+        let root = path_key(root);
+        let empty = CacheData { version: CACHE_VERSION, root: root.clone(), key: None, folders: BTreeMap::new() };
+
+        let data = match std::fs::read(&file) {
+            Err(e) => {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    warn!("Scan cache {} unreadable, starting empty: {}", file.display(), e);
+                }
+                empty
+            }
+            Ok(bytes) => match serde_json::from_slice::<CacheData>(&bytes) {
+                Ok(data) if data.version != CACHE_VERSION => {
+                    debug!("Scan cache {} has version {}, expected {}; discarding", file.display(), data.version, CACHE_VERSION);
+                    empty
+                }
+                Ok(data) if data.root != root => {
+                    warn!("Scan cache {} belongs to {}, not {}; discarding", file.display(), data.root, root);
+                    empty
+                }
+                Ok(data) => data,
+                Err(e) => {
+                    warn!("Scan cache {} is corrupt, starting empty: {}", file.display(), e);
+                    empty
+                }
+            },
+        };
+        ScanCache { file, data }
+    }
+
+    /// Path of the cache file.
+    #[must_use]
+    #[allow(dead_code)] // Public API
+    pub fn file(&self) -> &Path {
+        &self.file
+    }
+
+    /// Number of cached folders.
+    #[must_use]
+    #[allow(dead_code)] // Public API
+    pub fn len(&self) -> usize {
+        self.data.folders.len()
+    }
+
+    /// True if no folder is cached.
+    #[must_use]
+    #[allow(dead_code)] // Public API
+    pub fn is_empty(&self) -> bool {
+        self.data.folders.is_empty()
+    }
+
+    /// Drop every cached folder at or below `path`, so the next scan regroups
+    /// them. Returns the number of folders dropped.
+    pub fn invalidate<P: AsRef<Path>>(&mut self, path: P) -> usize {
+        let path = path.as_ref();
+        let before = self.data.folders.len();
+        self.data.folders.retain(|folder, _| !Path::new(folder).starts_with(path));
+        before - self.data.folders.len()
+    }
+
+    /// Drop all cached folders.
+    #[allow(dead_code)] // Public API
+    pub fn clear(&mut self) {
+        self.data.folders.clear();
+    }
+
+    /// Write the cache atomically (temp file + rename), creating `cache_dir` if needed.
+    pub fn save(&self) -> Result<(), ScanError> {
+        if let Some(dir) = self.file.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| ScanError::io(dir, e))?;
+        }
+        let json = serde_json::to_vec(&self.data).map_err(|e| ScanError::io(&self.file, e.into()))?;
+        let tmp = self.file.with_extension(format!("json.{}.tmp", std::process::id()));
+        std::fs::write(&tmp, json).map_err(|e| ScanError::io(&tmp, e))?;
+        std::fs::rename(&tmp, &self.file).map_err(|e| {
+            let _ = std::fs::remove_file(&tmp);
+            ScanError::io(&self.file, e)
+        })
+    }
+
+    /// Reset the cache if it was built with different grouping settings.
+    pub(crate) fn check_key(&mut self, mask: Option<&str>, min_len: usize, opts: &ScanOptions) {
+        let key = CacheKey::new(mask, min_len, opts);
+        if self.data.key.as_ref() != Some(&key) {
+            if self.data.key.is_some() {
+                debug!("Scan cache {}: settings changed, discarding", self.file.display());
+            }
+            self.data.folders.clear();
+            self.data.key = Some(key);
+        }
+    }
+
+    /// Cached sequences of `folder`, if its current mtime matches the cached one.
+    pub(crate) fn get(&self, folder: &Path, mtime: Option<SystemTime>) -> Option<&[Seq]> {
+        let cached = self.data.folders.get(&path_key(folder))?;
+        match (cached.mtime, mtime) {
+            (Some(a), Some(b)) if a == b => Some(&cached.seqs),
+            _ => None,
+        }
+    }
+
+    /// Replace the cached folder set with this scan's (folders no longer found are dropped).
+    pub(crate) fn replace(&mut self, folders: impl IntoIterator<Item = (PathBuf, Option<SystemTime>, Vec<Seq>)>, scan_start: SystemTime) {
+        let racy = scan_start.checked_sub(RACY_WINDOW).unwrap_or(SystemTime::UNIX_EPOCH);
+        self.data.folders = folders
+            .into_iter()
+            .map(|(folder, mtime, seqs)| (path_key(&folder), CachedFolder { mtime: mtime.filter(|&t| t < racy), seqs }))
+            .collect();
+    }
+
+    /// All cached sequences, in folder order.
+    pub(crate) fn into_seqs(self) -> Vec<Seq> {
+        self.data.folders.into_values().flat_map(|f| f.seqs).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corrupt_or_foreign_files_open_empty() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = Path::new("/renders");
+        let file = ScanCache::file_for(dir.path(), root);
+
+        std::fs::write(&file, b"{ not json").expect("write");
+        assert!(ScanCache::open(dir.path(), root).is_empty());
+
+        let other = CacheData { version: CACHE_VERSION, root: "/elsewhere".into(), key: None, folders: BTreeMap::from([("/elsewhere/a".to_string(), CachedFolder { mtime: None, seqs: Vec::new() })]) };
+        std::fs::write(&file, serde_json::to_vec(&other).expect("json")).expect("write");
+        assert!(ScanCache::open(dir.path(), root).is_empty());
+
+        let old = CacheData { version: 0, root: "/renders".into(), ..other };
+        std::fs::write(&file, serde_json::to_vec(&old).expect("json")).expect("write");
+        assert!(ScanCache::open(dir.path(), root).is_empty());
+
+        // Version 1 keys had no `archives`: such a cache must not pass for any scan
+        let key = CacheKey::new(None, 2, &ScanOptions::default());
+        let v1 = CacheData { version: 1, key: Some(key), folders: BTreeMap::from([("/renders".to_string(), CachedFolder { mtime: None, seqs: Vec::new() })]), ..old };
+        let json = serde_json::to_string(&v1).expect("json").replace(",\"archives\":false", "");
+        assert!(!json.contains("archives"));
+        std::fs::write(&file, json).expect("write");
+        assert!(ScanCache::open(dir.path(), root).is_empty());
+    }
+
+    #[test]
+    fn invalidate_drops_subtree_only() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let mut cache = ScanCache::open(dir.path(), Path::new("/r"));
+        let folders = ["/r", "/r/a", "/r/a/b", "/r/ab"].map(|f| (PathBuf::from(f), None, Vec::new()));
+        cache.replace(folders, SystemTime::now());
+        assert_eq!(cache.invalidate("/r/a"), 2);
+        assert_eq!(cache.len(), 2);
+
+        cache.save().expect("save");
+        assert_eq!(ScanCache::open(dir.path(), Path::new("/r")).len(), 2);
+    }
+
+    #[test]
+    fn racy_folders_are_not_trusted() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let mut cache = ScanCache::open(dir.path(), Path::new("/r"));
+        let now = SystemTime::now();
+        let old = now - Duration::from_secs(60);
+        cache.replace([(PathBuf::from("/r/old"), Some(old), Vec::new()), (PathBuf::from("/r/new"), Some(now), Vec::new())], now);
+        assert!(cache.get(Path::new("/r/old"), Some(old)).is_some());
+        assert!(cache.get(Path::new("/r/new"), Some(now)).is_none());
+        assert!(cache.get(Path::new("/r/old"), Some(now)).is_none());
+    }
+}
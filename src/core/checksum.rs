@@ -0,0 +1,627 @@
+//! Per-frame checksum manifests, for archive and vendor deliveries.
+//!
+//! - [`Seq::checksum`]: digest every present frame in parallel into a
+//!   [`ChecksumManifest`] (pattern, frame set, per-frame size and digest).
+//! - [`Seq::verify`] / [`ChecksumManifest::verify`]: compare frames on disk
+//!   with a manifest, reporting missing, changed and extra frames.
+//! - [`Seq::held_frames`] / [`duplicate_frames`]: runs of byte-identical
+//!   frames (editorial holds, stuck renders) and frames shared between
+//!   sequences, hashing only frames whose size matches a candidate.
+//!
+//! Manifests are JSON files; a manifest written next to its sequence refers to
+//! it by file name, so the folder can be moved or delivered as a whole.
+//!
+//! Frames are read through a [`PathOpener`], so sequences found inside zip and
+//! tar archives are hashed like any other, with one listing per archive.
+
+use super::archive::{open_path, PathOpener};
+use super::error::ScanError;
+use super::ops::{frames_on_disk, OpError};
+use super::seq::{format_frame, format_frame_ranges, format_frame_span, Seq};
+use super::vfs::StdFs;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use xxhash_rust::xxh3::Xxh3;
+
+/// Manifest file format version. Bump whenever [`ChecksumManifest`] changes.
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// Digest algorithm of a manifest.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChecksumAlgo {
+    /// XXH3 64-bit: fast, for catching corruption and incomplete transfers.
+    #[default]
+    Xxh3,
+    /// SHA-256: slower, for deliveries that need a cryptographic digest.
+    Sha256,
+}
+
+impl FromStr for ChecksumAlgo {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "xxh3" | "xxhash" | "xxh" => Ok(ChecksumAlgo::Xxh3),
+            "sha256" | "sha-256" => Ok(ChecksumAlgo::Sha256),
+            _ => Err(format!("unknown checksum algorithm '{}' (expected xxh3 or sha256)", s)),
+        }
+    }
+}
+
+impl fmt::Display for ChecksumAlgo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ChecksumAlgo::Xxh3 => "xxh3",
+            ChecksumAlgo::Sha256 => "sha256",
+        })
+    }
+}
+
+impl ChecksumAlgo {
+    /// Lowercase hex digest of the file at `path`, which may be an archive
+    /// member (`plates.zip!/A001/plate.0001.dpx`, see [`open_path`]).
+    pub fn digest_file(self, path: &Path) -> io::Result<String> {
+        self.digest_reader(open_path(&StdFs, path)?)
+    }
+
+    /// Lowercase hex digest of everything `reader` yields, read in 1 MiB chunks.
+    pub fn digest_reader(self, mut reader: impl Read) -> io::Result<String> {
+        let mut buf = vec![0u8; 1 << 20];
+        let mut hasher = Hasher::new(self);
+        loop {
+            let n = reader.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+        }
+        Ok(hasher.finish())
+    }
+}
+
+enum Hasher {
+    Xxh3(Box<Xxh3>),
+    Sha256(Sha256),
+}
+
+impl Hasher {
+    fn new(algo: ChecksumAlgo) -> Self {
+        match algo {
+            ChecksumAlgo::Xxh3 => Hasher::Xxh3(Box::default()),
+            ChecksumAlgo::Sha256 => Hasher::Sha256(Sha256::new()),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Xxh3(h) => h.update(data),
+            Hasher::Sha256(h) => h.update(data),
+        }
+    }
+
+    fn finish(self) -> String {
+        match self {
+            Hasher::Xxh3(h) => format!("{:016x}", h.digest()),
+            Hasher::Sha256(h) => h.finalize().iter().map(|b| format!("{:02x}", b)).collect(),
+        }
+    }
+}
+
+/// Size and digest of one frame.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrameDigest {
+    pub size: u64,
+    pub digest: String,
+}
+
+/// Checksums of a sequence: what [`Seq::checksum`] computes and
+/// [`verify`](Self::verify) checks against.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChecksumManifest {
+    /// [`MANIFEST_VERSION`] of the file.
+    pub version: u32,
+    /// Sequence pattern (`/delivery/plate.####.dpx`).
+    pub pattern: String,
+    /// Frame padding of the pattern (0 = unpadded).
+    pub padding: usize,
+    pub algo: ChecksumAlgo,
+    /// Frame set, as ranges (`1-240,250`).
+    pub frames: String,
+    /// Digest of every frame, by frame number.
+    pub digests: BTreeMap<i64, FrameDigest>,
+}
+
+impl ChecksumManifest {
+    /// Digest `frames` of `pattern` in parallel. Fails on the first frame
+    /// that can't be read: a manifest with holes is no proof of anything.
+    /// Sizes come from `known_size` when it has them, else from the disk.
+    pub(crate) fn create(pattern: &str, padding: usize, frames: &[i64], algo: ChecksumAlgo, known_size: impl Fn(i64) -> Option<u64> + Sync) -> Result<Self, ScanError> {
+        let opener = PathOpener::new(&StdFs);
+        let digests = frames
+            .par_iter()
+            .map(|&frame| {
+                let path = PathBuf::from(format_frame(pattern, padding, frame));
+                let size = known_size(frame).map_or_else(|| opener.size(&path), Ok).map_err(|e| ScanError::io(&path, e))?;
+                let digest = opener.open(&path).and_then(|r| algo.digest_reader(r)).map_err(|e| ScanError::io(&path, e))?;
+                Ok((frame, FrameDigest { size, digest }))
+            })
+            .collect::<Result<BTreeMap<_, _>, ScanError>>()?;
+        Ok(ChecksumManifest { version: MANIFEST_VERSION, pattern: pattern.to_string(), padding, algo, frames: format_frame_ranges(frames), digests })
+    }
+
+    /// Read a manifest file. A pattern stored by file name is resolved
+    /// against the manifest's folder.
+    #[allow(dead_code)] // Public API
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self, ScanError> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).map_err(|e| ScanError::io(path, e))?;
+        let mut manifest: ChecksumManifest = serde_json::from_slice(&bytes).map_err(|e| ScanError::parse(e.to_string()).at_line(e.line()).with_path(path))?;
+        if manifest.version != MANIFEST_VERSION {
+            return Err(ScanError::parse(format!("manifest version {}, expected {}", manifest.version, MANIFEST_VERSION)).with_path(path));
+        }
+        let frames: Vec<i64> = manifest.digests.keys().copied().collect();
+        if format_frame_ranges(&frames) != manifest.frames {
+            return Err(ScanError::parse(format!("frame set {} doesn't match the digests ({})", manifest.frames, format_frame_ranges(&frames))).with_path(path));
+        }
+        if Path::new(&manifest.pattern).is_relative() {
+            if let Some(dir) = path.parent() {
+                manifest.pattern = dir.join(&manifest.pattern).to_string_lossy().into_owned();
+            }
+        }
+        Ok(manifest)
+    }
+
+    /// Write the manifest as pretty JSON, atomically (temp file + rename). A
+    /// sequence in the manifest's own folder is stored by file name.
+    #[allow(dead_code)] // Public API
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), ScanError> {
+        let path = path.as_ref();
+        let mut manifest = self.clone();
+        let pattern = Path::new(&self.pattern);
+        let dir_of = |p: &Path| p.parent().filter(|d| !d.as_os_str().is_empty()).map_or_else(|| fs::canonicalize("."), fs::canonicalize).ok();
+        if let Some(name) = pattern.file_name().filter(|_| dir_of(pattern).is_some() && dir_of(pattern) == dir_of(path)) {
+            manifest.pattern = name.to_string_lossy().into_owned();
+        }
+        let json = serde_json::to_vec_pretty(&manifest).map_err(|e| ScanError::io(path, e.into()))?;
+        let tmp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+        fs::write(&tmp, json).map_err(|e| ScanError::io(&tmp, e))?;
+        fs::rename(&tmp, path).map_err(|e| {
+            let _ = fs::remove_file(&tmp);
+            ScanError::io(path, e)
+        })
+    }
+
+    /// Check the frames of [`pattern`](Self::pattern) on disk against the
+    /// manifest. A folder that no longer exists has every frame missing.
+    #[allow(dead_code)] // Public API
+    pub fn verify(&self) -> Result<VerifyReport, ScanError> {
+        let present: Vec<i64> = match frames_on_disk(&self.pattern, self.padding) {
+            Ok(frames) => frames.into_iter().collect(),
+            Err(OpError::Io { source, .. }) if source.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(OpError::Io { path, source }) => return Err(ScanError::Io { path: Some(path), source }),
+            Err(e) => return Err(ScanError::parse(e.to_string())),
+        };
+        Ok(self.verify_frames(&self.pattern, self.padding, &present, |_| None))
+    }
+
+    /// Compare `present` frames of `pattern` with the manifest. Frames whose
+    /// size already differs are not read; sizes come from `known_size` when
+    /// it has them, else from the disk.
+    pub(crate) fn verify_frames(&self, pattern: &str, padding: usize, present: &[i64], known_size: impl Fn(i64) -> Option<u64> + Sync) -> VerifyReport {
+        let opener = PathOpener::new(&StdFs);
+        let missing = self.digests.keys().copied().filter(|f| present.binary_search(f).is_err()).collect();
+        let extra = present.iter().copied().filter(|f| !self.digests.contains_key(f)).collect();
+        let checked: Vec<(i64, Result<bool, ()>)> = present
+            .par_iter()
+            .filter_map(|&frame| Some((frame, self.digests.get(&frame)?)))
+            .map(|(frame, expected)| {
+                let path = PathBuf::from(format_frame(pattern, padding, frame));
+                let size = known_size(frame).map_or_else(|| opener.size(&path), Ok);
+                let same = size.and_then(|size| if size == expected.size { Ok(self.algo.digest_reader(opener.open(&path)?)? == expected.digest) } else { Ok(false) });
+                (frame, same.map_err(|e| log::warn!("{}: {}", path.display(), e)))
+            })
+            .collect();
+        let pick = |want: Result<bool, ()>| checked.iter().filter(|(_, r)| *r == want).map(|&(f, _)| f).collect();
+        VerifyReport { pattern: pattern.to_string(), verified: pick(Ok(true)), changed: pick(Ok(false)), unreadable: pick(Err(())), missing, extra }
+    }
+}
+
+/// Outcome of checking a sequence against a [`ChecksumManifest`]. Frame
+/// lists are ascending.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct VerifyReport {
+    /// The sequence checked.
+    pub pattern: String,
+    /// Frames that match their digest.
+    pub verified: Vec<i64>,
+    /// In the manifest, not on disk.
+    pub missing: Vec<i64>,
+    /// Size or digest differs from the manifest.
+    pub changed: Vec<i64>,
+    /// On disk, not in the manifest.
+    pub extra: Vec<i64>,
+    /// In the manifest and on disk, but could not be read.
+    pub unreadable: Vec<i64>,
+}
+
+impl VerifyReport {
+    /// True if every frame of the manifest is there, unchanged, and nothing else is.
+    #[must_use]
+    #[allow(dead_code)] // Public API
+    pub fn is_ok(&self) -> bool {
+        self.missing.is_empty() && self.changed.is_empty() && self.extra.is_empty() && self.unreadable.is_empty()
+    }
+}
+
+impl fmt::Display for VerifyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} verified", self.pattern, self.verified.len())?;
+        for (what, frames) in [("missing", &self.missing), ("changed", &self.changed), ("extra", &self.extra), ("unreadable", &self.unreadable)] {
+            if !frames.is_empty() {
+                write!(f, ", {} {} ({})", frames.len(), what, format_frame_ranges(frames))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Options for [`Seq::held_frames`] and [`duplicate_frames`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DuplicateOptions {
+    /// Digest used to compare frames.
+    pub algo: ChecksumAlgo,
+    /// Only read frames whose size matches a frame they could be identical
+    /// to (a neighbour, or a frame of another sequence). Sizes come from the
+    /// scan metadata when there is some, else from the disk.
+    pub size_prefilter: bool,
+    /// Frames smaller than this are never reported (the default, 1, leaves
+    /// out empty frames, which [`Seq::suspect_frames`] reports already).
+    pub min_size: u64,
+}
+
+impl Default for DuplicateOptions {
+    fn default() -> Self {
+        DuplicateOptions { algo: ChecksumAlgo::Xxh3, size_prefilter: true, min_size: 1 }
+    }
+}
+
+/// A run of consecutive, byte-identical frames found by [`Seq::held_frames`].
+/// Missing frames don't break a run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HeldRun {
+    /// First frame of the run (the one that is held).
+    pub start: i64,
+    /// Last frame of the run.
+    pub end: i64,
+    /// Present frames in the run, at least 2.
+    pub count: usize,
+    /// Size of each frame in bytes.
+    pub size: u64,
+    pub digest: String,
+}
+
+impl fmt::Display for HeldRun {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "frames {}: {} identical frames ({} bytes)", format_frame_span(self.start, self.end), self.count, self.size)
+    }
+}
+
+/// Frames with the same content in more than one sequence, found by
+/// [`duplicate_frames`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DuplicateGroup {
+    /// Size of each frame in bytes.
+    pub size: u64,
+    pub digest: String,
+    /// The frames of each sequence with this content, in the order the
+    /// sequences were given; at least two sequences.
+    pub members: Vec<DuplicateMember>,
+}
+
+/// The frames of one sequence in a [`DuplicateGroup`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DuplicateMember {
+    pub pattern: String,
+    /// Ascending frame numbers.
+    pub frames: Vec<i64>,
+}
+
+impl fmt::Display for DuplicateGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, member) in self.members.iter().enumerate() {
+            let sep = if i == 0 { "" } else { " = " };
+            write!(f, "{}{} {}", sep, member.pattern, format_frame_ranges(&member.frames))?;
+        }
+        write!(f, " ({} bytes)", self.size)
+    }
+}
+
+/// Size of every present frame of `seq` (aligned with `indices`), from the
+/// scan metadata or the disk. `None` for frames smaller than `min_size`,
+/// broken links and frames that can't be read (with a warning).
+fn frame_sizes(seq: &Seq, opener: &PathOpener, min_size: u64) -> Vec<Option<u64>> {
+    seq.indices
+        .par_iter()
+        .map(|&frame| {
+            if seq.broken.contains(&frame) {
+                return None;
+            }
+            let size = match seq.frame_info(frame) {
+                Some(info) => info.size,
+                None => {
+                    let path = format_frame(seq.pattern(), seq.padding, frame);
+                    opener.size(&path).map_err(|e| log::warn!("{}: {}", path, e)).ok()?
+                }
+            };
+            Some(size).filter(|&size| size >= min_size)
+        })
+        .collect()
+}
+
+/// Digest `frames` of `seq` in parallel. Frames that can't be read are left
+/// out, with a warning.
+fn digest_frames(seq: &Seq, opener: &PathOpener, frames: &[i64], algo: ChecksumAlgo) -> HashMap<i64, String> {
+    frames
+        .par_iter()
+        .filter_map(|&frame| {
+            let path = PathBuf::from(format_frame(seq.pattern(), seq.padding, frame));
+            let digest = opener.open(&path).and_then(|r| algo.digest_reader(r)).map_err(|e| log::warn!("{}: {}", path.display(), e)).ok()?;
+            Some((frame, digest))
+        })
+        .collect()
+}
+
+/// Frames with the same content in different sequences of `seqs`, largest
+/// frames first. A sequence's own repeats are listed along with the match
+/// (a hold in one sequence against a single frame of another), but content
+/// found in only one sequence is not reported: see [`Seq::held_frames`].
+///
+/// # Example
+/// ```ignore
+/// for group in duplicate_frames(&seqs, &DuplicateOptions::default()) {
+///     println!("{}", group); // comp.####.exr 1010-1012 = plate.####.exr 1001 (8294400 bytes)
+/// }
+/// ```
+#[allow(dead_code)] // Public API
+pub fn duplicate_frames(seqs: &[Seq], opts: &DuplicateOptions) -> Vec<DuplicateGroup> {
+    let opener = PathOpener::new(&StdFs);
+    let sizes: Vec<Vec<(i64, u64)>> = seqs
+        .iter()
+        .map(|seq| seq.indices.iter().zip(frame_sizes(seq, &opener, opts.min_size)).filter_map(|(&frame, size)| Some((frame, size?))).collect())
+        .collect();
+    // Number of sequences each size appears in
+    let mut spread: HashMap<u64, usize> = HashMap::new();
+    for seq_sizes in &sizes {
+        let mut distinct: Vec<u64> = seq_sizes.iter().map(|&(_, size)| size).collect();
+        distinct.sort_unstable();
+        distinct.dedup();
+        for size in distinct {
+            *spread.entry(size).or_default() += 1;
+        }
+    }
+
+    // (size, digest) -> frames of each sequence, by index into `seqs`
+    let mut found: BTreeMap<(u64, String), BTreeMap<usize, Vec<i64>>> = BTreeMap::new();
+    for (i, (seq, seq_sizes)) in seqs.iter().zip(&sizes).enumerate() {
+        let size_of: HashMap<i64, u64> = seq_sizes.iter().copied().collect();
+        let candidates: Vec<i64> = seq_sizes.iter().filter(|&&(_, size)| !opts.size_prefilter || spread[&size] > 1).map(|&(frame, _)| frame).collect();
+        for (frame, digest) in digest_frames(seq, &opener, &candidates, opts.algo) {
+            found.entry((size_of[&frame], digest)).or_default().entry(i).or_default().push(frame);
+        }
+    }
+
+    let mut groups: Vec<DuplicateGroup> = found
+        .into_iter()
+        .filter(|(_, by_seq)| by_seq.len() > 1)
+        .map(|((size, digest), by_seq)| {
+            let members = by_seq
+                .into_iter()
+                .map(|(i, mut frames)| {
+                    frames.sort_unstable();
+                    DuplicateMember { pattern: seqs[i].pattern().to_string(), frames }
+                })
+                .collect();
+            DuplicateGroup { size, digest, members }
+        })
+        .collect();
+    groups.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.members[0].pattern.cmp(&b.members[0].pattern)).then(a.members[0].frames.cmp(&b.members[0].frames)));
+    groups
+}
+
+impl Seq {
+    /// Digest every present frame with `algo`, in parallel.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let manifest = seq.checksum(ChecksumAlgo::Sha256)?;
+    /// manifest.write("/delivery/plate.sha256.json")?;
+    /// // at the other end:
+    /// let report = ChecksumManifest::read("/ingest/plate.sha256.json")?.verify()?;
+    /// ```
+    #[allow(dead_code)] // Public API
+    pub fn checksum(&self, algo: ChecksumAlgo) -> Result<ChecksumManifest, ScanError> {
+        ChecksumManifest::create(self.pattern(), self.padding, &self.indices, algo, |frame| self.frame_info(frame).map(|info| info.size))
+    }
+
+    /// Check this sequence's frames against `manifest`, which may have been
+    /// made for the same frames elsewhere (before a copy or a delivery).
+    #[must_use]
+    #[allow(dead_code)] // Public API
+    pub fn verify(&self, manifest: &ChecksumManifest) -> VerifyReport {
+        manifest.verify_frames(self.pattern(), self.padding, &self.indices, |frame| self.frame_info(frame).map(|info| info.size))
+    }
+
+
+    /// Runs of consecutive present frames with the same content: an
+    /// editorial hold, or a render that got stuck on one frame. Only
+    /// neighbours of the same size are read (with
+    /// [`size_prefilter`](DuplicateOptions::size_prefilter)), so a sequence
+    /// without holds costs a metadata lookup per frame.
+    ///
+    /// # Example
+    /// ```ignore
+    /// for run in seq.held_frames(&DuplicateOptions::default()) {
+    ///     println!("{}", run); // frames 1040-1047: 8 identical frames (8294400 bytes)
+    /// }
+    /// ```
+    #[must_use]
+    #[allow(dead_code)] // Public API
+    pub fn held_frames(&self, opts: &DuplicateOptions) -> Vec<HeldRun> {
+        let opener = PathOpener::new(&StdFs);
+        let sizes = frame_sizes(self, &opener, opts.min_size);
+        let mut candidates = Vec::new();
+        for (i, pair) in sizes.windows(2).enumerate() {
+            if let [Some(a), Some(b)] = pair {
+                if !opts.size_prefilter || a == b {
+                    candidates.extend([self.indices[i], self.indices[i + 1]]);
+                }
+            }
+        }
+        candidates.dedup();
+        let digests = digest_frames(self, &opener, &candidates, opts.algo);
+
+        let mut runs: Vec<HeldRun> = Vec::new();
+        for (i, pair) in self.indices.windows(2).enumerate() {
+            let (Some(a), Some(b)) = (digests.get(&pair[0]), digests.get(&pair[1])) else { continue };
+            if a != b || sizes[i] != sizes[i + 1] {
+                continue;
+            }
+            match runs.last_mut().filter(|run| run.end == pair[0]) {
+                Some(run) => {
+                    run.end = pair[1];
+                    run.count += 1;
+                }
+                None => runs.push(HeldRun { start: pair[0], end: pair[1], count: 2, size: sizes[i].unwrap_or_default(), digest: a.clone() }),
+            }
+        }
+        runs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::get_seqs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_digests() {
+        let tmp = tempdir().expect("tempdir");
+        let path = tmp.path().join("abc");
+        fs::write(&path, "abc").unwrap();
+        assert_eq!(ChecksumAlgo::Sha256.digest_file(&path).unwrap(), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(ChecksumAlgo::Xxh3.digest_file(&path).unwrap(), format!("{:016x}", xxhash_rust::xxh3::xxh3_64(b"abc")));
+        assert_eq!("SHA-256".parse::<ChecksumAlgo>(), Ok(ChecksumAlgo::Sha256));
+        assert!("md5".parse::<ChecksumAlgo>().is_err());
+    }
+
+    #[test]
+    fn test_checksum_and_verify() {
+        let tmp = tempdir().expect("tempdir");
+        for f in 1..=4 {
+            fs::write(tmp.path().join(format!("p.{f:04}.exr")), f.to_string()).unwrap();
+        }
+        let seq = get_seqs(tmp.path(), false, None, 2).unwrap().remove(0);
+        let manifest = seq.checksum(ChecksumAlgo::Xxh3).unwrap();
+        assert_eq!(manifest.frames, "1-4");
+        assert_eq!(manifest.digests[&2].size, 1);
+
+        // Written next to the frames, the manifest names them by file name
+        let file = tmp.path().join("p.xxh3.json");
+        manifest.write(&file).unwrap();
+        let text = fs::read_to_string(&file).unwrap();
+        assert!(text.contains("\"pattern\": \"p.####.exr\""), "{text}");
+        let read = ChecksumManifest::read(&file).unwrap();
+        assert_eq!(read, manifest);
+        assert!(read.verify().unwrap().is_ok());
+
+        // Same size, other content; a frame gone; a new one; a differently padded name is no frame
+        fs::write(tmp.path().join("p.0002.exr"), "x").unwrap();
+        fs::remove_file(tmp.path().join("p.0003.exr")).unwrap();
+        fs::write(tmp.path().join("p.0007.exr"), "7").unwrap();
+        fs::write(tmp.path().join("p.00008.exr"), "8").unwrap();
+        let report = read.verify().unwrap();
+        assert_eq!((report.verified, report.changed, report.missing, report.extra), (vec![1, 4], vec![2], vec![3], vec![7]));
+
+        let copy = tmp.path().join("copy");
+        fs::create_dir(&copy).unwrap();
+        fs::copy(tmp.path().join("p.0001.exr"), copy.join("p.0001.exr")).unwrap();
+        fs::copy(tmp.path().join("p.0004.exr"), copy.join("p.0004.exr")).unwrap();
+        let copied = get_seqs(&copy, false, None, 2).unwrap().remove(0);
+        let report = copied.verify(&manifest);
+        assert_eq!((report.verified, report.missing), (vec![1, 4], vec![2, 3]));
+
+        fs::write(&file, text.replace("\"1-4\"", "\"1-5\"")).unwrap();
+        assert!(matches!(ChecksumManifest::read(&file), Err(ScanError::Parse { .. })));
+    }
+
+    #[test]
+    fn test_held_and_duplicate_frames() {
+        let tmp = tempdir().expect("tempdir");
+        let plate = tmp.path().join("plate");
+        let comp = tmp.path().join("comp");
+        fs::create_dir(&plate).unwrap();
+        fs::create_dir(&comp).unwrap();
+        // Held 2-4 (across missing frame 3), same size but different at 5, empty 7-8, held again 9-10
+        let frames = [(1, "aaaa"), (2, "bbbb"), (4, "bbbb"), (5, "bbbc"), (6, "bbbc"), (7, ""), (8, ""), (9, "zz"), (10, "zz")];
+        for (f, content) in frames {
+            fs::write(plate.join(format!("p.{f:04}.exr")), content).unwrap();
+        }
+        let seq = get_seqs(&plate, false, None, 2).unwrap().remove(0);
+        let opts = DuplicateOptions::default();
+        let runs = seq.held_frames(&opts);
+        let spans: Vec<(i64, i64, usize)> = runs.iter().map(|r| (r.start, r.end, r.count)).collect();
+        assert_eq!(spans, vec![(2, 4, 2), (5, 6, 2), (9, 10, 2)]);
+        assert_eq!(runs[0].to_string(), "frames 2-4: 2 identical frames (4 bytes)");
+        assert_eq!(seq.held_frames(&DuplicateOptions { size_prefilter: false, ..opts }), runs);
+        let with_empty = seq.held_frames(&DuplicateOptions { min_size: 0, ..opts });
+        assert_eq!(with_empty.iter().map(|r| (r.start, r.end)).collect::<Vec<_>>(), vec![(2, 4), (5, 6), (7, 8), (9, 10)]);
+
+        fs::write(comp.join("c.0101.exr"), "bbbb").unwrap();
+        fs::write(comp.join("c.0102.exr"), "cccc").unwrap();
+        fs::write(comp.join("c.0103.exr"), "zz").unwrap();
+        let other = get_seqs(&comp, false, None, 2).unwrap().remove(0);
+        let groups = duplicate_frames(&[seq.clone(), other.clone()], &opts);
+        let found: Vec<(u64, Vec<i64>, Vec<i64>)> = groups.iter().map(|g| (g.size, g.members[0].frames.clone(), g.members[1].frames.clone())).collect();
+        assert_eq!(found, vec![(4, vec![2, 4], vec![101]), (2, vec![9, 10], vec![103])]);
+        assert_eq!(groups[1].to_string(), format!("{} 9-10 = {} 103 (2 bytes)", seq.pattern(), other.pattern()));
+        assert_eq!(duplicate_frames(&[seq.clone(), other], &DuplicateOptions { size_prefilter: false, ..opts }), groups);
+        assert!(duplicate_frames(&[seq], &opts).is_empty());
+    }
+
+    #[test]
+    fn test_archive_sequences() {
+        use crate::core::{get_seqs_with, ScanOptions};
+
+        let tmp = tempdir().expect("tempdir");
+        let mut w = ::zip::ZipWriter::new(fs::File::create(tmp.path().join("plates.zip")).unwrap());
+        for (f, content) in [(1, "aaaa"), (2, "bbbb"), (3, "bbbb")] {
+            w.start_file(format!("A001/p.{f:04}.dpx"), ::zip::write::SimpleFileOptions::default()).unwrap();
+            std::io::Write::write_all(&mut w, content.as_bytes()).unwrap();
+        }
+        w.finish().unwrap();
+        let opts = ScanOptions { archives: true, ..Default::default() };
+        let seq = get_seqs_with(tmp.path(), true, None, 2, &opts).unwrap().remove(0);
+        assert!(seq.pattern().contains("plates.zip!/"));
+
+        // Frames are read from inside the archive, sizes taken from its listing
+        let manifest = seq.checksum(ChecksumAlgo::Xxh3).unwrap();
+        assert_eq!(manifest.digests[&2], FrameDigest { size: 4, digest: format!("{:016x}", xxhash_rust::xxh3::xxh3_64(b"bbbb")) });
+        assert_eq!(ChecksumAlgo::Xxh3.digest_file(Path::new(&seq.get_file(1).unwrap())).unwrap(), manifest.digests[&1].digest);
+        assert!(seq.verify(&manifest).is_ok());
+        let runs = seq.held_frames(&DuplicateOptions::default());
+        assert_eq!(runs.iter().map(|r| (r.start, r.end)).collect::<Vec<_>>(), vec![(2, 3)]);
+
+        // Scan metadata sizes count as the frame's size
+        let with_meta = get_seqs_with(tmp.path(), true, None, 2, &ScanOptions { with_metadata: true, ..opts }).unwrap().remove(0);
+        assert_eq!(with_meta.checksum(ChecksumAlgo::Xxh3).unwrap(), manifest);
+    }
+}
@@ -0,0 +1,132 @@
+//! Error and warning types shared by the scan API.
+
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Why a scan, listing or expansion failed.
+///
+/// Match on the variant (or on [`kind`](Self::kind) for filesystem failures)
+/// instead of the message text: a missing root is `Io` with
+/// `io::ErrorKind::NotFound`, an unreadable one `PermissionDenied`.
+/// Sources are shared (`Arc`) so results carrying errors stay `Clone`.
+#[derive(Debug, Clone)]
+pub enum ScanError {
+    /// Filesystem failure, on `path` when known.
+    Io { path: Option<PathBuf>, source: Arc<io::Error> },
+    /// A file mask that does not compile as a glob pattern (after brace expansion).
+    InvalidMask { mask: String, source: Arc<glob::PatternError> },
+    /// [`Seq::expand`](super::Seq::expand) refused a range longer than `max` frames.
+    RangeTooLarge { frames: i64, max: i64 },
+    /// Malformed archive, listing or inventory, at `line` of `path` when known.
+    Parse { path: Option<PathBuf>, line: Option<usize>, message: String },
+    /// The file watcher could not be started or could not watch `path`.
+    Watch { path: Option<PathBuf>, message: String },
+    /// The worker thread pool could not be built.
+    ThreadPool(String),
+}
+
+impl ScanError {
+    /// Filesystem failure on `path`.
+    pub fn io<P: Into<PathBuf>>(path: P, source: io::Error) -> Self {
+        ScanError::Io { path: Some(path.into()), source: Arc::new(source) }
+    }
+
+    /// Parse failure without location; see [`at_line`](Self::at_line) and [`with_path`](Self::with_path).
+    pub(crate) fn parse(message: impl Into<String>) -> Self {
+        ScanError::Parse { path: None, line: None, message: message.into() }
+    }
+
+    /// Set the line of a `Parse` error.
+    pub(crate) fn at_line(self, n: usize) -> Self {
+        match self {
+            ScanError::Parse { path, message, .. } => ScanError::Parse { path, line: Some(n), message },
+            other => other,
+        }
+    }
+
+    /// Fill in the path of an `Io`, `Parse` or `Watch` error that has none yet.
+    pub fn with_path<P: AsRef<Path>>(self, p: P) -> Self {
+        let p = Some(p.as_ref().to_path_buf());
+        match self {
+            ScanError::Io { path: None, source } => ScanError::Io { path: p, source },
+            ScanError::Parse { path: None, line, message } => ScanError::Parse { path: p, line, message },
+            ScanError::Watch { path: None, message } => ScanError::Watch { path: p, message },
+            other => other,
+        }
+    }
+
+    /// Path the error is about, if any.
+    #[must_use]
+    #[allow(dead_code)] // Public API
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            ScanError::Io { path, .. } | ScanError::Parse { path, .. } | ScanError::Watch { path, .. } => path.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// `io::ErrorKind` of a filesystem failure; `None` for the other variants.
+    #[must_use]
+    #[allow(dead_code)] // Public API
+    pub fn kind(&self) -> Option<io::ErrorKind> {
+        match self {
+            ScanError::Io { source, .. } => Some(source.kind()),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ScanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(path) = self.path() {
+            write!(f, "{}: ", path.display())?;
+        }
+        match self {
+            ScanError::Io { source, .. } => write!(f, "{source}"),
+            ScanError::InvalidMask { mask, source } => write!(f, "Invalid mask {mask:?}: {source}"),
+            ScanError::RangeTooLarge { frames, max } => write!(f, "Range too large: {frames} frames (max {max})"),
+            ScanError::Parse { line, message, .. } => match line {
+                Some(n) => write!(f, "line {n}: {message}"),
+                None => write!(f, "{message}"),
+            },
+            ScanError::Watch { message, .. } => write!(f, "{message}"),
+            ScanError::ThreadPool(message) => write!(f, "Failed to create thread pool: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for ScanError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ScanError::Io { source, .. } => Some(source.as_ref()),
+            ScanError::InvalidMask { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Something a scan worked around instead of failing on, reported in
+/// [`ScanResult::warnings`](super::ScanResult::warnings).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanWarning {
+    /// `root` resolves to the same folder as `same_as`, listed before it; scanned once.
+    DuplicateRoot { root: PathBuf, same_as: PathBuf },
+    /// `root` lies inside `covered_by`, whose recursive scan already includes it.
+    NestedRoot { root: PathBuf, covered_by: PathBuf },
+}
+
+impl fmt::Display for ScanWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScanWarning::DuplicateRoot { root, same_as } if root == same_as => write!(f, "{}: listed twice, scanned once", root.display()),
+            ScanWarning::DuplicateRoot { root, same_as } => {
+                write!(f, "{}: same folder as root {}, scanned once", root.display(), same_as.display())
+            }
+            ScanWarning::NestedRoot { root, covered_by } => {
+                write!(f, "{}: inside root {}, already scanned there", root.display(), covered_by.display())
+            }
+        }
+    }
+}
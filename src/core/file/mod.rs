@@ -12,7 +12,42 @@
 //! The mask is key for grouping: files with the same mask belong to the same
 //! sequence family, even if they have different padding (e.g., `img_1` and `img_100`).
 
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// Per-frame file metadata, captured during the directory walk when a scan
+/// runs with `with_metadata` (see [`crate::core::ScanOptions`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrameInfo {
+    /// File size in bytes (of the link target for symlinked frames)
+    pub size: u64,
+    /// Last modification time, if the platform reports one
+    pub mtime: Option<SystemTime>,
+    /// Inode number (Unix only)
+    pub inode: Option<u64>,
+}
+
+impl FrameInfo {
+    /// Capture size, mtime and inode from already-fetched metadata (no extra stat).
+    #[allow(dead_code)] // Public API (scans go through `From<&FsMetadata>`)
+    pub fn from_metadata(m: &std::fs::Metadata) -> Self {
+        #[cfg(unix)]
+        let inode = {
+            use std::os::unix::fs::MetadataExt;
+            Some(m.ino())
+        };
+        #[cfg(not(unix))]
+        let inode = None;
+        FrameInfo { size: m.len(), mtime: m.modified().ok(), inode }
+    }
+}
+
+impl From<&crate::core::FsMetadata> for FrameInfo {
+    fn from(m: &crate::core::FsMetadata) -> Self {
+        FrameInfo { size: m.size, mtime: m.mtime, inode: m.inode }
+    }
+}
 
 /// Parsed file with path components and digit group metadata.
 ///
@@ -36,6 +71,10 @@ pub struct File {
     /// Name with all digit groups replaced by @ (e.g., "shot_@_frame_@")
     /// Used for hash-based grouping - files with same mask are candidates for same sequence
     pub mask: String,
+    /// Dangling symlink (only set by scans that follow links)
+    pub broken: bool,
+    /// Size/mtime/inode (only set by scans with `with_metadata`)
+    pub meta: Option<FrameInfo>,
 }
 
 impl File {
@@ -60,7 +99,7 @@ impl File {
             mask.to_lowercase(),
         );
 
-        Self { fpn, drive, path, name, ext, num_groups, mask }
+        Self { fpn, drive, path, name, ext, num_groups, mask, broken: false, meta: None }
     }
 
     /// Compute signature hash for grouping files into sequence candidates.
@@ -0,0 +1,335 @@
+//! Inventory listings: sequences from object-storage and file listings, without
+//! touching the storage itself.
+//!
+//! Readers turn a listing into [`InventoryEntry`]s (path, size, mtime);
+//! [`group_inventory`] groups them like [`group_paths`](super::group_paths) and
+//! records size and mtime as per-frame metadata, so [`Seq::total_bytes`],
+//! [`Seq::suspect_frames`] and [`Seq::stale_frames`] work on archived data.
+//! Object keys are split on `/`, so key prefixes group like directories.
+//!
+//! Supported formats ([`InventoryFormat`]):
+//! - **S3 inventory CSV** (uncompressed; pipe `.csv.gz` through `zcat`). Keys are
+//!   URL-decoded, delete markers and non-current versions skipped. Paths become
+//!   `s3://bucket/key`, or just `key` without a `Bucket` column.
+//! - **`find -printf '%s %T@ %p\n'`**: size, epoch mtime, path per line.
+//! - **`rclone lsjson -R`**: JSON array of objects; directories are skipped.
+
+use super::error::ScanError;
+use super::file::{File, FrameInfo};
+use super::scan::{group_files, GroupOptions};
+use super::seq::Seq;
+use serde::Deserialize;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+/// Column layout of S3 inventory CSV files when no schema is given. The actual
+/// layout is the `fileSchema` field of the inventory's `manifest.json`.
+pub const S3_DEFAULT_SCHEMA: &str = "Bucket, Key, Size, LastModifiedDate";
+
+/// One file or object from a listing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InventoryEntry {
+    /// Full path or object key (`s3://bucket/key` for S3 inventories with a bucket column)
+    pub path: String,
+    /// Size in bytes
+    pub size: u64,
+    /// Last modification time, if the listing has one
+    pub mtime: Option<SystemTime>,
+}
+
+/// Listing format for [`read_inventory`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InventoryFormat {
+    /// S3 inventory CSV (no header row; columns per [`S3_DEFAULT_SCHEMA`] unless given)
+    S3Csv,
+    /// `find -printf '%s %T@ %p\n'`
+    FindPrintf,
+    /// `rclone lsjson` (use `-R` for a recursive listing)
+    RcloneJson,
+}
+
+impl InventoryFormat {
+    /// Guess the format from a file name: `.csv` is S3, `.json` is rclone,
+    /// anything else is `find` output.
+    #[must_use]
+    #[allow(dead_code)] // Public API
+    pub fn guess<P: AsRef<Path>>(path: P) -> Self {
+        let name = path.as_ref().to_string_lossy().to_lowercase();
+        if name.ends_with(".csv") {
+            InventoryFormat::S3Csv
+        } else if name.ends_with(".json") {
+            InventoryFormat::RcloneJson
+        } else {
+            InventoryFormat::FindPrintf
+        }
+    }
+}
+
+impl FromStr for InventoryFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "s3" | "s3-csv" | "csv" => Ok(InventoryFormat::S3Csv),
+            "find" | "printf" => Ok(InventoryFormat::FindPrintf),
+            "rclone" | "lsjson" | "json" => Ok(InventoryFormat::RcloneJson),
+            _ => Err(format!("unknown inventory format '{}' (expected s3, find or rclone)", s)),
+        }
+    }
+}
+
+/// Read a listing in `format` (S3 with [`S3_DEFAULT_SCHEMA`]).
+#[allow(dead_code)] // Public API
+pub fn read_inventory<R: BufRead>(reader: R, format: InventoryFormat) -> Result<Vec<InventoryEntry>, ScanError> {
+    match format {
+        InventoryFormat::S3Csv => read_s3_inventory(reader, None),
+        InventoryFormat::FindPrintf => read_find_printf(reader),
+        InventoryFormat::RcloneJson => read_rclone_lsjson(reader),
+    }
+}
+
+/// Read a listing file in `format`.
+#[allow(dead_code)] // Public API
+pub fn read_inventory_file<P: AsRef<Path>>(path: P, format: InventoryFormat) -> Result<Vec<InventoryEntry>, ScanError> {
+    let path = path.as_ref();
+    let file = std::fs::File::open(path).map_err(|e| ScanError::io(path, e))?;
+    read_inventory(BufReader::new(file), format).map_err(|e| e.with_path(path))
+}
+
+/// Group listing entries into sequences carrying per-frame size and mtime,
+/// with the same [`GroupOptions`] as [`group_paths`](super::group_paths).
+#[allow(dead_code)] // Public API
+pub fn group_inventory<I: IntoIterator<Item = InventoryEntry>>(entries: I, opts: &GroupOptions) -> Result<Vec<Seq>, ScanError> {
+    let files = entries.into_iter().map(|e| {
+        let mut file = File::new(e.path);
+        file.meta = Some(FrameInfo { size: e.size, mtime: e.mtime, inode: None });
+        file
+    });
+    group_files(files, opts.mask.as_deref(), opts.min_len)
+}
+
+// === S3 inventory CSV ===
+
+/// Read an S3 inventory CSV. `schema` is the manifest's `fileSchema`
+/// (e.g. `"Bucket, Key, VersionId, IsLatest, IsDeleteMarker, Size, LastModifiedDate"`);
+/// `None` = [`S3_DEFAULT_SCHEMA`]. `Key` and `Size` columns are required.
+#[allow(dead_code)] // Public API
+pub fn read_s3_inventory<R: BufRead>(reader: R, schema: Option<&str>) -> Result<Vec<InventoryEntry>, ScanError> {
+    let columns: Vec<String> = schema.unwrap_or(S3_DEFAULT_SCHEMA).split(',').map(|c| c.trim().to_ascii_lowercase()).collect();
+    let col = |name: &str| columns.iter().position(|c| c == name);
+    let key_col = col("key").ok_or_else(|| ScanError::parse("S3 inventory schema has no Key column"))?;
+    let size_col = col("size").ok_or_else(|| ScanError::parse("S3 inventory schema has no Size column"))?;
+    let (bucket_col, mtime_col) = (col("bucket"), col("lastmodifieddate"));
+    let (latest_col, marker_col) = (col("islatest"), col("isdeletemarker"));
+
+    let mut entries = Vec::new();
+    for (n, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| ScanError::Io { path: None, source: e.into() })?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields = split_csv(&line);
+        let field = |i: usize| fields.get(i).map(String::as_str).unwrap_or("");
+        // Only current, live objects
+        if latest_col.is_some_and(|i| field(i) == "false") || marker_col.is_some_and(|i| field(i) == "true") {
+            continue;
+        }
+        let key = percent_decode(field(key_col));
+        if key.is_empty() || key.ends_with('/') {
+            continue;
+        }
+        let size = field(size_col).parse().map_err(|_| ScanError::parse(format!("invalid size '{}'", field(size_col))).at_line(n + 1))?;
+        let mtime = match mtime_col.map(field).filter(|s| !s.is_empty()) {
+            Some(s) => Some(parse_rfc3339(s).ok_or_else(|| ScanError::parse(format!("invalid LastModifiedDate '{}'", s)).at_line(n + 1))?),
+            None => None,
+        };
+        let path = match bucket_col.map(field) {
+            Some(bucket) if !bucket.is_empty() => format!("s3://{}/{}", bucket, key),
+            _ => key,
+        };
+        entries.push(InventoryEntry { path, size, mtime });
+    }
+    Ok(entries)
+}
+
+/// Split one CSV record: comma separated, fields optionally in double quotes
+/// with `""` as an escaped quote.
+fn split_csv(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Decode an S3 inventory key: `%XX` escapes and `+` for space. Invalid escapes
+/// are kept literally.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len() => {
+                match std::str::from_utf8(&bytes[i + 1..i + 3]).ok().and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                    Some(b) => {
+                        out.push(b);
+                        i += 3;
+                        continue;
+                    }
+                    None => out.push(b'%'),
+                }
+            }
+            b'+' => out.push(b' '),
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+// === find -printf ===
+
+/// Read `find -printf '%s %T@ %p\n'` output. The path is everything after the
+/// second space, so paths with spaces are fine.
+#[allow(dead_code)] // Public API
+pub fn read_find_printf<R: BufRead>(reader: R) -> Result<Vec<InventoryEntry>, ScanError> {
+    let mut entries = Vec::new();
+    for (n, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| ScanError::Io { path: None, source: e.into() })?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut parts = line.splitn(3, ' ');
+        let (Some(size), Some(mtime), Some(path)) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(ScanError::parse("expected '<size> <mtime> <path>'").at_line(n + 1));
+        };
+        let size = size.parse().map_err(|_| ScanError::parse(format!("invalid size '{}'", size)).at_line(n + 1))?;
+        let mtime = parse_epoch(mtime).ok_or_else(|| ScanError::parse(format!("invalid mtime '{}'", mtime)).at_line(n + 1))?;
+        entries.push(InventoryEntry { path: path.to_string(), size, mtime: Some(mtime) });
+    }
+    Ok(entries)
+}
+
+/// Epoch seconds with optional fraction, as printed by `%T@` (`1684161600.1234567890`).
+fn parse_epoch(s: &str) -> Option<SystemTime> {
+    let (secs, frac) = s.split_once('.').unwrap_or((s, ""));
+    let secs: u64 = secs.parse().ok()?;
+    SystemTime::UNIX_EPOCH.checked_add(Duration::new(secs, parse_nanos(frac)?))
+}
+
+/// Fractional-second digits to nanoseconds (extra digits beyond 9 are dropped).
+fn parse_nanos(frac: &str) -> Option<u32> {
+    if !frac.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let digits = &frac[..frac.len().min(9)];
+    let value: u32 = if digits.is_empty() { 0 } else { digits.parse().ok()? };
+    Some(value * 10u32.pow(9 - digits.len() as u32))
+}
+
+// === rclone lsjson ===
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RcloneItem {
+    path: String,
+    #[serde(default)]
+    size: i64,
+    #[serde(default)]
+    mod_time: Option<String>,
+    #[serde(default)]
+    is_dir: bool,
+}
+
+/// Read `rclone lsjson` output (a JSON array). Paths are relative to the listed
+/// remote directory; directories are skipped.
+#[allow(dead_code)] // Public API
+pub fn read_rclone_lsjson<R: Read>(reader: R) -> Result<Vec<InventoryEntry>, ScanError> {
+    let items: Vec<RcloneItem> = serde_json::from_reader(reader).map_err(|e| ScanError::parse(format!("invalid rclone lsjson: {}", e)))?;
+    let mut entries = Vec::with_capacity(items.len());
+    for item in items.into_iter().filter(|i| !i.is_dir) {
+        let mtime = match item.mod_time.as_deref() {
+            Some(s) => Some(parse_rfc3339(s).ok_or_else(|| ScanError::parse(format!("{}: invalid ModTime '{}'", item.path, s)))?),
+            None => None,
+        };
+        // rclone reports -1 for unknown sizes
+        entries.push(InventoryEntry { path: item.path, size: item.size.max(0) as u64, mtime });
+    }
+    Ok(entries)
+}
+
+// === Timestamps ===
+
+/// Parse an RFC 3339 / ISO 8601 timestamp: `2023-01-15T10:20:30.123Z` or with an
+/// offset (`+01:00`). A space may replace the `T`. Years must lie in 0..=9999
+/// and offsets within a day.
+fn parse_rfc3339(s: &str) -> Option<SystemTime> {
+    let s = s.trim();
+    let (date, time) = s.split_once(['T', 't', ' '])?;
+
+    let mut ymd = date.splitn(3, '-');
+    let year: i64 = ymd.next()?.parse().ok()?;
+    let month: u32 = ymd.next()?.parse().ok()?;
+    let day: u32 = ymd.next()?.parse().ok()?;
+
+    // Split the zone off the clock time
+    let (clock, offset_secs) = if let Some(clock) = time.strip_suffix(['Z', 'z']) {
+        (clock, 0)
+    } else {
+        let pos = time.rfind(['+', '-'])?;
+        let (clock, zone) = time.split_at(pos);
+        let sign = if zone.starts_with('-') { -1 } else { 1 };
+        let (h, m) = zone[1..].split_once(':').unwrap_or((&zone[1..], "0"));
+        let (h, m) = (h.parse::<i64>().ok()?, m.parse::<i64>().ok()?);
+        if !(0..=23).contains(&h) || !(0..=59).contains(&m) {
+            return None;
+        }
+        (clock, sign * h.checked_mul(3600)?.checked_add(m.checked_mul(60)?)?)
+    };
+    let (hms, frac) = clock.split_once('.').unwrap_or((clock, ""));
+    let mut hms = hms.splitn(3, ':');
+    let hour: i64 = hms.next()?.parse().ok()?;
+    let minute: i64 = hms.next()?.parse().ok()?;
+    let second: i64 = hms.next()?.parse().ok()?;
+    if !(0..=9999).contains(&year) || !(1..=12).contains(&month) || !(1..=31).contains(&day) || !(0..=23).contains(&hour) || !(0..=59).contains(&minute) || !(0..=60).contains(&second) {
+        return None;
+    }
+
+    let clock_secs = hour.checked_mul(3600)?.checked_add(minute.checked_mul(60)?)?.checked_add(second)?;
+    let secs = days_from_civil(year, month, day).checked_mul(86_400)?.checked_add(clock_secs)?.checked_sub(offset_secs)?;
+    let nanos = parse_nanos(frac)?;
+    if secs >= 0 {
+        SystemTime::UNIX_EPOCH.checked_add(Duration::new(secs as u64, nanos))
+    } else {
+        SystemTime::UNIX_EPOCH.checked_sub(Duration::from_secs(secs.unsigned_abs()))?.checked_add(Duration::from_nanos(nanos as u64))
+    }
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date (Howard Hinnant's algorithm).
+pub(crate) fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests;
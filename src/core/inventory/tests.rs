@@ -0,0 +1,137 @@
+use super::*;
+use crate::core::{GroupOptions, StaleRule, SuspectReason, SuspectThresholds};
+use std::io::Cursor;
+
+const S3_CSV: &str = include_str!("fixtures/s3_inventory.csv");
+const S3_VERSIONS_CSV: &str = include_str!("fixtures/s3_inventory_versions.csv");
+const FIND_PRINTF: &str = include_str!("fixtures/find_printf.txt");
+const RCLONE_JSON: &str = include_str!("fixtures/rclone_lsjson.json");
+
+fn epoch(secs: u64) -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+fn sorted(mut seqs: Vec<Seq>) -> Vec<Seq> {
+    seqs.sort_by(|a, b| a.pattern().cmp(b.pattern()));
+    seqs
+}
+
+#[test]
+fn test_s3_inventory_groups_by_prefix() {
+    let schema = "Bucket, Key, Size, LastModifiedDate, StorageClass";
+    let entries = read_s3_inventory(Cursor::new(S3_CSV), Some(schema)).unwrap();
+    assert_eq!(entries.len(), 7, "folder placeholder key is skipped");
+    assert_eq!(entries[0].mtime, Some(epoch(1_709_287_200)));
+
+    let seqs = sorted(group_inventory(entries, &GroupOptions::default()).unwrap());
+    assert_eq!(seqs.len(), 2);
+    assert_eq!(seqs[0].pattern(), "s3://render-archive/shows/abc/sh010/comp/v003/sh010_comp_v003.####.exr");
+    assert_eq!(seqs[0].indices, vec![1001, 1002, 1003, 1004]);
+    assert_eq!(seqs[0].total_bytes(), 3 * 10_485_760);
+    let suspects = seqs[0].suspect_frames(&SuspectThresholds::default());
+    assert_eq!(suspects.len(), 1);
+    assert_eq!((suspects[0].frame, suspects[0].reason), (1003, SuspectReason::Empty));
+
+    // URL-decoded keys: '+' is a space, %2B a literal plus
+    assert_eq!(seqs[1].pattern(), "s3://render-archive/shows/abc/sh020/plates/my plate+v1.####.dpx");
+}
+
+#[test]
+fn test_s3_inventory_versions_and_delete_markers() {
+    let schema = "Bucket, Key, VersionId, IsLatest, IsDeleteMarker, Size, LastModifiedDate";
+    let entries = read_s3_inventory(Cursor::new(S3_VERSIONS_CSV), Some(schema)).unwrap();
+    let paths: Vec<&str> = entries.iter().map(|e| e.path.as_str()).collect();
+    assert_eq!(paths, vec!["s3://bkt/seq/a.0001.exr", "s3://bkt/seq/a.0002.exr"]);
+    assert_eq!(entries[0].size, 100);
+}
+
+#[test]
+fn test_s3_inventory_schema_errors() {
+    assert!(read_s3_inventory(Cursor::new(S3_CSV), Some("Bucket, Size")).is_err());
+    let err = read_s3_inventory(Cursor::new("\"b\",\"k.0001.exr\",\"big\"\n"), Some("Bucket, Key, Size")).unwrap_err();
+    assert!(matches!(err, ScanError::Parse { line: Some(1), .. }), "{err}");
+    assert_eq!(err.to_string(), "line 1: invalid size 'big'");
+}
+
+#[test]
+fn test_find_printf() {
+    let entries = read_find_printf(Cursor::new(FIND_PRINTF)).unwrap();
+    assert_eq!(entries.len(), 7);
+    assert_eq!(entries[1].mtime, Some(epoch(1_709_287_260) + Duration::from_millis(500)));
+    assert_eq!(entries[4].path, "/mnt/archive/sh010/lighting/notes for comp 01.txt");
+
+    let seqs = sorted(group_inventory(entries, &GroupOptions { mask: Some("*.exr".into()), ..Default::default() }).unwrap());
+    assert_eq!(seqs.len(), 1);
+    assert_eq!(seqs[0].missed, vec![4]);
+    assert_eq!(seqs[0].frame_info(5).map(|i| i.size), Some(5_242_880));
+
+    assert!(read_find_printf(Cursor::new("12 nope /x.0001.exr\n")).is_err());
+    assert!(read_find_printf(Cursor::new("12\n")).is_err());
+    // An mtime past what SystemTime holds is a parse error on its line, not a panic
+    let err = read_find_printf(Cursor::new("10 1.0 /x/a.0001.exr\n10 18446744073709551615.0 /x/a.0002.exr\n")).unwrap_err();
+    assert!(matches!(err, ScanError::Parse { line: Some(2), .. }), "{err}");
+    assert!(err.to_string().contains("invalid mtime"), "{err}");
+}
+
+#[test]
+fn test_rclone_lsjson() {
+    let entries = read_rclone_lsjson(Cursor::new(RCLONE_JSON)).unwrap();
+    assert_eq!(entries.len(), 4, "directories are skipped");
+    // 13:00 at +01:00 is 12:00 UTC
+    assert_eq!(entries[0].mtime, Some(epoch(1_709_640_000)));
+    assert_eq!(entries[1].mtime, Some(epoch(1_709_640_010) + Duration::from_nanos(123_456_789)));
+
+    let seqs = group_inventory(entries, &GroupOptions::default()).unwrap();
+    assert_eq!(seqs.len(), 1);
+    assert_eq!(seqs[0].pattern(), "sh030/fx/smoke.####.exr");
+    let suspects = seqs[0].suspect_frames(&SuspectThresholds::default());
+    assert_eq!(suspects.iter().map(|s| s.frame).collect::<Vec<_>>(), vec![103]);
+    // 101-102 carry +01:00 offsets: in UTC they are over an hour older than 103-104
+    assert_eq!(seqs[0].stale_frames(&StaleRule::default()), vec![101, 102]);
+
+    assert!(read_rclone_lsjson(Cursor::new("{\"not\": \"an array\"}")).is_err());
+}
+
+#[test]
+fn test_read_inventory_file() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/core/inventory/fixtures");
+    for (name, count) in [("s3_inventory.csv", 7), ("find_printf.txt", 7), ("rclone_lsjson.json", 4)] {
+        let path = dir.join(name);
+        let format = InventoryFormat::guess(&path);
+        // The fixture has a StorageClass column the default schema ignores
+        assert_eq!(read_inventory_file(&path, format).unwrap().len(), count, "{name}");
+    }
+    let missing = read_inventory_file(dir.join("missing.csv"), InventoryFormat::S3Csv).unwrap_err();
+    assert_eq!((missing.kind(), missing.path()), (Some(std::io::ErrorKind::NotFound), Some(dir.join("missing.csv").as_path())));
+}
+
+#[test]
+fn test_format_from_str() {
+    assert_eq!("s3".parse(), Ok(InventoryFormat::S3Csv));
+    assert_eq!("FIND".parse(), Ok(InventoryFormat::FindPrintf));
+    assert_eq!("lsjson".parse(), Ok(InventoryFormat::RcloneJson));
+    assert!("xml".parse::<InventoryFormat>().is_err());
+}
+
+#[test]
+fn test_parse_rfc3339() {
+    assert_eq!(parse_rfc3339("1970-01-01T00:00:00Z"), Some(SystemTime::UNIX_EPOCH));
+    assert_eq!(parse_rfc3339("2000-03-01 00:00:00Z"), Some(epoch(951_868_800)));
+    assert_eq!(parse_rfc3339("2024-02-29T23:59:59-00:30"), Some(epoch(1_709_251_199 + 1_800)));
+    assert_eq!(parse_rfc3339("1969-12-31T23:59:59Z"), SystemTime::UNIX_EPOCH.checked_sub(Duration::from_secs(1)));
+    assert!(parse_rfc3339("2024-13-01T00:00:00Z").is_none());
+    assert!(parse_rfc3339("yesterday").is_none());
+    // Out-of-range years and offsets are rejected, not overflowed
+    assert!(parse_rfc3339("2020-01-01T00:00:00+9999999999999999:00").is_none());
+    assert!(parse_rfc3339("2020-01-01T00:00:00+24:00").is_none());
+    assert!(parse_rfc3339("2020-01-01T00:00:00-01:60").is_none());
+    assert!(parse_rfc3339("9223372036854775807-01-01T00:00:00Z").is_none());
+    assert!(parse_rfc3339("-1-01-01T00:00:00Z").is_none());
+    assert_eq!(parse_rfc3339("9999-12-31T23:59:59+23:59"), Some(epoch(253_402_300_799 - 86_340)));
+}
+
+#[test]
+fn test_split_csv_and_decode() {
+    assert_eq!(split_csv(r#""a,b","c""d",,e"#), vec!["a,b", "c\"d", "", "e"]);
+    assert_eq!(percent_decode("a%20b+c%2Bd%zz%"), "a b c+d%zz%");
+}
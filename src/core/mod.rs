@@ -6,6 +6,16 @@
 //! - `file`: Parses paths, extracts digit groups, creates masks for grouping
 //! - `seq`: Groups files into sequences using mask-based hashing
 //! - `scan`: Parallel directory traversal and orchestration
+//! - `analyze`: Metadata-based health checks on detected sequences
+//! - `archive`: Sequences inside zip and tar archives (`plates.zip!/A001/...`)
+//! - `cache`: Per-root on-disk scan cache keyed by folder mtime
+//! - `checksum`: Per-frame checksum manifests (xxh3, sha256), their verification, held and duplicate frames
+//! - `error`: `ScanError` and `ScanWarning`, what the scan API reports
+//! - `inventory`: Sequences from S3 inventory / `find` / rclone listings
+//! - `ops`: File operations on whole sequences (copy, move, link, renumber, repad, rename, remove, gap filling) and their journal
+//! - `sort`: Deterministic (optionally natural) ordering of scan output
+//! - `watch`: Incremental updates of a `Scanner` as files change
+//! - `vfs`: The `Fs` trait scans go through (local disk, in-memory, remote)
 //!
 //! # Algorithm
 //! Files are grouped by mask (e.g., `render_@_frame_@.exr`), then sub-grouped
@@ -14,19 +24,50 @@
 //! - Unpadded sequences: `img_1.exr` - `img_100.exr`
 //! - Multi-group names: `shot_01_frame_0001.exr` (anchor=01, frame=0001)
 
+mod analyze;
+mod archive;
+mod cache;
+mod checksum;
+mod error;
 mod file;
+mod inventory;
+pub mod ops;
 mod seq;
 mod scan;
 mod slice;
-
-pub use seq::{Seq, format_frame};
-pub use scan::{get_seqs, scan_files};
+mod sort;
+mod vfs;
+mod watch;
+
+pub use analyze::{StaleRule, SuspectFrame, SuspectReason, SuspectThresholds};
+pub use archive::{list_archive, open_path, split_archive_path, ArchiveIndex, ArchiveKind, ArchiveMember, PathOpener, ARCHIVE_SEP};
+pub use cache::{ScanCache, CACHE_VERSION};
+pub use checksum::{
+    duplicate_frames, ChecksumAlgo, ChecksumManifest, DuplicateGroup, DuplicateMember, DuplicateOptions, FrameDigest, HeldRun, VerifyReport, MANIFEST_VERSION,
+};
+pub use error::{ScanError, ScanWarning};
+pub use file::FrameInfo;
+pub use inventory::{
+    group_inventory, read_find_printf, read_inventory, read_inventory_file, read_rclone_lsjson, read_s3_inventory, InventoryEntry, InventoryFormat,
+    S3_DEFAULT_SCHEMA,
+};
+pub use ops::{
+    FileOp, FillMethod, FillSource, FillStrategy, FrameMap, FrameSelection, Journal, JournalEntry, LinkKind, LinkOptions, NameFields, NameTemplate, OnExists, OpError,
+    OpKind, OpReport, OpStatus, RemoveMode, Renumber, TransferOptions,
+};
+pub use seq::{Seq, format_frame, format_frame_ranges, parse_frame_ranges};
+pub use scan::{get_seqs, get_seqs_result, get_seqs_with, group_paths, scan_files, scan_files_with, GroupOptions, LinkPaths, ScanOptions};
 pub use slice::{FrameSlice, FrameSliceError};
+pub use sort::{natural_cmp, sort_seqs, SortKey};
+pub use vfs::{FileKind, Fs, FsEntry, FsMetadata, MemFs, ReadSeek, StdFs};
+pub use watch::{Watch, WatchEvent, WatchMode, WatchOptions};
 
 use file::File;
+use scan::dedup_roots;
 
 use rayon::prelude::*;
 use std::path::Path;
+use std::sync::Arc;
 use std::time::Instant;
 
 /// Result of a scan operation
@@ -37,8 +78,12 @@ pub struct ScanResult {
     pub seqs: Vec<Seq>,
     /// Scan duration in milliseconds
     pub elapsed_ms: f64,
-    /// Errors encountered during scan
-    pub errors: Vec<String>,
+    /// Errors encountered during scan: failed roots, and folders or entries
+    /// below a root that could not be read
+    pub errors: Vec<ScanError>,
+    /// Problems worked around, e.g. roots skipped because another root's scan
+    /// already covers them
+    pub warnings: Vec<ScanWarning>,
 }
 
 /// Stateful scanner with configuration and results.
@@ -70,6 +115,8 @@ pub struct Scanner {
     pub mask: Option<String>,
     /// Minimum sequence length
     pub min_len: usize,
+    /// Traversal controls (depth window, same file system, symlinks)
+    pub options: ScanOptions,
     /// Scan results
     pub result: ScanResult,
 }
@@ -103,6 +150,7 @@ impl Scanner {
             recursive,
             mask,
             min_len,
+            options: ScanOptions::default(),
             result: ScanResult::default(),
         };
         scanner.rescan();
@@ -129,6 +177,7 @@ impl Scanner {
             recursive: true,
             mask: None,
             min_len: 2,
+            options: ScanOptions::default(),
         }
     }
 
@@ -141,6 +190,7 @@ impl Scanner {
             recursive: true,
             mask: None,
             min_len: 2,
+            options: ScanOptions::default(),
         }
     }
 
@@ -164,8 +214,8 @@ impl Scanner {
         let start = Instant::now();
         let mut result = ScanResult::default();
 
-        match get_seqs(root.as_ref(), recursive, mask, min_len) {
-            Ok(seqs) => result.seqs = seqs,
+        match get_seqs_result(root.as_ref(), recursive, mask, min_len, &ScanOptions::default()) {
+            Ok(scanned) => result = scanned,
             Err(e) => result.errors.push(e),
         }
 
@@ -183,15 +233,27 @@ impl Scanner {
     /// `Some(Seq)` if file is part of a sequence, `None` otherwise
     #[allow(dead_code)] // Public library API
     pub fn from_file<P: AsRef<Path>>(path: P) -> Option<Seq> {
+        Self::from_file_in(&StdFs, path)
+    }
+
+    /// [`from_file`](Self::from_file) on another filesystem.
+    #[allow(dead_code)] // Public library API
+    pub fn from_file_in<P: AsRef<Path>>(fs: &dyn Fs, path: P) -> Option<Seq> {
         let path = path.as_ref();
         let dir = path.parent()?;
 
-        // Read directory (non-recursive, no mask filter)
-        let entries: Vec<std::path::PathBuf> = std::fs::read_dir(dir)
+        // Read directory (non-recursive, no mask filter); symlinks count if they resolve to a file
+        let entries: Vec<std::path::PathBuf> = fs
+            .read_dir(dir)
             .ok()?
-            .filter_map(|e| e.ok())
-            .map(|e| e.path())
-            .filter(|p| p.is_file())
+            .into_iter()
+            .flatten()
+            .filter(|e| match e.kind {
+                FileKind::File => true,
+                FileKind::Symlink => fs.metadata(&e.path).is_ok_and(|m| m.is_file()),
+                _ => false,
+            })
+            .map(|e| e.path)
             .collect();
 
         let target = File::new(path);
@@ -209,6 +271,9 @@ impl Scanner {
 
     /// Scan multiple paths in parallel (static method).
     ///
+    /// Duplicate roots, and roots inside another root of a recursive scan, are
+    /// scanned once and listed in `warnings`.
+    ///
     /// # Arguments
     /// * `roots` - Directory paths to scan
     /// * `recursive` - Scan subdirectories
@@ -217,6 +282,7 @@ impl Scanner {
     ///
     /// # Returns
     /// `ScanResult` with sequences, timing, and errors
+    #[allow(dead_code)] // Public library API
     pub fn get_seqs<P: AsRef<Path> + Sync>(
         roots: &[P],
         recursive: bool,
@@ -224,53 +290,60 @@ impl Scanner {
         min_len: usize,
     ) -> ScanResult {
         let start = Instant::now();
+        let options = ScanOptions::default();
+        let (roots, warnings) = dedup_roots(roots, recursive, &options);
 
         // Scan roots in parallel
         let results: Vec<_> = roots.par_iter().map(|root| {
-            match get_seqs(root.as_ref(), recursive, mask, min_len) {
-                Ok(seqs) => (seqs, None),
-                Err(e) => (Vec::new(), Some(format!("{}: {}", root.as_ref().display(), e))),
-            }
+            get_seqs_result(root, recursive, mask, min_len, &options)
         }).collect();
 
-        let mut result = ScanResult::default();
-        for (seqs, err) in results {
-            result.seqs.extend(seqs);
-            if let Some(e) = err {
-                result.errors.push(e);
+        let mut result = ScanResult { warnings, ..Default::default() };
+        for scanned in results {
+            match scanned {
+                Ok(scanned) => {
+                    result.seqs.extend(scanned.seqs);
+                    result.errors.extend(scanned.errors);
+                }
+                Err(e) => result.errors.push(e),
             }
         }
+        sort_seqs(&mut result.seqs, SortKey::Path, false);
 
         result.elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
         result
     }
 
     /// Re-scan all roots in parallel with current settings.
-    /// Updates `result` with new sequences, timing, and errors.
+    /// Updates `result` with new sequences, timing, and errors; roots covered by
+    /// another root are skipped and listed in `result.warnings`.
     #[allow(dead_code)] // Public library API
     pub fn rescan(&mut self) {
         let start = Instant::now();
+        let (roots, warnings) = dedup_roots(&self.roots, self.recursive, &self.options);
 
         // Scan roots in parallel
-        let results: Vec<_> = self.roots.par_iter().map(|root| {
-            match get_seqs(root, self.recursive, self.mask.as_deref(), self.min_len) {
-                Ok(seqs) => (seqs, None),
-                Err(e) => (Vec::new(), Some(format!("{}: {}", root, e))),
-            }
+        let results: Vec<_> = roots.par_iter().map(|root| {
+            get_seqs_result(root, self.recursive, self.mask.as_deref(), self.min_len, &self.options).map_err(|e| e.with_path(root))
         }).collect();
 
         let mut all_seqs = Vec::new();
         let mut errors = Vec::new();
-        for (seqs, err) in results {
-            all_seqs.extend(seqs);
-            if let Some(e) = err {
-                errors.push(e);
+        for scanned in results {
+            match scanned {
+                Ok(scanned) => {
+                    all_seqs.extend(scanned.seqs);
+                    errors.extend(scanned.errors);
+                }
+                Err(e) => errors.push(e),
             }
         }
+        sort_seqs(&mut all_seqs, self.options.sort, self.options.natural_sort);
 
         self.result = ScanResult {
             seqs: all_seqs,
             errors,
+            warnings,
             elapsed_ms: start.elapsed().as_secs_f64() * 1000.0,
         };
     }
@@ -294,6 +367,27 @@ impl Scanner {
     pub fn iter(&self) -> impl Iterator<Item = &Seq> {
         self.result.seqs.iter()
     }
+
+    /// Drop cached folders at or below `path` from the caches of all roots, so
+    /// the next [`rescan`](Self::rescan) regroups them even if their mtime did
+    /// not change (e.g. after frames were overwritten in place).
+    /// Returns the number of folders dropped; no-op without `options.cache_dir`.
+    #[allow(dead_code)] // Public library API
+    pub fn invalidate_cache<P: AsRef<Path>>(&self, path: P) -> Result<usize, ScanError> {
+        let Some(dir) = &self.options.cache_dir else {
+            return Ok(0);
+        };
+        let mut dropped = 0;
+        for root in &self.roots {
+            let mut cache = ScanCache::open(dir, Path::new(root));
+            let n = cache.invalidate(path.as_ref());
+            if n > 0 {
+                cache.save()?;
+                dropped += n;
+            }
+        }
+        Ok(dropped)
+    }
 }
 
 impl std::fmt::Display for Scanner {
@@ -319,6 +413,7 @@ pub struct ScannerBuilder {
     recursive: bool,
     mask: Option<String>,
     min_len: usize,
+    options: ScanOptions,
 }
 
 impl ScannerBuilder {
@@ -374,10 +469,112 @@ impl ScannerBuilder {
         self
     }
 
+    /// Limit descent like `find -maxdepth`: `1` = only files directly in the roots.
+    /// Implies `recursive(true)` (default: unlimited)
+    #[allow(dead_code)]
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.recursive = true;
+        self.options.max_depth = Some(depth);
+        self
+    }
+
+    /// Skip files shallower than `depth`, like `find -mindepth` (default: 0)
+    #[allow(dead_code)]
+    pub fn min_depth(mut self, depth: usize) -> Self {
+        self.options.min_depth = depth;
+        self
+    }
+
+    /// Stay on the roots' file systems: never descend into other mounted volumes (default: false)
+    #[allow(dead_code)]
+    pub fn same_file_system(mut self, same: bool) -> Self {
+        self.options.same_file_system = same;
+        self
+    }
+
+    /// Follow symlinked directories and files, with inode-based cycle detection.
+    /// Dangling frame links are flagged in [`Seq::broken`] (default: false)
+    #[allow(dead_code)]
+    pub fn follow_links(mut self, follow: bool) -> Self {
+        self.options.follow_links = follow;
+        self
+    }
+
+    /// Report symlinked frames by link path or resolved target (default: [`LinkPaths::Link`])
+    #[allow(dead_code)]
+    pub fn link_paths(mut self, link_paths: LinkPaths) -> Self {
+        self.options.link_paths = link_paths;
+        self
+    }
+
+    /// Record size, mtime and inode per frame during the walk (default: false);
+    /// on the local disk that is one stat per frame (see [`ScanOptions::with_metadata`]).
+    /// Enables [`Seq::frame_info`], [`Seq::total_bytes`], [`Seq::oldest`] and [`Seq::newest`]
+    #[allow(dead_code)]
+    pub fn with_metadata(mut self, with: bool) -> Self {
+        self.options.with_metadata = with;
+        self
+    }
+
+    /// Cache grouped folders in `dir` (one file per root) and regroup only folders
+    /// whose mtime changed on later scans (default: no cache)
+    #[allow(dead_code)]
+    pub fn cache_dir<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        self.options.cache_dir = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Look inside `.zip` / `.tar` files and report their members as
+    /// `archive.zip!/member` paths (default: false)
+    #[allow(dead_code)]
+    pub fn archives(mut self, archives: bool) -> Self {
+        self.options.archives = archives;
+        self
+    }
+
+    /// Scan through `fs` instead of the local disk, e.g. a [`MemFs`] or an
+    /// asset-server client (default: [`StdFs`])
+    #[allow(dead_code)]
+    pub fn fs(mut self, fs: Arc<dyn Fs>) -> Self {
+        self.options.fs = Some(fs);
+        self
+    }
+
+    /// Fail a root's scan on the first unreadable folder or entry below it,
+    /// instead of recording each one in `result.errors` (default: false)
+    #[allow(dead_code)]
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.options.strict = strict;
+        self
+    }
+
+    /// Order of `result.seqs` (default: [`SortKey::Path`])
+    #[allow(dead_code)]
+    pub fn sort(mut self, key: SortKey) -> Self {
+        self.options.sort = key;
+        self
+    }
+
+    /// Compare paths naturally when sorting, `shot2` before `shot10` (default: false)
+    #[allow(dead_code)]
+    pub fn natural_sort(mut self, natural: bool) -> Self {
+        self.options.natural_sort = natural;
+        self
+    }
+
     /// Execute scan and return configured Scanner with results.
     #[allow(dead_code)]
     pub fn scan(self) -> Scanner {
-        Scanner::new(self.roots, self.recursive, self.mask.as_deref(), self.min_len)
+        let mut scanner = Scanner {
+            roots: self.roots,
+            recursive: self.recursive,
+            mask: self.mask,
+            min_len: self.min_len,
+            options: self.options,
+            result: ScanResult::default(),
+        };
+        scanner.rescan();
+        scanner
     }
 
     /// Execute scan and return only the sequences (convenience).
@@ -389,7 +586,7 @@ impl ScannerBuilder {
 
 // === Unified detection entry point (file OR directory) ===
 
-/// Error from [`detect`]. Intentionally minimal; a full typed error set is a later pass.
+/// Error from [`detect`]: an ambiguous directory, or the [`ScanError`] of the scan.
 ///
 /// Lives here so callers can `use scanseq::core::DetectError` alongside [`detect`].
 #[derive(Debug)]
@@ -397,7 +594,7 @@ pub enum DetectError {
     /// Directory holds more than one sequence — caller must disambiguate (e.g. pass a file).
     Ambiguous { count: usize },
     /// Filesystem / scan error.
-    Scan(String),
+    Scan(ScanError),
 }
 
 impl std::fmt::Display for DetectError {
@@ -409,12 +606,19 @@ impl std::fmt::Display for DetectError {
     }
 }
 
-impl std::error::Error for DetectError {}
+impl std::error::Error for DetectError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DetectError::Ambiguous { .. } => None,
+            DetectError::Scan(e) => Some(e),
+        }
+    }
+}
 
 /// Detect the sequence for a FILE or a DIRECTORY.
 /// - file -> the sequence containing it (via [`Scanner::from_file`])
 /// - dir  -> the single sequence if EXACTLY one exists (scanned NON-recursively);
-///           `Err(Ambiguous)` if 2+, `Ok(None)` if none.
+///   `Err(Ambiguous)` if 2+, `Ok(None)` if none.
 ///
 /// No silent "pick longest" — ambiguity is a loud error by design, so callers
 /// (e.g. codec-core's EXR scanner) never quietly load the wrong sequence.
@@ -509,6 +713,21 @@ mod detect_tests {
         assert_eq!(seq.end, 4);
     }
 
+    #[test]
+    fn test_from_file_in_mem_fs() {
+        let mem = MemFs::new();
+        for n in [1, 2, 4] {
+            mem.add_file(format!("/r/shot.{n:04}.exr"), 1);
+        }
+        mem.add_symlink("/r/shot.0005.exr", "/elsewhere/shot.0005.exr");
+        let seq = Scanner::from_file_in(&mem, "/r/shot.0002.exr").expect("sequence");
+        assert_eq!(seq.indices, vec![1, 2, 4], "dangling link is not a frame");
+
+        let seqs = Scanner::path("/r").fs(Arc::new(mem)).into_seqs();
+        assert_eq!(seqs.len(), 1);
+        assert_eq!(seqs[0].missed, vec![3]);
+    }
+
     #[test]
     fn test_frame_of() {
         assert_eq!(frame_of("render_0042.exr"), Some(42));
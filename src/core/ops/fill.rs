@@ -0,0 +1,156 @@
+//! Filling the missing frames of a sequence.
+
+use super::{copy_atomic, link_atomic, run_parallel, FileOp, OnExists, OpError, OpKind, OpReport, OpStatus, TransferOptions};
+use crate::core::seq::{format_frame, Seq};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// What a missing frame is filled with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FillSource {
+    /// The last frame before the gap (a hold).
+    Previous,
+    /// The first frame after the gap.
+    Next,
+    /// Whichever of the two is closer; the previous one on a tie.
+    Nearest,
+    /// This file (a "missing frame" slate), for every gap.
+    Placeholder(PathBuf),
+}
+
+impl FromStr for FillSource {
+    type Err = String;
+
+    /// `previous` (`prev`, `hold`), `next` or `nearest`; placeholders take a path, not a name.
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "previous" | "prev" | "hold" => Ok(FillSource::Previous),
+            "next" => Ok(FillSource::Next),
+            "nearest" => Ok(FillSource::Nearest),
+            _ => Err(format!("unknown fill source '{}' (expected previous, next or nearest)", s)),
+        }
+    }
+}
+
+/// How a missing frame gets its content.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FillMethod {
+    /// A full copy.
+    #[default]
+    Copy,
+    /// A hard link (same file system only, no extra space).
+    Hardlink,
+    /// A symbolic link: relative for frames of the sequence, absolute for a placeholder.
+    Symlink,
+}
+
+impl FromStr for FillMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "copy" => Ok(FillMethod::Copy),
+            "hardlink" | "hard" => Ok(FillMethod::Hardlink),
+            "symlink" | "sym" => Ok(FillMethod::Symlink),
+            _ => Err(format!("unknown fill method '{}' (expected copy, hardlink or symlink)", s)),
+        }
+    }
+}
+
+impl fmt::Display for FillMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FillMethod::Copy => "copy",
+            FillMethod::Hardlink => "hardlink",
+            FillMethod::Symlink => "symlink",
+        })
+    }
+}
+
+/// A [`Seq::fill_gaps`] strategy: where fill frames come from and how they are made.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FillStrategy {
+    pub source: FillSource,
+    pub method: FillMethod,
+}
+
+impl Seq {
+    /// Create every missing frame (`missed`), so players and comp tools see an
+    /// unbroken range: a hold of the previous frame, the next or nearest one,
+    /// or a placeholder file, copied or linked.
+    ///
+    /// Each op of the report names the file a frame was made from; gap files
+    /// that exist by now follow `opts.on_exists`. [`OpReport::undo`] removes
+    /// the created frames. Broken frames (dangling links) are never used as a
+    /// source.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let hold = FillStrategy { source: FillSource::Previous, method: FillMethod::Symlink };
+    /// let report = seq.fill_gaps(&hold, &TransferOptions::default())?;
+    /// // later: report.undo();
+    /// ```
+    #[allow(dead_code)] // Public API
+    pub fn fill_gaps(&self, strategy: &FillStrategy, opts: &TransferOptions) -> Result<OpReport, OpError> {
+        let kind = match strategy.method {
+            FillMethod::Copy => OpKind::Copy,
+            FillMethod::Hardlink => OpKind::Hardlink,
+            FillMethod::Symlink => OpKind::Symlink,
+        };
+        let placeholder = match &strategy.source {
+            FillSource::Placeholder(path) => Some(fs::canonicalize(path).map_err(|e| OpError::io(path, e))?),
+            _ => None,
+        };
+        let sources: Vec<i64> = self.indices.iter().copied().filter(|f| self.broken.binary_search(f).is_err()).collect();
+
+        let mut ops = Vec::with_capacity(self.missed.len());
+        let mut existing = Vec::new();
+        for &frame in &self.missed {
+            let from = match &placeholder {
+                Some(path) => path.clone(),
+                None => {
+                    let at = sources.partition_point(|&f| f < frame);
+                    let (prev, next) = (at.checked_sub(1).map(|i| sources[i]), sources.get(at).copied());
+                    let pick = match strategy.source {
+                        FillSource::Previous => prev.or(next),
+                        FillSource::Next => next.or(prev),
+                        _ => match (prev, next) {
+                            (Some(p), Some(n)) => Some(if frame - p <= n - frame { p } else { n }),
+                            (p, n) => p.or(n),
+                        },
+                    };
+                    match pick {
+                        Some(f) => PathBuf::from(format_frame(self.pattern(), self.padding, f)),
+                        None => return Err(OpError::Conflict { path: PathBuf::from(self.pattern()), message: "no frame to fill the gaps from".into() }),
+                    }
+                }
+            };
+            let to = PathBuf::from(format_frame(self.pattern(), self.padding, frame));
+            let mut status = OpStatus::Planned;
+            if fs::symlink_metadata(&to).is_ok() {
+                match opts.on_exists {
+                    OnExists::Fail => existing.push(to.clone()),
+                    OnExists::Skip => status = OpStatus::Skipped,
+                    OnExists::Overwrite => {}
+                }
+            }
+            ops.push(FileOp { kind, frame, from, to, status });
+        }
+        if !existing.is_empty() {
+            return Err(OpError::Exists { paths: existing });
+        }
+
+        if !opts.dry_run {
+            let overwrite = opts.on_exists == OnExists::Overwrite;
+            run_parallel(&mut ops, opts.journal.as_ref(), |op| match op.kind {
+                OpKind::Copy => copy_atomic(&op.from, &op.to, overwrite),
+                // Frames of the sequence sit next to the gap: link by name, so the folder can move
+                _ if placeholder.is_some() => link_atomic(op.kind, &op.from, &op.from, &op.to, overwrite),
+                _ => link_atomic(op.kind, &op.from, Path::new(op.from.file_name().unwrap_or_default()), &op.to, overwrite),
+            });
+        }
+        Ok(OpReport { ops, dry_run: opts.dry_run })
+    }
+}
@@ -0,0 +1,308 @@
+//! Journal of file operations, for rolling back a batch after the fact.
+
+use super::{is_empty_path, move_file, reverse_op, run_in_order, FileOp, OpError, OpKind, OpReport, OpStatus};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// An append-only log of every file op run with it (see
+/// [`TransferOptions::journal`](super::TransferOptions::journal)), one JSON
+/// record per line, with absolute paths.
+///
+/// Each op is recorded before it starts and again once it is done or has
+/// failed, so after an interruption (a kill, a crash, a full disk) the ops
+/// left open are known: [`replay`](Self::replay) settles them from what is on
+/// disk, and [`rollback`](Self::rollback) reverses everything that was done,
+/// last op first. A file an op overwrites is moved aside to a hidden backup
+/// next to it first, and stays there for the rollback to put back. A journal can take any number of operations and processes;
+/// clones share the file. Writers take an exclusive lock on the file for each
+/// record and first catch up on what others appended, so op ids stay unique
+/// however many times and by however many processes the file is open.
+///
+/// # Example
+/// ```ignore
+/// let journal = Journal::open("/renders/.scanseq-journal")?;
+/// let opts = TransferOptions { journal: Some(journal.clone()), ..Default::default() };
+/// seq.move_to("/archive/", &opts)?;
+/// other.renumber(Renumber::Start(1001), &opts)?;
+/// // all of it, put back:
+/// let report = journal.rollback(false)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct Journal {
+    path: PathBuf,
+    inner: Arc<Mutex<JournalFile>>,
+}
+
+#[derive(Debug)]
+struct JournalFile {
+    file: fs::File,
+    /// Id of the next op begun here.
+    next_id: u64,
+    /// Bytes of the file read or written so far; anything past it was
+    /// appended by another writer.
+    seen: u64,
+}
+
+/// One op of a journal, as [`Journal::replay`] finds it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct JournalEntry {
+    /// Number of the op in the journal, from 1.
+    pub id: u64,
+    /// The op, with its last known status.
+    #[serde(flatten)]
+    pub op: FileOp,
+    /// Begun but never finished: the status was settled from the disk.
+    pub interrupted: bool,
+    /// Reversed by a [`Journal::rollback`].
+    pub undone: bool,
+    /// Temp file the op wrote through, left behind if it was interrupted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temp: Option<PathBuf>,
+    /// Where the file the op overwrote was moved aside to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup: Option<PathBuf>,
+}
+
+/// A line of the journal file.
+#[derive(Debug, Serialize, Deserialize)]
+struct Record {
+    id: u64,
+    #[serde(flatten)]
+    event: Event,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum Event {
+    Begin {
+        kind: OpKind,
+        frame: i64,
+        from: PathBuf,
+        #[serde(default, skip_serializing_if = "is_empty_path")]
+        to: PathBuf,
+        /// Temp file the op writes through, next to `to`
+        #[serde(default, skip_serializing_if = "is_empty_path")]
+        temp: PathBuf,
+        /// Where `to` was moved aside to before the op (an overwrite)
+        #[serde(default, skip_serializing_if = "is_empty_path")]
+        backup: PathBuf,
+    },
+    Done,
+    Failed {
+        error: String,
+    },
+    Undone,
+}
+
+impl Journal {
+    /// Open the journal at `path` for appending, creating it if needed. A
+    /// last record cut short by an interruption is dropped.
+    #[allow(dead_code)] // Public API
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Journal, OpError> {
+        let path = path.as_ref().to_path_buf();
+        let file = fs::File::options().read(true).append(true).create(true).open(&path).map_err(|e| OpError::io(&path, e))?;
+        let journal = Journal { path, inner: Arc::new(Mutex::new(JournalFile { file, next_id: 1, seen: 0 })) };
+        journal.locked(|_| Ok(())).map_err(|e| OpError::io(&journal.path, e))?;
+        Ok(journal)
+    }
+
+    /// The journal file.
+    #[must_use]
+    #[allow(dead_code)] // Public API
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Every op of the journal in the order they were started, with its
+    /// outcome. Ops that were begun and never finished are settled from the
+    /// disk: a move counts as done when its source is gone and its
+    /// destination is there, a copy or link when its destination is there
+    /// (and didn't exist before), a deletion when the file is gone; anything
+    /// else is a failed op.
+    #[allow(dead_code)] // Public API
+    pub fn replay(&self) -> Result<Vec<JournalEntry>, OpError> {
+        let text = fs::read_to_string(&self.path).map_err(|e| OpError::io(&self.path, e))?;
+        let mut entries: Vec<JournalEntry> = Vec::new();
+        let mut index = HashMap::new();
+        for record in parse_records(&text).map_err(|e| OpError::io(&self.path, e))? {
+            let at = index.get(&record.id).copied();
+            match (record.event, at) {
+                (Event::Begin { kind, frame, from, to, temp, backup }, _) => {
+                    index.insert(record.id, entries.len());
+                    let op = FileOp { kind, frame, from, to, status: OpStatus::Planned };
+                    let recorded = |path: PathBuf| Some(path).filter(|p| !is_empty_path(p));
+                    entries.push(JournalEntry { id: record.id, op, interrupted: true, undone: false, temp: recorded(temp), backup: recorded(backup) });
+                }
+                (Event::Done, Some(at)) => {
+                    entries[at].op.status = OpStatus::Done;
+                    entries[at].interrupted = false;
+                }
+                (Event::Failed { error }, Some(at)) => {
+                    entries[at].op.status = OpStatus::Failed(error);
+                    entries[at].interrupted = false;
+                }
+                (Event::Undone, Some(at)) => entries[at].undone = true,
+                (_, None) => warn!("{}: record for unknown op {}, ignored", self.path.display(), record.id),
+            }
+        }
+        for entry in entries.iter_mut().filter(|e| e.interrupted) {
+            entry.op.status = settle(&entry.op, entry.backup.as_deref());
+        }
+        Ok(entries)
+    }
+
+    /// Reverse every done op that isn't undone yet, last one first (see
+    /// [`OpReport::undo`]), and record them as undone, so rolling back twice
+    /// does nothing the second time. A file an op overwrote is renamed back
+    /// from its backup once the op is reversed, or right away if the op never
+    /// finished. A rollback cut short can simply be run again: frames already
+    /// back in place are counted as done. The temp files of interrupted ops
+    /// are removed; no other file is. Stops at the first failure.
+    #[allow(dead_code)] // Public API
+    pub fn rollback(&self, dry_run: bool) -> Result<OpReport, OpError> {
+        let entries = self.replay()?;
+        let exists = |path: &Path| fs::symlink_metadata(path).is_ok();
+        let mut ids = Vec::new();
+        let mut ops = Vec::new();
+        for entry in entries.iter().rev().filter(|e| !e.undone) {
+            let done = entry.op.status == OpStatus::Done;
+            if let Some(op) = reverse_op(&entry.op).filter(|_| done) {
+                ids.push(entry.id);
+                ops.push(op);
+            }
+            if let Some(backup) = entry.backup.as_ref().filter(|&b| done || exists(b)) {
+                ids.push(entry.id);
+                ops.push(FileOp { kind: OpKind::Rename, frame: entry.op.frame, from: backup.clone(), to: entry.op.to.clone(), status: OpStatus::Planned });
+            }
+        }
+        if dry_run {
+            return Ok(OpReport { ops, dry_run: true });
+        }
+
+        for temp in entries.iter().filter(|e| e.interrupted).filter_map(|e| e.temp.as_deref()) {
+            match fs::remove_file(temp) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => warn!("{}: can't remove temp file: {}", temp.display(), e),
+                _ => {}
+            }
+        }
+        run_in_order(&mut ops, None, |op| match op.kind {
+            OpKind::Delete if !exists(&op.from) => Ok(()),
+            OpKind::Delete => fs::remove_file(&op.from),
+            _ if !exists(&op.from) && exists(&op.to) => Ok(()),
+            _ => move_file(&op.from, &op.to, false),
+        });
+        // An op is undone once all of its reversing ops are
+        let unfinished: HashSet<u64> = ids.iter().zip(&ops).filter(|(_, op)| op.status != OpStatus::Done).map(|(&id, _)| id).collect();
+        let mut recorded = HashSet::new();
+        for &id in ids.iter().filter(|id| !unfinished.contains(id)) {
+            if recorded.insert(id) {
+                self.write(&Record { id, event: Event::Undone }).map_err(|e| OpError::io(&self.path, e))?;
+            }
+        }
+        Ok(OpReport { ops, dry_run: false })
+    }
+
+    /// Record `op` as started, with the temp file it writes through and the
+    /// backup of the file it overwrites, if any; returns its id.
+    pub(super) fn begin(&self, op: &FileOp, temp: Option<&Path>, backup: Option<&Path>) -> io::Result<u64> {
+        // Absolute, so the journal can be rolled back from any folder
+        let absolute = |path: &Path| if is_empty_path(path) { PathBuf::new() } else { std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf()) };
+        let (temp, backup) = (temp.map(absolute).unwrap_or_default(), backup.map(absolute).unwrap_or_default());
+        let event = Event::Begin { kind: op.kind, frame: op.frame, from: absolute(&op.from), to: absolute(&op.to), temp, backup };
+        self.locked(|journal| {
+            let id = journal.next_id;
+            journal.next_id += 1;
+            journal.append(&Record { id, event })?;
+            Ok(id)
+        })
+    }
+
+    /// Record how op `id` ended. The op has happened either way, so a failed
+    /// write is only logged: [`replay`](Self::replay) settles the op from the disk.
+    pub(super) fn end(&self, id: u64, result: &io::Result<()>) {
+        let event = match result {
+            Ok(()) => Event::Done,
+            Err(e) => Event::Failed { error: e.to_string() },
+        };
+        if let Err(e) = self.write(&Record { id, event }) {
+            warn!("{}: can't record the end of op {}: {}", self.path.display(), id, e);
+        }
+    }
+
+    /// Append one record.
+    fn write(&self, record: &Record) -> io::Result<()> {
+        self.locked(|journal| journal.append(record))
+    }
+
+    /// Run `f` holding both the in-process mutex and an exclusive lock on the
+    /// file, after catching up on records other writers appended.
+    fn locked<T>(&self, f: impl FnOnce(&mut JournalFile) -> io::Result<T>) -> io::Result<T> {
+        let mut journal = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        journal.file.lock()?;
+        let result = journal.catch_up().and_then(|()| f(&mut journal));
+        let unlocked = journal.file.unlock();
+        result.and_then(|value| unlocked.map(|()| value))
+    }
+}
+
+impl JournalFile {
+    /// Read what was appended since `seen` and move `next_id` past its ids. A
+    /// last record cut short by an interrupted writer is dropped. File lock held.
+    fn catch_up(&mut self) -> io::Result<()> {
+        let len = self.file.metadata()?.len();
+        if len == self.seen {
+            return Ok(());
+        }
+        let mut text = String::new();
+        self.file.seek(SeekFrom::Start(self.seen))?;
+        self.file.read_to_string(&mut text)?;
+        if !text.ends_with('\n') {
+            let complete = text.rfind('\n').map_or(0, |at| at + 1);
+            self.file.set_len(self.seen + complete as u64)?;
+            text.truncate(complete);
+        }
+        if let Some(last_id) = parse_records(&text)?.iter().map(|r| r.id).max() {
+            self.next_id = self.next_id.max(last_id + 1);
+        }
+        self.seen += text.len() as u64;
+        Ok(())
+    }
+
+    /// Append `record` as a single write. File lock held, caught up.
+    fn append(&mut self, record: &Record) -> io::Result<()> {
+        let mut line = serde_json::to_string(record).map_err(io::Error::other)?;
+        line.push('\n');
+        self.file.write_all(line.as_bytes())?;
+        self.seen += line.len() as u64;
+        Ok(())
+    }
+}
+
+fn parse_records(text: &str) -> io::Result<Vec<Record>> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(n, line)| serde_json::from_str(line).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", n + 1, e))))
+        .collect()
+}
+
+/// Status of an op that was begun and never finished, judged from the disk.
+fn settle(op: &FileOp, backup: Option<&Path>) -> OpStatus {
+    let exists = |path: &Path| fs::symlink_metadata(path).is_ok();
+    let done = match op.kind {
+        OpKind::Move | OpKind::Rename => !exists(&op.from) && exists(&op.to),
+        OpKind::Delete => !exists(&op.from),
+        // Over a replaced file, `to` is the op's only while the backup is aside
+        OpKind::Copy | OpKind::Hardlink | OpKind::Symlink | OpKind::Reflink => exists(&op.to) && backup.is_none_or(exists),
+    };
+    if done {
+        OpStatus::Done
+    } else {
+        OpStatus::Failed("interrupted".into())
+    }
+}
@@ -0,0 +1,243 @@
+//! Linking a sequence into another layout (link farms, publish views).
+
+use super::{copy_atomic, dest_pattern, journaled, link_atomic, run_parallel, FileOp, Journal, OnExists, OpError, OpKind, OpReport, OpStatus, Renumber};
+use crate::core::seq::{format_frame, format_frame_ranges, Seq};
+use log::warn;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::str::FromStr;
+
+/// What [`Seq::link_to`] creates for each frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkKind {
+    /// A symbolic link (see [`LinkOptions::relative`]).
+    Sym,
+    /// A hard link: same file system only.
+    Hard,
+    /// A copy-on-write clone (Btrfs, XFS, APFS, ReFS), falling back to a plain
+    /// copy where unsupported (see [`LinkOptions::reflink_fallback`]).
+    Reflink,
+}
+
+impl FromStr for LinkKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "sym" | "symlink" | "soft" => Ok(LinkKind::Sym),
+            "hard" | "hardlink" => Ok(LinkKind::Hard),
+            "reflink" | "clone" | "cow" => Ok(LinkKind::Reflink),
+            _ => Err(format!("unknown link kind '{}' (expected sym, hard or reflink)", s)),
+        }
+    }
+}
+
+impl fmt::Display for LinkKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LinkKind::Sym => "sym",
+            LinkKind::Hard => "hard",
+            LinkKind::Reflink => "reflink",
+        })
+    }
+}
+
+/// Frame numbers of the links, from the frame numbers of the sequence.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum FrameMap {
+    /// Same numbers.
+    #[default]
+    Keep,
+    /// Shifted, as [`Seq::renumber`] would.
+    Renumber(Renumber),
+    /// Source frame to link frame; only these frames are linked.
+    #[allow(dead_code)] // Public API (unused by the bundled CLI bin)
+    Explicit(BTreeMap<i64, i64>),
+}
+
+/// Options of [`Seq::link_to`].
+#[derive(Debug, Clone)]
+pub struct LinkOptions {
+    /// Symlinks point at the source by a relative path (`../../render/...`)
+    /// instead of an absolute one, so the tree can move as a whole.
+    pub relative: bool,
+    /// Reflinks: make plain copies when the file system can't clone
+    /// (default). Off, the unsupported clone is a failed op.
+    pub reflink_fallback: bool,
+    /// Link frames that already exist.
+    pub on_exists: OnExists,
+    /// Only plan: report what would be done, touch nothing.
+    pub dry_run: bool,
+    /// Record every op in this journal (see [`TransferOptions::journal`](super::TransferOptions::journal)).
+    pub journal: Option<Journal>,
+}
+
+impl Default for LinkOptions {
+    fn default() -> Self {
+        LinkOptions { relative: false, reflink_fallback: true, on_exists: OnExists::default(), dry_run: false, journal: None }
+    }
+}
+
+impl Seq {
+    /// Link every mapped frame to `dest` (a pattern or folder, as in
+    /// [`copy_to`](Self::copy_to)), numbered through `map`.
+    ///
+    /// Each link is made under a temp name and renamed into place, then
+    /// verified: a symlink must resolve to its source, a hard link must share
+    /// its inode, a clone must have its size. A link that fails the check is
+    /// removed and its op failed. Reflinks are tried on one frame first; if
+    /// the file system can't clone, all frames fall back to copies (reported
+    /// as `copy`), unless `opts.reflink_fallback` is off.
+    ///
+    /// # Example
+    /// ```ignore
+    /// // /render/comp.0001-0240 -> /publish/shot010/comp/v003/comp.1001-1240, absolute symlinks
+    /// let map = FrameMap::Renumber(Renumber::Start(1001));
+    /// let report = seq.link_to("/publish/shot010/comp/v003/comp.####.exr", LinkKind::Sym, &map, &LinkOptions::default())?;
+    /// ```
+    #[allow(dead_code)] // Public API
+    pub fn link_to(&self, dest: &str, kind: LinkKind, map: &FrameMap, opts: &LinkOptions) -> Result<OpReport, OpError> {
+        let (pattern, padding) = dest_pattern(self, dest)?;
+        let pairs: Vec<(i64, i64)> = match map {
+            FrameMap::Keep => self.indices.iter().map(|&f| (f, f)).collect(),
+            FrameMap::Renumber(how) => {
+                let offset = how.offset(self);
+                let shifted = self.indices.iter().map(|&f| f.checked_add(offset).map(|new| (f, new)).ok_or(f));
+                shifted.collect::<Result<_, _>>().map_err(|f| OpError::pattern(self.pattern(), format!("frame {f} shifted by {offset} is out of range")))?
+            }
+            FrameMap::Explicit(frames) => {
+                let absent: Vec<i64> = frames.keys().copied().filter(|f| self.indices.binary_search(f).is_err()).collect();
+                if !absent.is_empty() {
+                    let message = format!("frames {} are not in the sequence", format_frame_ranges(&absent));
+                    return Err(OpError::Conflict { path: PathBuf::from(self.pattern()), message });
+                }
+                frames.iter().map(|(&from, &to)| (from, to)).collect()
+            }
+        };
+
+        let op_kind = match kind {
+            LinkKind::Sym => OpKind::Symlink,
+            LinkKind::Hard => OpKind::Hardlink,
+            LinkKind::Reflink => OpKind::Reflink,
+        };
+        let sources: HashSet<PathBuf> = self.paths().into_iter().collect();
+        let mut targets = BTreeSet::new();
+        let mut ops = Vec::with_capacity(pairs.len());
+        let mut existing = Vec::new();
+        for (frame, new) in pairs {
+            if padding >= 2 && new.to_string().len() > padding {
+                return Err(OpError::pattern(&pattern, format!("frame {new} does not fit {padding} digits")));
+            }
+            let from = PathBuf::from(format_frame(self.pattern(), self.padding, frame));
+            let to = PathBuf::from(format_frame(&pattern, padding, new));
+            if sources.contains(&to) {
+                return Err(OpError::Conflict { path: to, message: "a link would replace a frame of the source".into() });
+            }
+            if !targets.insert(new) {
+                return Err(OpError::Conflict { path: to, message: format!("more than one frame maps to {new}") });
+            }
+            let mut status = OpStatus::Planned;
+            if fs::symlink_metadata(&to).is_ok() {
+                match opts.on_exists {
+                    OnExists::Fail => existing.push(to.clone()),
+                    OnExists::Skip => status = OpStatus::Skipped,
+                    OnExists::Overwrite => {}
+                }
+            }
+            ops.push(FileOp { kind: op_kind, frame, from, to, status });
+        }
+        if !existing.is_empty() {
+            return Err(OpError::Exists { paths: existing });
+        }
+        if opts.dry_run {
+            return Ok(OpReport { ops, dry_run: true });
+        }
+
+        let dirs: BTreeSet<PathBuf> = ops.iter().filter_map(|op| op.to.parent()).map(PathBuf::from).collect();
+        for dir in dirs.iter().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(|e| OpError::io(dir, e))?;
+        }
+        let overwrite = opts.on_exists == OnExists::Overwrite;
+        let run = |op: &FileOp| -> io::Result<()> {
+            match op.kind {
+                OpKind::Copy => copy_atomic(&op.from, &op.to, overwrite)?,
+                OpKind::Symlink => link_atomic(op.kind, &op.from, &link_target(&op.from, &op.to, opts.relative), &op.to, overwrite)?,
+                _ => link_atomic(op.kind, &op.from, &op.from, &op.to, overwrite)?,
+            }
+            verify_link(op).inspect_err(|_| {
+                let _ = fs::remove_file(&op.to);
+            })
+        };
+
+        // Probe the file system with one clone before committing every frame to it
+        if let Some(first) = ops.iter_mut().find(|op| op.kind == OpKind::Reflink && op.status == OpStatus::Planned) {
+            match journaled(first, opts.journal.as_ref(), run) {
+                Ok(()) => first.status = OpStatus::Done,
+                Err(e) if opts.reflink_fallback => {
+                    warn!("{}: can't clone ({}), copying instead", self.pattern(), e);
+                    ops.iter_mut().for_each(|op| op.kind = OpKind::Copy);
+                }
+                Err(e) => first.status = OpStatus::Failed(e.to_string()),
+            }
+        }
+        run_parallel(&mut ops, opts.journal.as_ref(), run);
+        Ok(OpReport { ops, dry_run: false })
+    }
+}
+
+/// Symlink target for a link at `link` to `src`: absolute, or relative to the
+/// link's folder. Both sides are resolved first, so the relative path holds
+/// even when the folders are reached through symlinks.
+fn link_target(src: &Path, link: &Path, relative: bool) -> PathBuf {
+    let resolve = |path: &Path| -> PathBuf {
+        let dir = path.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        let dir = fs::canonicalize(dir).or_else(|_| std::path::absolute(dir)).unwrap_or_else(|_| dir.to_path_buf());
+        dir.join(path.file_name().unwrap_or_default())
+    };
+    let src = resolve(src);
+    if !relative {
+        return src;
+    }
+    let link_dir = resolve(link).parent().map(Path::to_path_buf).unwrap_or_default();
+    relative_path(&link_dir, &src)
+}
+
+/// Path from folder `base` to `path`, both absolute and normalized.
+fn relative_path(base: &Path, path: &Path) -> PathBuf {
+    let base: Vec<Component> = base.components().collect();
+    let path: Vec<Component> = path.components().collect();
+    // Different roots (Windows drives): nothing relative about it
+    if base.first() != path.first() {
+        return path.iter().collect();
+    }
+    let common = base.iter().zip(&path).take_while(|(a, b)| a == b).count();
+    let mut out: PathBuf = base[common..].iter().map(|_| Component::ParentDir).collect();
+    out.extend(&path[common..]);
+    out
+}
+
+/// Check that `op.to` is what `op.kind` should have made of `op.from`.
+fn verify_link(op: &FileOp) -> io::Result<()> {
+    let mismatch = |what: &str| Err(io::Error::other(format!("verification failed: {what}")));
+    let (src, dst) = (fs::metadata(&op.from)?, fs::metadata(&op.to)?);
+    match op.kind {
+        OpKind::Symlink if fs::canonicalize(&op.to)? != fs::canonicalize(&op.from)? => mismatch("link does not resolve to the source"),
+        OpKind::Hardlink if !same_file(&src, &dst) => mismatch("not the same file as the source"),
+        _ if src.len() != dst.len() => mismatch("size differs from the source"),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(unix)]
+fn same_file(a: &fs::Metadata, b: &fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    a.dev() == b.dev() && a.ino() == b.ino()
+}
+
+#[cfg(not(unix))]
+fn same_file(a: &fs::Metadata, b: &fs::Metadata) -> bool {
+    a.len() == b.len() && a.modified().ok() == b.modified().ok()
+}
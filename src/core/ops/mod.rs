@@ -0,0 +1,486 @@
+//! File operations on whole sequences.
+//!
+//! Everything here changes files on disk, so unlike the scan it goes straight
+//! to the local filesystem (`std::fs`), not through [`Fs`](super::Fs). Every
+//! operation works in two steps:
+//!
+//! 1. **Plan**: one [`FileOp`] per frame, with all collision checks done up
+//!    front. A plan that can't run as a whole is an [`OpError`] and nothing is
+//!    touched.
+//! 2. **Run**: frames are processed in parallel and each op gets its own
+//!    [`OpStatus`], so a failure on one frame never hides the others. With
+//!    `dry_run` the plan comes back as is, every op [`OpStatus::Planned`].
+//!
+//! - `transfer`: [`Seq::copy_to`](super::Seq::copy_to) / [`Seq::move_to`](super::Seq::move_to)
+//! - `renumber`: [`Seq::renumber`](super::Seq::renumber), order-safe in-place frame shifts
+//! - `repad`: [`Seq::repad`](super::Seq::repad), new frame padding in place
+//! - `rename`: [`NameTemplate`] and [`Seq::rename_with`](super::Seq::rename_with), renames through naming templates
+//! - `remove`: [`Seq::remove`](super::Seq::remove), deletion or trashing of checked frame selections
+//! - `fill`: [`Seq::fill_gaps`](super::Seq::fill_gaps), hold or placeholder frames for the missing ones
+//! - `link`: [`Seq::link_to`](super::Seq::link_to), verified symlink, hardlink or reflink views of a sequence
+//! - `journal`: [`Journal`], an append-only record of the ops run with it, replayed to roll them back
+//!
+//! With a [`Journal`] in the options, each op is recorded before it starts
+//! and after it ends, so even a killed process leaves a batch that can be
+//! rolled back.
+
+mod fill;
+mod journal;
+mod link;
+mod remove;
+mod rename;
+mod renumber;
+mod repad;
+mod transfer;
+
+pub use fill::{FillMethod, FillSource, FillStrategy};
+pub use journal::{Journal, JournalEntry};
+pub use link::{FrameMap, LinkKind, LinkOptions};
+pub use remove::{FrameSelection, RemoveMode};
+pub use rename::{NameFields, NameTemplate};
+pub use renumber::Renumber;
+pub use transfer::{OnExists, TransferOptions};
+
+use super::seq::split_placeholder;
+use log::warn;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Why a sequence operation could not be planned or started.
+///
+/// Failures on single frames while running are not errors: they are recorded
+/// in the [`OpReport`] as [`OpStatus::Failed`].
+#[derive(Debug, Clone)]
+pub enum OpError {
+    /// Filesystem failure while planning (e.g. creating a destination folder).
+    Io { path: PathBuf, source: Arc<io::Error> },
+    /// A destination pattern or folder that can't take the sequence.
+    InvalidPattern { pattern: String, message: String },
+    /// Destination frames that already exist, under [`OnExists::Fail`].
+    Exists { paths: Vec<PathBuf> },
+    /// A frame would land on itself or on another frame of the same plan.
+    Conflict { path: PathBuf, message: String },
+    /// The files on disk no longer match the sequence: frames appeared, went
+    /// missing or were rewritten since the scan.
+    Changed { pattern: String, added: Vec<i64>, missing: Vec<i64>, modified: Vec<i64> },
+}
+
+impl OpError {
+    pub(crate) fn io<P: Into<PathBuf>>(path: P, source: io::Error) -> Self {
+        OpError::Io { path: path.into(), source: Arc::new(source) }
+    }
+
+    pub(crate) fn pattern(pattern: &str, message: impl Into<String>) -> Self {
+        OpError::InvalidPattern { pattern: pattern.to_string(), message: message.into() }
+    }
+}
+
+impl fmt::Display for OpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OpError::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            OpError::InvalidPattern { pattern, message } => write!(f, "Invalid pattern {pattern:?}: {message}"),
+            OpError::Exists { paths } => match paths.as_slice() {
+                [one] => write!(f, "{}: already exists", one.display()),
+                [first, ..] => write!(f, "{} destination frames already exist (first: {})", paths.len(), first.display()),
+                [] => write!(f, "Destination already exists"),
+            },
+            OpError::Conflict { path, message } => write!(f, "{}: {}", path.display(), message),
+            OpError::Changed { pattern, added, missing, modified } => {
+                let changes: Vec<String> = [("new", added), ("missing", missing), ("modified", modified)]
+                    .iter()
+                    .filter(|(_, frames)| !frames.is_empty())
+                    .map(|(what, frames)| format!("{} frames {}", what, super::format_frame_ranges(frames)))
+                    .collect();
+                write!(f, "{}: changed on disk since the scan ({})", pattern, changes.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for OpError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            OpError::Io { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// What an op does to its frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OpKind {
+    Copy,
+    Move,
+    /// A move within the same folder
+    Rename,
+    /// Permanent removal (no `to`)
+    Delete,
+    /// `to` made a hard link of `from`
+    Hardlink,
+    /// `to` made a symbolic link to `from`
+    Symlink,
+    /// `to` made a copy-on-write clone of `from`
+    Reflink,
+}
+
+impl fmt::Display for OpKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            OpKind::Copy => "copy",
+            OpKind::Move => "move",
+            OpKind::Rename => "rename",
+            OpKind::Delete => "delete",
+            OpKind::Hardlink => "hardlink",
+            OpKind::Symlink => "symlink",
+            OpKind::Reflink => "reflink",
+        })
+    }
+}
+
+/// Outcome of a single op.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", content = "error", rename_all = "snake_case")]
+pub enum OpStatus {
+    /// Dry run: would be done.
+    Planned,
+    Done,
+    /// Left alone (destination exists under [`OnExists::Skip`]).
+    Skipped,
+    /// Failed, with the reason.
+    Failed(String),
+}
+
+/// One file operation of a plan: `kind` applied to frame `frame`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileOp {
+    pub kind: OpKind,
+    pub frame: i64,
+    pub from: PathBuf,
+    #[serde(skip_serializing_if = "is_empty_path")]
+    pub to: PathBuf,
+    #[serde(flatten)]
+    pub status: OpStatus,
+}
+
+impl fmt::Display for FileOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            OpKind::Delete => write!(f, "{} {}", self.kind, self.from.display())?,
+            _ => write!(f, "{} {} -> {}", self.kind, self.from.display(), self.to.display())?,
+        }
+        match &self.status {
+            OpStatus::Planned | OpStatus::Done => Ok(()),
+            OpStatus::Skipped => write!(f, " (skipped)"),
+            OpStatus::Failed(e) => write!(f, " (failed: {e})"),
+        }
+    }
+}
+
+/// Everything a sequence operation did (or, with `dry_run`, would do), in the
+/// order the ops were run.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OpReport {
+    pub ops: Vec<FileOp>,
+    pub dry_run: bool,
+}
+
+impl OpReport {
+    /// Frames whose op is done (planned, in a dry run).
+    #[must_use]
+    #[allow(dead_code)] // Public API
+    pub fn succeeded(&self) -> Vec<i64> {
+        self.frames(|s| matches!(s, OpStatus::Done | OpStatus::Planned))
+    }
+
+    /// Frames left alone.
+    #[must_use]
+    #[allow(dead_code)] // Public API
+    pub fn skipped(&self) -> Vec<i64> {
+        self.frames(|s| *s == OpStatus::Skipped)
+    }
+
+    /// Frames whose op failed.
+    #[must_use]
+    #[allow(dead_code)] // Public API
+    pub fn failed(&self) -> Vec<i64> {
+        self.frames(|s| matches!(s, OpStatus::Failed(_)))
+    }
+
+    /// True if no op failed.
+    #[must_use]
+    #[allow(dead_code)] // Public API
+    pub fn is_ok(&self) -> bool {
+        !self.ops.iter().any(|op| matches!(op.status, OpStatus::Failed(_)))
+    }
+
+    /// Reverse every done op, last one first: moves and renames go back, so
+    /// frames shuffled in place (see [`Seq::renumber`](super::Seq::renumber))
+    /// land where they were and trashed frames return; copies and links are
+    /// deleted. Deletions can't be undone. Stops at the first failure, like
+    /// the operation itself; the returned report lists the reversing ops.
+    #[allow(dead_code)] // Public API
+    pub fn undo(&self) -> OpReport {
+        self.undo_with(None)
+    }
+
+    /// [`undo`](Self::undo), with the reversing ops recorded in `journal`.
+    #[allow(dead_code)] // Public API
+    pub fn undo_with(&self, journal: Option<&Journal>) -> OpReport {
+        let mut ops: Vec<FileOp> = self.ops.iter().rev().filter(|op| op.status == OpStatus::Done).filter_map(reverse_op).collect();
+        run_in_order(&mut ops, journal, |op| match op.kind {
+            OpKind::Delete => fs::remove_file(&op.from),
+            _ => move_file(&op.from, &op.to, false),
+        });
+        OpReport { ops, dry_run: false }
+    }
+
+    fn frames(&self, pick: impl Fn(&OpStatus) -> bool) -> Vec<i64> {
+        self.ops.iter().filter(|op| pick(&op.status)).map(|op| op.frame).collect()
+    }
+}
+
+/// Resolve a destination for `seq` into a pattern and padding for [`format_frame`](super::format_frame).
+///
+/// `dest` is either a folder (ending in a separator, or existing), which keeps
+/// the file names, or a pattern with a single frame placeholder: a run of `#`
+/// (its length is the padding, a single `#` means unpadded) or `@`.
+pub(crate) fn dest_pattern(seq: &super::Seq, dest: &str) -> Result<(String, usize), OpError> {
+    if dest.ends_with(['/', '\\']) || Path::new(dest).is_dir() {
+        let name = Path::new(seq.pattern()).file_name().ok_or_else(|| OpError::pattern(seq.pattern(), "sequence has no file name"))?;
+        return Ok((Path::new(dest).join(name).to_string_lossy().into_owned(), seq.padding));
+    }
+    let name = Path::new(dest).file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let runs: Vec<&str> = name.split(|c| c != '#').filter(|r| !r.is_empty()).collect();
+    let ats = name.matches('@').count();
+    match (runs.as_slice(), ats) {
+        ([], 1) => Ok((dest.to_string(), 0)),
+        ([run], 0) if run.len() == 1 => match dest.strip_suffix(name.as_str()) {
+            Some(dir) => Ok((format!("{}{}", dir, name.replace('#', "@")), 0)),
+            None => Err(OpError::pattern(dest, "no file name")),
+        },
+        ([run], 0) => Ok((dest.to_string(), run.len())),
+        ([], 0) => Err(OpError::pattern(dest, "no frame placeholder (#### or @) in the file name")),
+        _ => Err(OpError::pattern(dest, "more than one frame placeholder in the file name")),
+    }
+}
+
+/// The planned op that reverses `op`: moves and renames go back, copies and
+/// links are deleted. Deletions have none.
+fn reverse_op(op: &FileOp) -> Option<FileOp> {
+    let (kind, from, to) = match op.kind {
+        OpKind::Move | OpKind::Rename => (op.kind, op.to.clone(), op.from.clone()),
+        OpKind::Copy | OpKind::Hardlink | OpKind::Symlink | OpKind::Reflink => (OpKind::Delete, op.to.clone(), PathBuf::new()),
+        OpKind::Delete => return None,
+    };
+    Some(FileOp { kind, frame: op.frame, from, to, status: OpStatus::Planned })
+}
+
+fn is_empty_path(path: &Path) -> bool {
+    path.as_os_str().is_empty()
+}
+
+/// Frames of `pattern` present in its folder right now. Only names the
+/// pattern formats exactly count: `r.00005.exr` is not frame 5 of `r.####.exr`.
+pub(crate) fn frames_on_disk(pattern: &str, padding: usize) -> Result<BTreeSet<i64>, OpError> {
+    let path = Path::new(pattern);
+    let dir = path.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let (before, after) = split_placeholder(&name, padding).ok_or_else(|| OpError::pattern(pattern, "no frame placeholder"))?;
+
+    let mut frames = BTreeSet::new();
+    for entry in fs::read_dir(dir).map_err(|e| OpError::io(dir, e))? {
+        let entry = entry.map_err(|e| OpError::io(dir, e))?;
+        let entry_name = entry.file_name();
+        let digits = entry_name.to_str().and_then(|n| n.strip_prefix(before)).and_then(|n| n.strip_suffix(after));
+        let Some(digits) = digits.filter(|d| !d.is_empty() && d.bytes().all(|b| b.is_ascii_digit())) else { continue };
+        if let Ok(frame) = digits.parse::<i64>() {
+            let formatted = if padding >= 2 { format!("{:0w$}", frame, w = padding) } else { frame.to_string() };
+            if formatted == digits {
+                frames.insert(frame);
+            }
+        }
+    }
+    Ok(frames)
+}
+
+/// Run planned ops in parallel, each on its own.
+fn run_parallel(ops: &mut [FileOp], journal: Option<&Journal>, run: impl Fn(&FileOp) -> io::Result<()> + Sync) {
+    ops.par_iter_mut().filter(|op| op.status == OpStatus::Planned).for_each(|op| {
+        op.status = match journaled(op, journal, &run) {
+            Ok(()) => OpStatus::Done,
+            Err(e) => OpStatus::Failed(e.to_string()),
+        };
+    });
+}
+
+/// Run planned ops one after the other. The order matters when frames move
+/// onto each other's old names, so after a failure nothing else is attempted.
+fn run_in_order(ops: &mut [FileOp], journal: Option<&Journal>, run: impl Fn(&FileOp) -> io::Result<()>) {
+    let mut failed: Option<i64> = None;
+    for op in ops.iter_mut().filter(|op| op.status == OpStatus::Planned) {
+        op.status = match failed {
+            Some(frame) => OpStatus::Failed(format!("not attempted, frame {frame} failed first")),
+            None => match journaled(op, journal, &run) {
+                Ok(()) => OpStatus::Done,
+                Err(e) => {
+                    failed = Some(op.frame);
+                    OpStatus::Failed(e.to_string())
+                }
+            },
+        };
+    }
+}
+
+/// Run `op`, recorded in `journal` if there is one: begun before it starts,
+/// done or failed after. An op that can't be recorded doesn't run.
+///
+/// With a journal, the temp file the op writes through is picked up front and
+/// recorded, and a destination the op would overwrite is first moved aside to
+/// a recorded backup, so [`Journal::rollback`] can clean up after the op and
+/// put the replaced file back. A failed op puts it back right away.
+fn journaled(op: &FileOp, journal: Option<&Journal>, run: impl Fn(&FileOp) -> io::Result<()>) -> io::Result<()> {
+    let Some(journal) = journal else { return run(op) };
+    let writes = op.kind != OpKind::Delete;
+    let temp = writes.then(|| hidden_path(&op.to, TEMP_PREFIX));
+    let backup = (writes && fs::symlink_metadata(&op.to).is_ok()).then(|| hidden_path(&op.to, BACKUP_PREFIX));
+    let id = journal.begin(op, temp.as_deref(), backup.as_deref()).map_err(|e| io::Error::new(e.kind(), format!("journal {}: {}", journal.path().display(), e)))?;
+    let result = (|| {
+        if let Some(backup) = &backup {
+            fs::rename(&op.to, backup)?;
+        }
+        JOURNALED_TEMP.set(temp);
+        let result = run(op);
+        JOURNALED_TEMP.set(None);
+        if let (Err(_), Some(backup)) = (&result, &backup) {
+            if let Err(e) = fs::rename(backup, &op.to) {
+                warn!("{}: can't put the replaced file back from {}: {}", op.to.display(), backup.display(), e);
+            }
+        }
+        result
+    })();
+    journal.end(id, &result);
+    result
+}
+
+/// File name start of the temp files ops write through.
+const TEMP_PREFIX: &str = ".scanseq-tmp-";
+
+/// File name start of the files journaled ops overwrite, kept for a rollback.
+const BACKUP_PREFIX: &str = ".scanseq-bak-";
+
+thread_local! {
+    /// Temp path [`journaled`] recorded for the op running on this thread,
+    /// handed out by the op's [`temp_path`] call.
+    static JOURNALED_TEMP: Cell<Option<PathBuf>> = const { Cell::new(None) };
+}
+
+/// Temp name next to `dst` for an op to write through: the one its journal
+/// recorded, else a fresh [`hidden_path`].
+fn temp_path(dst: &Path) -> PathBuf {
+    match JOURNALED_TEMP.take() {
+        Some(temp) if temp.parent() == dst.parent() => temp,
+        _ => hidden_path(dst, TEMP_PREFIX),
+    }
+}
+
+/// Name next to `dst` starting with `prefix` that no scan groups into a
+/// sequence: hidden, and digit-free (a unique id spelled in letters), so it
+/// never matches a frame mask.
+fn hidden_path(dst: &Path, prefix: &str) -> PathBuf {
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    let mut id = (u64::from(std::process::id()) << 24) | NEXT.fetch_add(1, Ordering::Relaxed);
+    let mut name = String::from(prefix);
+    loop {
+        name.push(char::from(b'a' + (id % 26) as u8));
+        id /= 26;
+        if id == 0 {
+            break;
+        }
+    }
+    dst.with_file_name(name)
+}
+
+/// Copy `src` to `dst` through a temp file and a rename, so `dst` is either
+/// absent or complete. Keeps the source mtime. Unless `overwrite`, fails with
+/// `AlreadyExists` when `dst` appeared in the meantime.
+fn copy_atomic(src: &Path, dst: &Path, overwrite: bool) -> io::Result<()> {
+    let tmp = temp_path(dst);
+    let result = (|| {
+        fs::copy(src, &tmp)?;
+        if let Ok(mtime) = fs::metadata(src).and_then(|m| m.modified()) {
+            fs::File::options().write(true).open(&tmp)?.set_modified(mtime)?;
+        }
+        if !overwrite && fs::symlink_metadata(dst).is_ok() {
+            return Err(io::Error::new(io::ErrorKind::AlreadyExists, "destination appeared during the copy"));
+        }
+        fs::rename(&tmp, dst)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// Make `dst` a hard link or a reflink of `src`, or a symbolic link whose
+/// target is `target`, through a temp name and a rename like [`copy_atomic`].
+fn link_atomic(kind: OpKind, src: &Path, target: &Path, dst: &Path, overwrite: bool) -> io::Result<()> {
+    let tmp = temp_path(dst);
+    let result = (|| {
+        match kind {
+            OpKind::Hardlink => fs::hard_link(src, &tmp)?,
+            OpKind::Reflink => reflink_copy::reflink(src, &tmp)?,
+            _ => symlink_file(target, &tmp)?,
+        }
+        if !overwrite && fs::symlink_metadata(dst).is_ok() {
+            return Err(io::Error::new(io::ErrorKind::AlreadyExists, "destination appeared while linking"));
+        }
+        fs::rename(&tmp, dst)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+#[cfg(unix)]
+fn symlink_file(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn symlink_file(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_file(target, link)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn symlink_file(_target: &Path, _link: &Path) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "symbolic links are not supported on this platform"))
+}
+
+/// Move `src` to `dst`: a rename when both are on the same file system, else
+/// [`copy_atomic`] and removal of the source.
+fn move_file(src: &Path, dst: &Path, overwrite: bool) -> io::Result<()> {
+    if !overwrite && fs::symlink_metadata(dst).is_ok() {
+        return Err(io::Error::new(io::ErrorKind::AlreadyExists, "destination appeared during the move"));
+    }
+    match fs::rename(src, dst) {
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+            copy_atomic(src, dst, overwrite)?;
+            fs::remove_file(src)
+        }
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests;
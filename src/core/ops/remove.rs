@@ -0,0 +1,120 @@
+//! Deleting sequence frames, permanently or into a trash folder.
+
+use super::{frames_on_disk, move_file, run_parallel, FileOp, OnExists, OpError, OpKind, OpReport, OpStatus, TransferOptions};
+use crate::core::seq::{format_frame, format_frame_ranges, Seq};
+use crate::core::slice::FrameSlice;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::PathBuf;
+
+/// Which frames of a sequence [`Seq::remove`] takes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrameSelection {
+    /// Every present frame.
+    All,
+    /// Present frames by position (`::2`, `-10:`), see [`FrameSlice`].
+    Slice(FrameSlice),
+    /// These frame numbers; all of them must be present.
+    Frames(Vec<i64>),
+}
+
+/// How [`Seq::remove`] gets rid of a frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoveMode {
+    /// Delete the file.
+    Delete,
+    /// Move the file into this folder (created if needed), keeping its name.
+    /// [`OpReport::undo`] brings it back.
+    Trash(PathBuf),
+}
+
+impl Seq {
+    /// Remove the selected frames.
+    ///
+    /// Nothing is removed when the folder no longer holds what the sequence
+    /// describes ([`OpError::Changed`], see [`check_on_disk`](Self::check_on_disk)):
+    /// a re-render that added frames, or a newer version written over the old
+    /// names, is never deleted on the strength of an old scan. Frame numbers
+    /// that are not in the sequence are refused too. Under [`RemoveMode::Trash`],
+    /// names already in the trash follow `opts.on_exists`.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let report = seq.remove(&FrameSelection::Slice("::2".parse()?), &RemoveMode::Trash("/renders/.trash".into()), &TransferOptions::default())?;
+    /// ```
+    #[allow(dead_code)] // Public API
+    pub fn remove(&self, selection: &FrameSelection, mode: &RemoveMode, opts: &TransferOptions) -> Result<OpReport, OpError> {
+        let frames: Vec<i64> = match selection {
+            FrameSelection::All => self.indices.clone(),
+            FrameSelection::Slice(slice) => self.select(slice).indices,
+            FrameSelection::Frames(frames) => {
+                let absent: Vec<i64> = frames.iter().copied().filter(|f| self.indices.binary_search(f).is_err()).collect();
+                if !absent.is_empty() {
+                    let message = format!("frames {} are not in the sequence", format_frame_ranges(&absent));
+                    return Err(OpError::Conflict { path: PathBuf::from(self.pattern()), message });
+                }
+                frames.iter().copied().collect::<BTreeSet<_>>().into_iter().collect()
+            }
+        };
+        self.check_on_disk()?;
+
+        let mut ops = Vec::with_capacity(frames.len());
+        let mut existing = Vec::new();
+        for frame in frames {
+            let from = PathBuf::from(format_frame(self.pattern(), self.padding, frame));
+            let (kind, to) = match mode {
+                RemoveMode::Delete => (OpKind::Delete, PathBuf::new()),
+                RemoveMode::Trash(dir) => (OpKind::Move, dir.join(from.file_name().unwrap_or_default())),
+            };
+            let mut status = OpStatus::Planned;
+            if kind == OpKind::Move && fs::symlink_metadata(&to).is_ok() {
+                match opts.on_exists {
+                    OnExists::Fail => existing.push(to.clone()),
+                    OnExists::Skip => status = OpStatus::Skipped,
+                    OnExists::Overwrite => {}
+                }
+            }
+            ops.push(FileOp { kind, frame, from, to, status });
+        }
+        if !existing.is_empty() {
+            return Err(OpError::Exists { paths: existing });
+        }
+        if opts.dry_run {
+            return Ok(OpReport { ops, dry_run: true });
+        }
+
+        if let RemoveMode::Trash(dir) = mode {
+            fs::create_dir_all(dir).map_err(|e| OpError::io(dir, e))?;
+        }
+        let overwrite = opts.on_exists == OnExists::Overwrite;
+        run_parallel(&mut ops, opts.journal.as_ref(), |op| match op.kind {
+            OpKind::Delete => fs::remove_file(&op.from),
+            _ => move_file(&op.from, &op.to, overwrite),
+        });
+        Ok(OpReport { ops, dry_run: false })
+    }
+
+    /// Check that the folder still holds exactly the frames of the sequence:
+    /// no frame of the pattern appeared or went missing, and (for scans with
+    /// metadata) no frame changed size or mtime.
+    #[allow(dead_code)] // Public API
+    pub fn check_on_disk(&self) -> Result<(), OpError> {
+        let on_disk = frames_on_disk(self.pattern(), self.padding)?;
+        let known: BTreeSet<i64> = self.indices.iter().copied().collect();
+        let added: Vec<i64> = on_disk.difference(&known).copied().collect();
+        let missing: Vec<i64> = known.difference(&on_disk).copied().collect();
+        let modified: Vec<i64> = self
+            .frame_infos()
+            .filter(|(frame, info)| {
+                let now = self.get_path(*frame).and_then(|path| fs::metadata(path).ok());
+                now.is_some_and(|m| m.len() != info.size || (info.mtime.is_some() && m.modified().ok() != info.mtime))
+            })
+            .map(|(frame, _)| frame)
+            .collect();
+        if added.is_empty() && missing.is_empty() && modified.is_empty() {
+            Ok(())
+        } else {
+            Err(OpError::Changed { pattern: self.pattern().to_string(), added, missing, modified })
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//! Renaming sequences through naming templates.
+
+use super::{split_placeholder, OpError, OpKind, OpReport, TransferOptions};
+use crate::core::seq::Seq;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Field values a [`NameTemplate`] is rendered with, by field name.
+pub type NameFields = BTreeMap<String, String>;
+
+/// A file naming template: literal text with `{field}` and `{field:0N}`
+/// (zero-padded to N digits) placeholders, `{{` and `}}` for braces.
+///
+/// Rendering a sequence name (see [`Seq::rename_with`]) uses `{frame}`
+/// (`{frame}` keeps the padding, `{frame:04}` sets it) and the fields of
+/// [`Seq::name_fields`] plus any given ones. As a matcher,
+/// [`capture`](Self::capture) pulls fields out of an existing name.
+///
+/// ```ignore
+/// let to: NameTemplate = "{show}_{shot}_plate_v{version:03}.{frame:04}.{ext}".parse()?;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameTemplate {
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Text(String),
+    /// Field name and zero-padded width
+    Field(String, Option<usize>),
+}
+
+impl FromStr for NameTemplate {
+    type Err = OpError;
+
+    fn from_str(s: &str) -> Result<Self, OpError> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.as_str().starts_with('{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.as_str().starts_with('}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let rest = chars.as_str();
+                    let close = rest.find('}').ok_or_else(|| OpError::pattern(s, "unclosed '{'"))?;
+                    let (name, spec) = rest[..close].split_once(':').unwrap_or((&rest[..close], ""));
+                    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                        return Err(OpError::pattern(s, format!("invalid field name {:?}", name)));
+                    }
+                    let width = match spec {
+                        "" => None,
+                        _ => Some(spec.strip_prefix('0').and_then(|w| if w.is_empty() { Some(0) } else { w.parse().ok() }).ok_or_else(|| {
+                            OpError::pattern(s, format!("invalid format {:?} for {{{}}} (expected 0N, like {{{}:04}})", spec, name, name))
+                        })?),
+                    };
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(Part::Field(name.to_string(), width));
+                    chars = rest[close + 1..].chars();
+                }
+                '}' => return Err(OpError::pattern(s, "unmatched '}' (write '}}')")),
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+        Ok(NameTemplate { parts })
+    }
+}
+
+impl fmt::Display for NameTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => f.write_str(&text.replace('{', "{{").replace('}', "}}"))?,
+                Part::Field(name, None) => write!(f, "{{{name}}}")?,
+                Part::Field(name, Some(width)) => write!(f, "{{{name}:0{width}}}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl NameTemplate {
+    /// Parse a template (same as `str::parse`).
+    #[allow(dead_code)] // Public API
+    pub fn parse(s: &str) -> Result<Self, OpError> {
+        s.parse()
+    }
+
+    /// Names of the fields used, in order of appearance.
+    #[must_use]
+    #[allow(dead_code)] // Public API
+    pub fn fields(&self) -> Vec<&str> {
+        self.parts
+            .iter()
+            .filter_map(|p| match p {
+                Part::Field(name, _) => Some(name.as_str()),
+                Part::Text(_) => None,
+            })
+            .collect()
+    }
+
+    /// Render with `fields`. A field with a width must hold an integer.
+    pub fn render(&self, fields: &NameFields) -> Result<String, OpError> {
+        let mut out = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => out.push_str(text),
+                Part::Field(name, width) => {
+                    let value = fields.get(name).ok_or_else(|| OpError::pattern(&self.to_string(), format!("unknown field {{{}}}", name)))?;
+                    match width {
+                        None => out.push_str(value),
+                        Some(width) => {
+                            let number: i64 = value
+                                .parse()
+                                .map_err(|_| OpError::pattern(&self.to_string(), format!("{{{}:0{}}} needs a number, got {:?}", name, width, value)))?;
+                            out.push_str(&format!("{:0w$}", number, w = *width));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    /// Match `text` against the template and return the field values, or
+    /// `None` if it doesn't match. Every field takes at least one character,
+    /// as few as possible; a field used twice must match the same text.
+    ///
+    /// ```ignore
+    /// let caps = NameTemplate::parse("{roll}_plate_v{version}")?.capture("A001C003_plate_v2");
+    /// // roll = "A001C003", version = "2"
+    /// ```
+    #[must_use]
+    #[allow(dead_code)] // Public API
+    pub fn capture(&self, text: &str) -> Option<NameFields> {
+        let mut fields = NameFields::new();
+        capture_parts(&self.parts, text, &mut fields).then_some(fields)
+    }
+}
+
+fn capture_parts(parts: &[Part], text: &str, fields: &mut NameFields) -> bool {
+    match parts.split_first() {
+        None => text.is_empty(),
+        Some((Part::Text(lit), rest)) => text.strip_prefix(lit.as_str()).is_some_and(|tail| capture_parts(rest, tail, fields)),
+        Some((Part::Field(name, _), rest)) => {
+            if let Some(known) = fields.get(name).cloned() {
+                return text.strip_prefix(known.as_str()).is_some_and(|tail| capture_parts(rest, tail, fields));
+            }
+            if text.is_empty() {
+                return false;
+            }
+            for (end, _) in text.char_indices().skip(1).chain([(text.len(), ' ')]) {
+                fields.insert(name.clone(), text[..end].to_string());
+                if capture_parts(rest, &text[end..], fields) {
+                    return true;
+                }
+            }
+            fields.remove(name);
+            false
+        }
+    }
+}
+
+impl Seq {
+    /// The name components templates can use, from the pattern
+    /// (`/in/A001C003_plate_v2.####.dpx`):
+    ///
+    /// - `dir`: the folder (`/in`)
+    /// - `prefix`: the name before the frame number, without its trailing
+    ///   separator (`A001C003_plate_v2`), and `sep`: that separator (`.`)
+    /// - `suffix`: between the frame number and the extension (empty here)
+    /// - `ext`: the extension, without the dot (`dpx`)
+    /// - `anchor1`, `anchor2`, ...: the other digit groups of the name, left
+    ///   to right (`001`, `003`, `2`)
+    #[must_use]
+    #[allow(dead_code)] // Public API
+    pub fn name_fields(&self) -> NameFields {
+        let path = Path::new(self.pattern());
+        let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        let (before, after) = split_placeholder(&name, self.padding).unwrap_or((&name, ""));
+        let prefix = before.trim_end_matches(['.', '_', '-']);
+        let (suffix, ext) = after.rsplit_once('.').unwrap_or((after, ""));
+
+        let mut fields = NameFields::new();
+        fields.insert("dir".into(), path.parent().map(|d| d.to_string_lossy().into_owned()).unwrap_or_default());
+        fields.insert("prefix".into(), prefix.to_string());
+        fields.insert("sep".into(), before[prefix.len()..].to_string());
+        fields.insert("suffix".into(), suffix.to_string());
+        fields.insert("ext".into(), ext.to_string());
+        let anchors = before.split(|c: char| !c.is_ascii_digit()).chain(after.split(|c: char| !c.is_ascii_digit())).filter(|g| !g.is_empty());
+        for (i, anchor) in anchors.enumerate() {
+            fields.insert(format!("anchor{}", i + 1), anchor.to_string());
+        }
+        fields
+    }
+
+    /// Rename every present frame to the name `template` renders, in one
+    /// checked plan per sequence (see [`Seq::move_to`]).
+    ///
+    /// Fields come from [`name_fields`](Self::name_fields), overridden by
+    /// `fields` (captured tokens, `show`, `shot`, ...). The template must use
+    /// `{frame}` once; a relative result stays in the sequence's folder.
+    ///
+    /// # Example
+    /// ```ignore
+    /// // A001C003_plate_v2.####.dpx -> abc_010_plate_v002.####.dpx
+    /// let mut fields = NameTemplate::parse("{roll}_plate_v{version}")?.capture("A001C003_plate_v2").unwrap_or_default();
+    /// fields.insert("show".into(), "abc".into());
+    /// fields.insert("shot".into(), "010".into());
+    /// let to = NameTemplate::parse("{show}_{shot}_plate_v{version:03}.{frame:04}.{ext}")?;
+    /// let report = seq.rename_with(&to, &fields, &TransferOptions::default())?;
+    /// ```
+    #[allow(dead_code)] // Public API
+    pub fn rename_with(&self, template: &NameTemplate, fields: &NameFields, opts: &TransferOptions) -> Result<OpReport, OpError> {
+        let dest = self.rendered_pattern(template, fields)?;
+        self.transfer(OpKind::Rename, &dest, opts)
+    }
+
+    /// The destination pattern `template` renders for this sequence, with
+    /// `####` (or `#` when unpadded) for the frame, resolved against the
+    /// sequence's folder.
+    #[allow(dead_code)] // Public API
+    pub fn rendered_pattern(&self, template: &NameTemplate, fields: &NameFields) -> Result<String, OpError> {
+        let frames: Vec<Option<usize>> = template
+            .parts
+            .iter()
+            .filter_map(|p| match p {
+                Part::Field(name, width) if name == "frame" => Some(*width),
+                _ => None,
+            })
+            .collect();
+        let [width] = frames[..] else {
+            return Err(OpError::pattern(&template.to_string(), "the template must use {frame} exactly once"));
+        };
+        let mut all = self.name_fields();
+        all.extend(fields.iter().map(|(k, v)| (k.clone(), v.clone())));
+        if let Some((name, _)) = all.iter().find(|(name, value)| template.fields().contains(&name.as_str()) && value.contains(['#', '@'])) {
+            return Err(OpError::pattern(&template.to_string(), format!("field {{{}}} holds a frame placeholder character (# or @)", name)));
+        }
+        let width = width.unwrap_or(self.padding);
+        all.insert("frame".into(), if width >= 2 { "#".repeat(width) } else { "#".into() });
+        let frame = template.parts.iter().position(|p| matches!(p, Part::Field(name, _) if name == "frame")).unwrap_or_default();
+        let mut unpadded = template.clone();
+        unpadded.parts[frame] = Part::Field("frame".into(), None);
+        let rendered = unpadded.render(&all)?;
+        let dir = Path::new(self.pattern()).parent().unwrap_or(Path::new(""));
+        Ok(dir.join(rendered).to_string_lossy().into_owned())
+    }
+}
@@ -0,0 +1,94 @@
+//! Shifting the frame numbers of a sequence in place.
+
+use super::{move_file, run_in_order, run_parallel, FileOp, OnExists, OpError, OpKind, OpReport, OpStatus, TransferOptions};
+use crate::core::seq::{format_frame, Seq};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+
+/// How [`Seq::renumber`] maps frame numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Renumber {
+    /// Shift every frame by this amount (`-24`).
+    Offset(i64),
+    /// Shift so the first frame gets this number (`1001`).
+    Start(i64),
+}
+
+impl Renumber {
+    /// The shift this gives for `seq`.
+    pub(super) fn offset(self, seq: &Seq) -> i64 {
+        match self {
+            Renumber::Offset(offset) => offset,
+            Renumber::Start(start) => start.saturating_sub(seq.start),
+        }
+    }
+}
+
+impl Seq {
+    /// Rename every present frame to its shifted number, in place
+    /// (`plate.0001-0240` to `plate.1001-1240` with `Renumber::Start(1001)`).
+    ///
+    /// When old and new frames overlap, frames are renamed one at a time in an
+    /// order that never overwrites a frame before it has moved: highest first
+    /// when shifting up, lowest first when shifting down. After a failure the
+    /// rest is left alone, and [`OpReport::undo`] puts the renamed frames back.
+    /// Without overlap frames are renamed in parallel. Existing files outside
+    /// the sequence follow `opts.on_exists`. Frames may go negative but must
+    /// fit the padding, which counts a minus sign as in [`repad`](Self::repad).
+    ///
+    /// # Example
+    /// ```ignore
+    /// let report = seq.renumber(Renumber::Start(1001), &TransferOptions::default())?;
+    /// if !report.is_ok() {
+    ///     report.undo();
+    /// }
+    /// ```
+    #[allow(dead_code)] // Public API
+    pub fn renumber(&self, how: Renumber, opts: &TransferOptions) -> Result<OpReport, OpError> {
+        let offset = how.offset(self);
+        if offset == 0 {
+            return Ok(OpReport { ops: Vec::new(), dry_run: opts.dry_run });
+        }
+        let sources: HashSet<PathBuf> = self.paths().into_iter().collect();
+        let mut ops = Vec::with_capacity(self.len());
+        let mut existing = Vec::new();
+        let mut overlap = false;
+        for (frame, from) in self.frames() {
+            let new = frame.checked_add(offset).ok_or_else(|| OpError::pattern(self.pattern(), format!("frame {frame} shifted by {offset} is out of range")))?;
+            if self.padding >= 2 && new.to_string().len() > self.padding {
+                return Err(OpError::pattern(self.pattern(), format!("frame {new} does not fit {} digits", self.padding)));
+            }
+            let to = PathBuf::from(format_frame(self.pattern(), self.padding, new));
+            let mut status = OpStatus::Planned;
+            if sources.contains(&to) {
+                overlap = true;
+            } else if fs::symlink_metadata(&to).is_ok() {
+                match opts.on_exists {
+                    OnExists::Fail => existing.push(to.clone()),
+                    OnExists::Skip => status = OpStatus::Skipped,
+                    OnExists::Overwrite => {}
+                }
+            }
+            ops.push(FileOp { kind: OpKind::Rename, frame, from, to, status });
+        }
+        if !existing.is_empty() {
+            return Err(OpError::Exists { paths: existing });
+        }
+        if offset > 0 {
+            ops.reverse();
+        }
+        if opts.dry_run {
+            return Ok(OpReport { ops, dry_run: true });
+        }
+
+        let overwrite = opts.on_exists == OnExists::Overwrite;
+        if overlap {
+            // A frame's new name is free only once the frame holding it has moved
+            run_in_order(&mut ops, opts.journal.as_ref(), |op| move_file(&op.from, &op.to, overwrite));
+        } else {
+            run_parallel(&mut ops, opts.journal.as_ref(), |op| move_file(&op.from, &op.to, overwrite));
+        }
+        Ok(OpReport { ops, dry_run: false })
+    }
+}
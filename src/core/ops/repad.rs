@@ -0,0 +1,68 @@
+//! Changing the frame padding of a sequence on disk.
+
+use super::{move_file, run_parallel, split_placeholder, FileOp, OnExists, OpError, OpKind, OpReport, OpStatus, TransferOptions};
+use crate::core::seq::{format_frame, Seq};
+use std::fs;
+use std::path::PathBuf;
+
+impl Seq {
+    /// Rename every present frame to a new padding (`img_1.png` to
+    /// `img_0001.png` with `repad(4)`), in place. `0` or `1` means unpadded
+    /// (`@`).
+    ///
+    /// Every frame must fit the new width, or nothing is renamed. As in the
+    /// pattern, the width counts a minus sign: frame `-5` becomes `-005` at
+    /// padding 4. Old and new names of different frames never collide, so
+    /// frames are renamed in parallel; frames already at the new width are
+    /// left out of the report. Existing files outside the sequence follow
+    /// `opts.on_exists`.
+    ///
+    /// Returns the sequence under its new names (what it would be, with
+    /// `dry_run`) along with the report.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let (seq, report) = seq.repad(4, &TransferOptions::default())?;
+    /// assert_eq!(seq.padding, 4);
+    /// ```
+    #[allow(dead_code)] // Public API
+    pub fn repad(&self, padding: usize, opts: &TransferOptions) -> Result<(Seq, OpReport), OpError> {
+        let padding = if padding < 2 { 0 } else { padding };
+        if let Some(&wide) = self.indices.iter().find(|f| padding >= 2 && f.to_string().len() > padding) {
+            return Err(OpError::pattern(self.pattern(), format!("frame {wide} does not fit {padding} digits")));
+        }
+        let pattern = repad_pattern(self.pattern(), self.padding, padding).ok_or_else(|| OpError::pattern(self.pattern(), "no frame placeholder"))?;
+        let mut ops = Vec::with_capacity(self.len());
+        let mut existing = Vec::new();
+        for (frame, from) in self.frames() {
+            let to = PathBuf::from(format_frame(&pattern, padding, frame));
+            if to == from {
+                continue;
+            }
+            let mut status = OpStatus::Planned;
+            if fs::symlink_metadata(&to).is_ok() {
+                match opts.on_exists {
+                    OnExists::Fail => existing.push(to.clone()),
+                    OnExists::Skip => status = OpStatus::Skipped,
+                    OnExists::Overwrite => {}
+                }
+            }
+            ops.push(FileOp { kind: OpKind::Rename, frame, from, to, status });
+        }
+        if !existing.is_empty() {
+            return Err(OpError::Exists { paths: existing });
+        }
+        if !opts.dry_run {
+            let overwrite = opts.on_exists == OnExists::Overwrite;
+            run_parallel(&mut ops, opts.journal.as_ref(), |op| move_file(&op.from, &op.to, overwrite));
+        }
+        Ok((self.with_pattern(pattern, padding), OpReport { ops, dry_run: opts.dry_run }))
+    }
+}
+
+/// `pattern` with its frame placeholder replaced by the one for `new_padding`.
+fn repad_pattern(pattern: &str, padding: usize, new_padding: usize) -> Option<String> {
+    let (before, after) = split_placeholder(pattern, padding)?;
+    let new = if new_padding >= 2 { "#".repeat(new_padding) } else { "@".to_string() };
+    Some(format!("{before}{new}{after}"))
+}
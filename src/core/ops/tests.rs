@@ -0,0 +1,552 @@
+use super::*;
+use crate::core::{get_seqs, Seq};
+use std::fs;
+use tempfile::tempdir;
+
+/// Write frames `name.NNNN.exr` with their frame number as content, and scan them back.
+fn make_seq(dir: &Path, name: &str, frames: &[i64]) -> Seq {
+    for &f in frames {
+        fs::write(dir.join(format!("{name}.{f:04}.exr")), f.to_string()).expect("write frame");
+    }
+    let seqs = get_seqs(dir, false, Some(&format!("{name}.*")), 2).expect("scan");
+    seqs.into_iter().next().expect("one sequence")
+}
+
+#[test]
+fn test_dest_pattern() {
+    let tmp = tempdir().expect("tempdir");
+    let seq = make_seq(tmp.path(), "comp", &[1, 2]);
+    let out = tmp.path().join("out");
+    let out = out.to_string_lossy();
+    assert_eq!(dest_pattern(&seq, &format!("{out}/c.#####.exr")).unwrap(), (format!("{out}/c.#####.exr"), 5));
+    assert_eq!(dest_pattern(&seq, &format!("{out}/c.#.exr")).unwrap(), (format!("{out}/c.@.exr"), 0));
+    assert_eq!(dest_pattern(&seq, &format!("{out}/c.@.exr")).unwrap(), (format!("{out}/c.@.exr"), 0));
+    assert_eq!(dest_pattern(&seq, &format!("{out}/")).unwrap(), (format!("{out}/comp.####.exr"), 4));
+    assert!(matches!(dest_pattern(&seq, &format!("{out}/c.exr")), Err(OpError::InvalidPattern { .. })));
+    assert!(matches!(dest_pattern(&seq, &format!("{out}/c_##.##.exr")), Err(OpError::InvalidPattern { .. })));
+}
+
+#[test]
+fn test_copy_and_move() {
+    let tmp = tempdir().expect("tempdir");
+    let seq = make_seq(tmp.path(), "plate", &[1, 2, 4]);
+    let dest = format!("{}/pub/v1/plate.#####.exr", tmp.path().display());
+
+    // Dry run plans without touching anything
+    let plan = seq.copy_to(&dest, &TransferOptions { dry_run: true, ..Default::default() }).unwrap();
+    assert_eq!(plan.succeeded(), vec![1, 2, 4]);
+    assert!(plan.ops.iter().all(|op| op.status == OpStatus::Planned));
+    assert!(!tmp.path().join("pub").exists());
+
+    let report = seq.copy_to(&dest, &TransferOptions::default()).unwrap();
+    assert!(report.is_ok());
+    assert_eq!(fs::read_to_string(tmp.path().join("pub/v1/plate.00004.exr")).unwrap(), "4");
+    let leftovers = fs::read_dir(tmp.path().join("pub/v1")).unwrap().filter(|e| e.as_ref().unwrap().file_name().to_string_lossy().starts_with('.')).count();
+    assert_eq!(leftovers, 0, "no temp files left behind");
+    assert_eq!(
+        fs::metadata(tmp.path().join("plate.0001.exr")).unwrap().modified().unwrap(),
+        fs::metadata(tmp.path().join("pub/v1/plate.00001.exr")).unwrap().modified().unwrap()
+    );
+
+    // Existing destinations: fail refuses up front, skip leaves them, overwrite replaces
+    fs::write(tmp.path().join("pub/v1/plate.00002.exr"), "old").unwrap();
+    assert!(matches!(seq.copy_to(&dest, &TransferOptions::default()), Err(OpError::Exists { paths }) if paths.len() == 3));
+    let skip = seq.copy_to(&dest, &TransferOptions { on_exists: OnExists::Skip, ..Default::default() }).unwrap();
+    assert_eq!(skip.skipped(), vec![1, 2, 4]);
+    assert_eq!(fs::read_to_string(tmp.path().join("pub/v1/plate.00002.exr")).unwrap(), "old");
+    seq.copy_to(&dest, &TransferOptions { on_exists: OnExists::Overwrite, ..Default::default() }).unwrap();
+    assert_eq!(fs::read_to_string(tmp.path().join("pub/v1/plate.00002.exr")).unwrap(), "2");
+
+    // Moving into a folder keeps the names and empties the source
+    let archive = format!("{}/archive/", tmp.path().display());
+    let moved = seq.move_to(&archive, &TransferOptions::default()).unwrap();
+    assert_eq!(moved.succeeded(), vec![1, 2, 4]);
+    assert!(!tmp.path().join("plate.0001.exr").exists());
+    assert_eq!(fs::read_to_string(tmp.path().join("archive/plate.0002.exr")).unwrap(), "2");
+
+    // Onto itself is a conflict
+    let back = get_seqs(tmp.path().join("archive"), false, None, 2).unwrap().remove(0);
+    assert!(matches!(back.copy_to(&archive, &TransferOptions::default()), Err(OpError::Conflict { .. })));
+}
+
+#[test]
+fn test_placeholders_in_dest_folder() {
+    let tmp = tempdir().expect("tempdir");
+    let seq = make_seq(tmp.path(), "plate", &[1, 2]);
+
+    // `#` and `@` in folder names are not frame placeholders
+    let into = tmp.path().join("u@h/v####/");
+    let report = seq.copy_to(&format!("{}/", into.display()), &TransferOptions::default()).unwrap();
+    assert!(report.is_ok());
+    assert_eq!(listing(&into), vec!["plate.0001.exr", "plate.0002.exr"]);
+    let renamed = tmp.path().join("u@h/v####/#1");
+    seq.copy_to(&format!("{}/p_@.exr", renamed.display()), &TransferOptions::default()).unwrap();
+    assert_eq!(listing(&renamed), vec!["p_1.exr", "p_2.exr"]);
+
+    // Unpadded frames keep the `@` of their folder too
+    let src = tmp.path().join("src@");
+    fs::create_dir(&src).unwrap();
+    for f in [1, 2, 3] {
+        fs::write(src.join(format!("img_{f}.png")), f.to_string()).unwrap();
+    }
+    let unpadded = get_seqs(&src, false, None, 2).unwrap().remove(0);
+    assert!(unpadded.pattern().ends_with("src@/img_@.png"), "{}", unpadded.pattern());
+    let dest = tmp.path().join("u@h/");
+    let plan = unpadded.copy_to(&format!("{}/", dest.display()), &TransferOptions { dry_run: true, ..Default::default() }).unwrap();
+    assert_eq!(plan.ops[0].to, dest.join("img_1.png"));
+    unpadded.copy_to(&format!("{}/", dest.display()), &TransferOptions::default()).unwrap();
+    assert_eq!(fs::read_to_string(dest.join("img_3.png")).unwrap(), "3");
+}
+
+/// Names in `dir`, sorted.
+fn listing(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir).unwrap().map(|e| e.unwrap().file_name().to_string_lossy().into_owned()).collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_renumber() {
+    let tmp = tempdir().expect("tempdir");
+    let seq = make_seq(tmp.path(), "p", &[1, 2, 3, 5]);
+
+    // Overlapping shift up: highest frame first, contents follow their frame
+    let report = seq.renumber(Renumber::Offset(2), &TransferOptions::default()).unwrap();
+    assert_eq!(report.ops.iter().map(|op| op.frame).collect::<Vec<_>>(), vec![5, 3, 2, 1]);
+    assert!(report.is_ok());
+    assert_eq!(listing(tmp.path()), vec!["p.0003.exr", "p.0004.exr", "p.0005.exr", "p.0007.exr"]);
+    assert_eq!(fs::read_to_string(tmp.path().join("p.0003.exr")).unwrap(), "1");
+
+    // Undo puts every frame back
+    assert!(report.undo().is_ok());
+    assert_eq!(listing(tmp.path()), vec!["p.0001.exr", "p.0002.exr", "p.0003.exr", "p.0005.exr"]);
+
+    // Rebase without overlap, then back down
+    let report = seq.renumber(Renumber::Start(1001), &TransferOptions::default()).unwrap();
+    assert_eq!(report.succeeded().len(), 4);
+    assert_eq!(fs::read_to_string(tmp.path().join("p.1005.exr")).unwrap(), "5");
+    let rebased = get_seqs(tmp.path(), false, None, 2).unwrap().remove(0);
+    rebased.renumber(Renumber::Offset(-1000), &TransferOptions::default()).unwrap();
+    assert_eq!(listing(tmp.path()), vec!["p.0001.exr", "p.0002.exr", "p.0003.exr", "p.0005.exr"]);
+
+    // Below zero the minus sign takes a digit of the padding
+    let report = seq.renumber(Renumber::Offset(-2), &TransferOptions::default()).unwrap();
+    assert_eq!(listing(tmp.path()), vec!["p.-001.exr", "p.0000.exr", "p.0001.exr", "p.0003.exr"]);
+    assert!(report.undo().is_ok());
+    assert!(matches!(seq.renumber(Renumber::Start(-1000), &TransferOptions::default()), Err(OpError::InvalidPattern { .. })), "-1000 takes 5 characters");
+    assert!(matches!(seq.renumber(Renumber::Offset(i64::MAX), &TransferOptions::default()), Err(OpError::InvalidPattern { .. })), "overflow");
+    assert!(matches!(seq.renumber(Renumber::Start(9999), &TransferOptions::default()), Err(OpError::InvalidPattern { .. })), "padding");
+    let plan = seq.renumber(Renumber::Offset(1), &TransferOptions { dry_run: true, ..Default::default() }).unwrap();
+    assert_eq!(plan.ops.len(), 4);
+    assert_eq!(listing(tmp.path()).len(), 4);
+}
+
+#[test]
+fn test_renumber_stops_after_failure() {
+    let tmp = tempdir().expect("tempdir");
+    let seq = make_seq(tmp.path(), "p", &[1, 2, 3]);
+    // Frame 3 can't take the name of a non-empty folder: 2 and 1 must not move onto 3 and 2
+    fs::create_dir(tmp.path().join("p.0004.exr")).unwrap();
+    fs::write(tmp.path().join("p.0004.exr/keep"), "").unwrap();
+    let report = seq.renumber(Renumber::Offset(1), &TransferOptions { on_exists: OnExists::Overwrite, ..Default::default() }).unwrap();
+    assert_eq!(report.failed(), vec![3, 2, 1]);
+    assert!(report.undo().ops.is_empty());
+    for f in 1..=3 {
+        assert_eq!(fs::read_to_string(tmp.path().join(format!("p.{f:04}.exr"))).unwrap(), f.to_string());
+    }
+}
+
+#[test]
+fn test_repad() {
+    let tmp = tempdir().expect("tempdir");
+    for f in [1, 2, 10, 240] {
+        fs::write(tmp.path().join(format!("img_{f}.png")), f.to_string()).unwrap();
+    }
+    let seq = get_seqs(tmp.path(), false, None, 2).unwrap().remove(0);
+    assert_eq!(seq.padding, 0);
+
+    assert!(matches!(seq.repad(2, &TransferOptions::default()), Err(OpError::InvalidPattern { .. })), "240 needs 3 digits");
+    let (planned, plan) = seq.repad(4, &TransferOptions { dry_run: true, ..Default::default() }).unwrap();
+    assert_eq!((planned.padding, plan.ops.len()), (4, 4));
+    assert_eq!(listing(tmp.path()).len(), 4);
+
+    let (padded, report) = seq.repad(4, &TransferOptions::default()).unwrap();
+    assert!(report.is_ok());
+    assert!(padded.pattern().ends_with("img_####.png"));
+    assert_eq!(padded.indices, seq.indices);
+    assert_eq!(listing(tmp.path()), vec!["img_0001.png", "img_0002.png", "img_0010.png", "img_0240.png"]);
+    assert_eq!(fs::read_to_string(tmp.path().join("img_0240.png")).unwrap(), "240");
+    assert_eq!(padded.first_file(), tmp.path().join("img_0001.png").to_string_lossy());
+
+    // Back to unpadded, then frames already at the new width are left out
+    let (unpadded, _) = padded.repad(0, &TransferOptions::default()).unwrap();
+    assert!(unpadded.pattern().ends_with("img_@.png"));
+    assert_eq!(listing(tmp.path()), vec!["img_1.png", "img_10.png", "img_2.png", "img_240.png"]);
+    let (_, report) = unpadded.repad(3, &TransferOptions::default()).unwrap();
+    assert_eq!(report.ops.iter().map(|op| op.frame).collect::<Vec<_>>(), vec![1, 2, 10]);
+    assert_eq!(listing(tmp.path()), vec!["img_001.png", "img_002.png", "img_010.png", "img_240.png"]);
+}
+
+#[test]
+fn test_repad_negative_frames() {
+    let tmp = tempdir().expect("tempdir");
+    for name in ["cam.-5.abc", "cam.-10.abc", "cam.3.abc"] {
+        fs::write(tmp.path().join(name), "").unwrap();
+    }
+    // Scans only see unsigned frames: build the sequence a negative-aware tool would hand over
+    let pattern = tmp.path().join("cam.@.abc").to_string_lossy().into_owned();
+    let seq: Seq = serde_json::from_value(serde_json::json!({
+        "indices": [-10, -5, 3], "missed": [], "start": -10, "end": 3, "padding": 0,
+        "pattern": pattern, "first_file_path": pattern.replace('@', "-10"),
+    }))
+    .unwrap();
+
+    assert!(matches!(seq.repad(2, &TransferOptions::default()), Err(OpError::InvalidPattern { .. })), "-10 takes 3 characters");
+    let (padded, report) = seq.repad(4, &TransferOptions::default()).unwrap();
+    assert!(report.is_ok());
+    assert_eq!(padded.get_file(-5), Some(tmp.path().join("cam.-005.abc").to_string_lossy().into_owned()));
+    assert_eq!(listing(tmp.path()), vec!["cam.-005.abc", "cam.-010.abc", "cam.0003.abc"]);
+}
+
+#[test]
+fn test_name_template() {
+    let t = NameTemplate::parse("{show}_{shot}_plate_v{version:03}.{frame:04}.{{x}}").unwrap();
+    assert_eq!(t.fields(), vec!["show", "shot", "version", "frame"]);
+    assert_eq!(t.to_string(), "{show}_{shot}_plate_v{version:03}.{frame:04}.{{x}}");
+    let fields: NameFields = [("show", "abc"), ("shot", "010"), ("version", "2"), ("frame", "7")].into_iter().map(|(k, v)| (k.into(), v.into())).collect();
+    assert_eq!(t.render(&fields).unwrap(), "abc_010_plate_v002.0007.{x}");
+    assert!(t.render(&NameFields::new()).is_err(), "unknown field");
+
+    for bad in ["{show", "show}", "{}", "{1x}", "{v:3}", "{v:0x}"] {
+        assert!(NameTemplate::parse(bad).is_err(), "{bad}");
+    }
+
+    let caps = NameTemplate::parse("{roll}_plate_v{version}").unwrap().capture("A001C003_plate_v2").unwrap();
+    assert_eq!((caps["roll"].as_str(), caps["version"].as_str()), ("A001C003", "2"));
+    let caps = NameTemplate::parse("{a}_{b}").unwrap().capture("x_y_z").unwrap();
+    assert_eq!((caps["a"].as_str(), caps["b"].as_str()), ("x", "y_z"));
+    assert_eq!(NameTemplate::parse("{a}_{a}").unwrap().capture("x_y"), None);
+    assert_eq!(NameTemplate::parse("{a}_plate").unwrap().capture("_plate"), None);
+}
+
+#[test]
+fn test_rename_with() {
+    let tmp = tempdir().expect("tempdir");
+    for f in [1, 2, 3] {
+        fs::write(tmp.path().join(format!("A001C003_plate_v2.{f:04}.dpx")), f.to_string()).unwrap();
+    }
+    let seq = get_seqs(tmp.path(), false, None, 2).unwrap().remove(0);
+    let names = seq.name_fields();
+    assert_eq!(names["prefix"], "A001C003_plate_v2");
+    assert_eq!((names["sep"].as_str(), names["suffix"].as_str(), names["ext"].as_str()), (".", "", "dpx"));
+    assert_eq!((names["anchor1"].as_str(), names["anchor2"].as_str(), names["anchor3"].as_str()), ("001", "003", "2"));
+
+    let mut fields = NameTemplate::parse("{roll}_plate_v{version}").unwrap().capture(&names["prefix"]).unwrap();
+    fields.insert("show".into(), "abc".into());
+    fields.insert("shot".into(), "010".into());
+    let to = NameTemplate::parse("{show}_{shot}_plate_v{version:03}.{frame:05}.{ext}").unwrap();
+    let dest = seq.rendered_pattern(&to, &fields).unwrap();
+    assert_eq!(dest, tmp.path().join("abc_010_plate_v002.#####.dpx").to_string_lossy());
+
+    let plan = seq.rename_with(&to, &fields, &TransferOptions { dry_run: true, ..Default::default() }).unwrap();
+    assert_eq!(plan.ops[0].kind, OpKind::Rename);
+    assert_eq!(listing(tmp.path()).len(), 3);
+    assert!(seq.rename_with(&to, &fields, &TransferOptions::default()).unwrap().is_ok());
+    assert_eq!(listing(tmp.path()), vec!["abc_010_plate_v002.00001.dpx", "abc_010_plate_v002.00002.dpx", "abc_010_plate_v002.00003.dpx"]);
+
+    let no_frame = NameTemplate::parse("{prefix}.{ext}").unwrap();
+    assert!(matches!(seq.rendered_pattern(&no_frame, &NameFields::new()), Err(OpError::InvalidPattern { .. })));
+    let hash: NameFields = [("prefix".to_string(), "a#b".to_string())].into();
+    let keep = NameTemplate::parse("{prefix}.{frame}.{ext}").unwrap();
+    assert!(seq.rendered_pattern(&keep, &NameFields::new()).is_ok());
+    assert!(matches!(seq.rendered_pattern(&keep, &hash), Err(OpError::InvalidPattern { message, .. }) if message.contains("placeholder")));
+}
+
+#[test]
+fn test_remove() {
+    let tmp = tempdir().expect("tempdir");
+    let seq = make_seq(tmp.path(), "r", &[1, 2, 3, 4, 5]);
+    let trash = tmp.path().join("trash");
+    let opts = TransferOptions::default();
+
+    let plan = seq.remove(&FrameSelection::Slice("::2".parse().unwrap()), &RemoveMode::Delete, &TransferOptions { dry_run: true, ..opts.clone() }).unwrap();
+    assert_eq!(plan.succeeded(), vec![1, 3, 5]);
+    assert_eq!(plan.ops[0].to_string(), format!("delete {}", tmp.path().join("r.0001.exr").display()));
+    assert!(matches!(seq.remove(&FrameSelection::Frames(vec![2, 9]), &RemoveMode::Delete, &opts), Err(OpError::Conflict { .. })));
+
+    // Trash and back
+    let report = seq.remove(&FrameSelection::Frames(vec![4, 2]), &RemoveMode::Trash(trash.clone()), &opts).unwrap();
+    assert_eq!(report.succeeded(), vec![2, 4]);
+    assert_eq!(listing(&trash), vec!["r.0002.exr", "r.0004.exr"]);
+    assert!(matches!(seq.remove(&FrameSelection::All, &RemoveMode::Delete, &opts), Err(OpError::Changed { missing, .. }) if missing == vec![2, 4]));
+    report.undo();
+
+    let seq = make_seq(tmp.path(), "r", &[1, 2, 3, 4, 5]);
+    let report = seq.remove(&FrameSelection::All, &RemoveMode::Delete, &opts).unwrap();
+    assert!(report.is_ok());
+    assert_eq!(listing(tmp.path()), vec!["trash"]);
+}
+
+#[test]
+fn test_remove_refuses_changed_seq() {
+    let tmp = tempdir().expect("tempdir");
+    let seq = make_seq(tmp.path(), "r", &[1, 2, 3]);
+    fs::write(tmp.path().join("r.0004.exr"), "4").unwrap();
+    fs::write(tmp.path().join("r.00005.exr"), "").unwrap(); // not a frame of r.####.exr
+    let err = seq.remove(&FrameSelection::All, &RemoveMode::Delete, &TransferOptions::default()).unwrap_err();
+    assert!(matches!(&err, OpError::Changed { added, .. } if *added == vec![4]));
+    assert!(err.to_string().contains("new frames 4"));
+    assert_eq!(listing(tmp.path()).len(), 5);
+
+    // Rewritten frames count as changed when the scan kept metadata
+    fs::remove_file(tmp.path().join("r.0004.exr")).unwrap();
+    fs::remove_file(tmp.path().join("r.00005.exr")).unwrap();
+    let opts = crate::core::ScanOptions { with_metadata: true, ..Default::default() };
+    let seq = crate::core::get_seqs_with(tmp.path(), false, Some("r.*"), 2, &opts).unwrap().remove(0);
+    fs::write(tmp.path().join("r.0002.exr"), "a newer version").unwrap();
+    let err = seq.remove(&FrameSelection::All, &RemoveMode::Delete, &TransferOptions::default()).unwrap_err();
+    assert!(matches!(err, OpError::Changed { modified, .. } if modified == vec![2]));
+}
+
+#[test]
+fn test_fill_gaps() {
+    let tmp = tempdir().expect("tempdir");
+    let seq = make_seq(tmp.path(), "g", &[1, 4, 5, 9]);
+    let frame = |f: i64| tmp.path().join(format!("g.{f:04}.exr"));
+    let content = |f: i64| fs::read_to_string(frame(f)).unwrap();
+    let strategy = |source, method| FillStrategy { source, method };
+
+    // Hold: every gap frame shows the frame before it
+    let report = seq.fill_gaps(&strategy(FillSource::Previous, FillMethod::Copy), &TransferOptions::default()).unwrap();
+    assert_eq!(report.succeeded(), vec![2, 3, 6, 7, 8]);
+    assert_eq!((content(3), content(8)), ("1".to_string(), "5".to_string()));
+    assert!(matches!(seq.fill_gaps(&strategy(FillSource::Next, FillMethod::Copy), &TransferOptions::default()), Err(OpError::Exists { .. })));
+    report.undo();
+    assert_eq!(listing(tmp.path()).len(), 4);
+
+    let plan = seq.fill_gaps(&strategy(FillSource::Nearest, FillMethod::Copy), &TransferOptions { dry_run: true, ..Default::default() }).unwrap();
+    let picked: Vec<PathBuf> = plan.ops.iter().map(|op| op.from.clone()).collect();
+    assert_eq!(picked, vec![frame(1), frame(4), frame(5), frame(5), frame(9)]);
+
+    #[cfg(unix)]
+    {
+        let report = seq.fill_gaps(&strategy(FillSource::Next, FillMethod::Symlink), &TransferOptions::default()).unwrap();
+        assert!(report.is_ok());
+        assert_eq!(fs::read_link(frame(2)).unwrap(), PathBuf::from("g.0004.exr"));
+        assert_eq!(content(6), "9");
+        report.undo();
+
+        let report = seq.fill_gaps(&strategy(FillSource::Previous, FillMethod::Hardlink), &TransferOptions::default()).unwrap();
+        use std::os::unix::fs::MetadataExt;
+        assert_eq!(fs::metadata(frame(7)).unwrap().ino(), fs::metadata(frame(5)).unwrap().ino());
+        report.undo();
+    }
+
+    let slate = tmp.path().join("missing.exr");
+    fs::write(&slate, "slate").unwrap();
+    let report = seq.fill_gaps(&strategy(FillSource::Placeholder(slate), FillMethod::Copy), &TransferOptions::default()).unwrap();
+    assert_eq!(report.ops.len(), 5);
+    assert_eq!(content(7), "slate");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_link_to() {
+    let tmp = tempdir().expect("tempdir");
+    let render = tmp.path().join("render");
+    fs::create_dir(&render).unwrap();
+    let seq = make_seq(&render, "comp", &[1, 2, 3]);
+    let publish = tmp.path().join("publish/v003");
+    let dest = format!("{}/comp.####.exr", publish.display());
+    let link = |f: i64| publish.join(format!("comp.{f:04}.exr"));
+    let opts = LinkOptions::default();
+
+    // Absolute symlinks, renumbered
+    let report = seq.link_to(&dest, LinkKind::Sym, &FrameMap::Renumber(Renumber::Start(1001)), &opts).unwrap();
+    assert!(report.is_ok(), "{report:?}");
+    let target = fs::read_link(link(1001)).unwrap();
+    assert!(target.is_absolute());
+    assert_eq!(fs::canonicalize(target).unwrap(), fs::canonicalize(render.join("comp.0001.exr")).unwrap());
+    assert_eq!(fs::read_to_string(link(1003)).unwrap(), "3");
+    report.undo();
+    assert_eq!(listing(&publish).len(), 0);
+
+    // Relative symlinks keep working when the whole tree moves
+    let relative = LinkOptions { relative: true, ..opts };
+    seq.link_to(&dest, LinkKind::Sym, &FrameMap::Keep, &relative).unwrap();
+    assert_eq!(fs::read_link(link(2)).unwrap(), PathBuf::from("../../render/comp.0002.exr"));
+    let moved = tempdir().expect("tempdir");
+    fs::rename(tmp.path().join("render"), moved.path().join("render")).unwrap();
+    fs::rename(tmp.path().join("publish"), moved.path().join("publish")).unwrap();
+    assert_eq!(fs::read_to_string(moved.path().join("publish/v003/comp.0002.exr")).unwrap(), "2");
+}
+
+#[test]
+fn test_link_kinds_and_conflicts() {
+    let tmp = tempdir().expect("tempdir");
+    let seq = make_seq(tmp.path(), "comp", &[1, 2]);
+    let out = tmp.path().join("out");
+    let opts = LinkOptions::default();
+
+    let hard = seq.link_to(&format!("{}/", out.display()), LinkKind::Hard, &FrameMap::Keep, &opts).unwrap();
+    assert!(hard.is_ok());
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        assert_eq!(fs::metadata(out.join("comp.0001.exr")).unwrap().ino(), fs::metadata(tmp.path().join("comp.0001.exr")).unwrap().ino());
+    }
+
+    // Clones where the file system can, copies elsewhere: the content is there either way
+    let map = FrameMap::Explicit([(2, 10)].into());
+    let report = seq.link_to(&format!("{}/c.####.exr", out.display()), LinkKind::Reflink, &map, &opts).unwrap();
+    assert!(report.is_ok());
+    assert!(matches!(report.ops[0].kind, OpKind::Reflink | OpKind::Copy));
+    assert_eq!(fs::read_to_string(out.join("c.0010.exr")).unwrap(), "2");
+
+    // Targets below zero are fine as long as they fit the padding, sign included
+    let down = FrameMap::Renumber(Renumber::Start(-1));
+    assert!(seq.link_to(&format!("{}/n.###.exr", out.display()), LinkKind::Hard, &down, &opts).unwrap().is_ok());
+    assert!(out.join("n.-01.exr").exists() && out.join("n.000.exr").exists());
+    let wide = FrameMap::Renumber(Renumber::Start(-100));
+    assert!(matches!(seq.link_to(&format!("{}/w.###.exr", out.display()), LinkKind::Hard, &wide, &opts), Err(OpError::InvalidPattern { .. })));
+
+    let own = format!("{}/comp.####.exr", tmp.path().display());
+    assert!(matches!(seq.link_to(&own, LinkKind::Sym, &FrameMap::Renumber(Renumber::Offset(1)), &opts), Err(OpError::Conflict { .. })));
+    let twice = FrameMap::Explicit([(1, 5), (2, 5)].into());
+    assert!(matches!(seq.link_to(&format!("{}/d.####.exr", out.display()), LinkKind::Sym, &twice, &opts), Err(OpError::Conflict { .. })));
+    let absent = FrameMap::Explicit([(7, 7)].into());
+    assert!(matches!(seq.link_to(&format!("{}/d.####.exr", out.display()), LinkKind::Sym, &absent, &opts), Err(OpError::Conflict { .. })));
+}
+
+#[test]
+fn test_journal_rollback() {
+    let tmp = tempdir().expect("tempdir");
+    let shots = tmp.path().join("shots");
+    fs::create_dir(&shots).unwrap();
+    let seq = make_seq(&shots, "p", &[1, 2, 3]);
+    let journal = Journal::open(tmp.path().join("ops.journal")).unwrap();
+    let opts = TransferOptions { journal: Some(journal.clone()), ..Default::default() };
+
+    seq.copy_to(&format!("{}/pub/", tmp.path().display()), &opts).unwrap();
+    seq.renumber(Renumber::Offset(1), &opts).unwrap();
+    assert_eq!(listing(&shots), vec!["p.0002.exr", "p.0003.exr", "p.0004.exr"]);
+    let entries = journal.replay().unwrap();
+    assert_eq!(entries.iter().map(|e| e.id).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5, 6]);
+    assert!(entries.iter().all(|e| e.op.status == OpStatus::Done && !e.interrupted && !e.undone));
+
+    // Dry run lists the reversing ops, last first, and touches nothing
+    let plan = journal.rollback(true).unwrap();
+    assert_eq!(plan.ops.len(), 6);
+    assert_eq!((plan.ops[0].kind, plan.ops[0].frame), (OpKind::Rename, 1));
+    assert_eq!(listing(&shots), vec!["p.0002.exr", "p.0003.exr", "p.0004.exr"]);
+
+    let report = journal.rollback(false).unwrap();
+    assert!(report.is_ok());
+    assert_eq!(listing(&shots), vec!["p.0001.exr", "p.0002.exr", "p.0003.exr"]);
+    assert_eq!(fs::read_to_string(shots.join("p.0001.exr")).unwrap(), "1");
+    assert!(listing(&tmp.path().join("pub")).is_empty());
+
+    // Undone ops stay undone; a reopened journal keeps numbering
+    assert!(journal.replay().unwrap().iter().all(|e| e.undone));
+    assert!(journal.rollback(false).unwrap().ops.is_empty());
+    let reopened = Journal::open(journal.path()).unwrap();
+    seq.copy_to(&format!("{}/pub/", tmp.path().display()), &TransferOptions { journal: Some(reopened.clone()), ..Default::default() }).unwrap();
+    assert_eq!(reopened.replay().unwrap().last().map(|e| e.id), Some(9));
+}
+
+#[test]
+fn test_journal_recovers_interrupted() {
+    let tmp = tempdir().expect("tempdir");
+    let (shots, archive) = (tmp.path().join("shots"), tmp.path().join("archive"));
+    fs::create_dir(&shots).unwrap();
+    fs::create_dir(&archive).unwrap();
+    make_seq(&shots, "p", &[1, 2, 3]);
+    let path = tmp.path().join("ops.journal");
+    let journal = Journal::open(&path).unwrap();
+    let mv = |f: i64| FileOp {
+        kind: OpKind::Move,
+        frame: f,
+        from: shots.join(format!("p.{f:04}.exr")),
+        to: archive.join(format!("p.{f:04}.exr")),
+        status: OpStatus::Planned,
+    };
+
+    // A move killed at various points: 1 finished, 2 moved but never recorded, 3 never moved
+    let id = journal.begin(&mv(1), None, None).unwrap();
+    fs::rename(&mv(1).from, &mv(1).to).unwrap();
+    journal.end(id, &Ok(()));
+    journal.begin(&mv(2), None, None).unwrap();
+    fs::rename(&mv(2).from, &mv(2).to).unwrap();
+    let temp = archive.join(format!("{TEMP_PREFIX}abc"));
+    journal.begin(&mv(3), Some(&temp), None).unwrap();
+    fs::write(&temp, "partial").unwrap();
+    // Another writer's temp file, not in this journal
+    let foreign = archive.join(format!("{TEMP_PREFIX}xyz"));
+    fs::write(&foreign, "busy").unwrap();
+    let mut file = fs::File::options().append(true).open(&path).unwrap();
+    std::io::Write::write_all(&mut file, br#"{"id":4,"event":"be"#).unwrap();
+
+    let journal = Journal::open(&path).unwrap();
+    let entries = journal.replay().unwrap();
+    let states: Vec<(i64, bool, &OpStatus)> = entries.iter().map(|e| (e.op.frame, e.interrupted, &e.op.status)).collect();
+    assert_eq!(states, vec![(1, false, &OpStatus::Done), (2, true, &OpStatus::Done), (3, true, &OpStatus::Failed("interrupted".into()))]);
+
+    let report = journal.rollback(false).unwrap();
+    assert_eq!(report.succeeded(), vec![2, 1]);
+    assert_eq!(listing(&shots), vec!["p.0001.exr", "p.0002.exr", "p.0003.exr"]);
+    assert!(!temp.exists(), "recorded temp file removed");
+    assert!(foreign.exists(), "unrecorded temp file left alone");
+}
+
+#[test]
+fn test_journal_restores_overwritten() {
+    let tmp = tempdir().expect("tempdir");
+    let (shots, out) = (tmp.path().join("shots"), tmp.path().join("out"));
+    fs::create_dir(&shots).unwrap();
+    fs::create_dir(&out).unwrap();
+    let seq = make_seq(&shots, "p", &[1, 2]);
+    fs::write(out.join("p.0001.exr"), "old").unwrap();
+    let journal = Journal::open(tmp.path().join("ops.journal")).unwrap();
+    let opts = TransferOptions { on_exists: OnExists::Overwrite, journal: Some(journal.clone()), ..Default::default() };
+
+    assert!(seq.copy_to(&format!("{}/", out.display()), &opts).unwrap().is_ok());
+    assert_eq!(fs::read_to_string(out.join("p.0001.exr")).unwrap(), "1");
+    let entries = journal.replay().unwrap();
+    let backup = entries[0].backup.clone().expect("overwrite backed up");
+    assert_eq!(fs::read_to_string(&backup).unwrap(), "old");
+    assert!(entries[1].backup.is_none());
+
+    let report = journal.rollback(false).unwrap();
+    assert!(report.is_ok());
+    assert_eq!(listing(&out), vec!["p.0001.exr"]);
+    assert_eq!(fs::read_to_string(out.join("p.0001.exr")).unwrap(), "old");
+    assert!(!backup.exists());
+    assert!(journal.replay().unwrap().iter().all(|e| e.undone));
+}
+
+#[test]
+fn test_journal_shared_by_two_writers() {
+    let tmp = tempdir().expect("tempdir");
+    let shots = tmp.path().join("shots");
+    fs::create_dir(&shots).unwrap();
+    let seq = make_seq(&shots, "p", &[1, 2]);
+    // Two opens of the same file, as two processes would have
+    let path = tmp.path().join("ops.journal");
+    let (a, b) = (Journal::open(&path).unwrap(), Journal::open(&path).unwrap());
+    let copy = |journal: &Journal, dest: &str| {
+        let opts = TransferOptions { journal: Some(journal.clone()), ..Default::default() };
+        seq.copy_to(&format!("{}/{}/", tmp.path().display(), dest), &opts).unwrap();
+    };
+    copy(&a, "a1");
+    copy(&b, "b1");
+    copy(&a, "a2");
+
+    let entries = a.replay().unwrap();
+    assert_eq!(entries.iter().map(|e| e.id).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5, 6], "ids unique across writers");
+    assert!(entries.iter().all(|e| e.op.status == OpStatus::Done && !e.interrupted));
+    let dests: Vec<_> = entries.iter().map(|e| e.op.to.parent().unwrap().file_name().unwrap().to_string_lossy().into_owned()).collect();
+    assert_eq!(dests, vec!["a1", "a1", "b1", "b1", "a2", "a2"]);
+
+    assert!(b.rollback(false).unwrap().is_ok());
+    assert!(["a1", "b1", "a2"].iter().all(|d| listing(&tmp.path().join(d)).is_empty()));
+}
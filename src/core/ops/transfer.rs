@@ -0,0 +1,132 @@
+//! Copying and moving whole sequences.
+
+use super::{copy_atomic, dest_pattern, move_file, run_parallel, FileOp, Journal, OpError, OpKind, OpReport, OpStatus};
+use crate::core::seq::{format_frame, Seq};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// What to do with a destination frame that already exists.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OnExists {
+    /// Refuse the whole transfer before touching anything ([`OpError::Exists`]).
+    #[default]
+    Fail,
+    /// Leave the existing frame alone ([`OpStatus::Skipped`]).
+    Skip,
+    /// Replace it.
+    Overwrite,
+}
+
+impl FromStr for OnExists {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "fail" | "error" => Ok(OnExists::Fail),
+            "skip" | "keep" => Ok(OnExists::Skip),
+            "overwrite" | "replace" => Ok(OnExists::Overwrite),
+            _ => Err(format!("unknown policy '{}' (expected fail, skip or overwrite)", s)),
+        }
+    }
+}
+
+impl fmt::Display for OnExists {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            OnExists::Fail => "fail",
+            OnExists::Skip => "skip",
+            OnExists::Overwrite => "overwrite",
+        })
+    }
+}
+
+/// Options of [`Seq::copy_to`], [`Seq::move_to`] and [`Seq::renumber`].
+#[derive(Debug, Clone, Default)]
+pub struct TransferOptions {
+    /// Destination frames that already exist.
+    pub on_exists: OnExists,
+    /// Only plan: report what would be done, touch nothing.
+    pub dry_run: bool,
+    /// Record every op in this journal, to roll the batch back later.
+    pub journal: Option<Journal>,
+}
+
+impl Seq {
+    /// Copy every present frame to `dest`, keeping frame numbers.
+    ///
+    /// `dest` is a pattern (`/publish/sh010/comp.####.exr`, `comp_v2.@.exr`;
+    /// the placeholder sets the new padding) or a folder ending in `/` (or an
+    /// existing one), which keeps the file names. Frames are copied in parallel,
+    /// each through a hidden temp file renamed into place, so readers never see
+    /// a half-written frame; mtimes are kept. Destination folders are created.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let report = seq.copy_to("/publish/sh010/comp.####.exr", &TransferOptions::default())?;
+    /// if !report.is_ok() {
+    ///     eprintln!("failed frames: {:?}", report.failed());
+    /// }
+    /// ```
+    #[allow(dead_code)] // Public API
+    pub fn copy_to(&self, dest: &str, opts: &TransferOptions) -> Result<OpReport, OpError> {
+        self.transfer(OpKind::Copy, dest, opts)
+    }
+
+    /// Move every present frame to `dest` (see [`copy_to`](Self::copy_to)).
+    /// Frames are renamed where possible and copied then removed across file
+    /// systems.
+    #[allow(dead_code)] // Public API
+    pub fn move_to(&self, dest: &str, opts: &TransferOptions) -> Result<OpReport, OpError> {
+        self.transfer(OpKind::Move, dest, opts)
+    }
+
+    pub(super) fn transfer(&self, kind: OpKind, dest: &str, opts: &TransferOptions) -> Result<OpReport, OpError> {
+        let mut ops = self.plan_transfer(kind, dest, opts.on_exists)?;
+        if !opts.dry_run {
+            let dirs: BTreeSet<PathBuf> = ops.iter().filter_map(|op| op.to.parent()).map(PathBuf::from).collect();
+            for dir in dirs.iter().filter(|d| !d.as_os_str().is_empty()) {
+                fs::create_dir_all(dir).map_err(|e| OpError::io(dir, e))?;
+            }
+            let overwrite = opts.on_exists == OnExists::Overwrite;
+            run_parallel(&mut ops, opts.journal.as_ref(), |op| match kind {
+                OpKind::Copy => copy_atomic(&op.from, &op.to, overwrite),
+                _ => move_file(&op.from, &op.to, overwrite),
+            });
+        }
+        Ok(OpReport { ops, dry_run: opts.dry_run })
+    }
+
+    /// One op per present frame; collisions checked against the disk as it is now.
+    fn plan_transfer(&self, kind: OpKind, dest: &str, on_exists: OnExists) -> Result<Vec<FileOp>, OpError> {
+        let (pattern, padding) = dest_pattern(self, dest)?;
+        let sources: HashSet<PathBuf> = self.paths().into_iter().collect();
+        let mut ops = Vec::with_capacity(self.len());
+        let mut existing = Vec::new();
+        for (frame, from) in self.frames() {
+            let to = PathBuf::from(format_frame(&pattern, padding, frame));
+            if to == from || (to.exists() && fs::canonicalize(&to).ok() == fs::canonicalize(&from).ok()) {
+                return Err(OpError::Conflict { path: to, message: "source and destination are the same file".into() });
+            }
+            if sources.contains(&to) {
+                return Err(OpError::Conflict { path: to, message: format!("frame {frame} would overwrite another frame of the source") });
+            }
+            let mut status = OpStatus::Planned;
+            if fs::symlink_metadata(&to).is_ok() {
+                match on_exists {
+                    OnExists::Fail => existing.push(to.clone()),
+                    OnExists::Skip => status = OpStatus::Skipped,
+                    OnExists::Overwrite => {}
+                }
+            }
+            ops.push(FileOp { kind, frame, from, to, status });
+        }
+        if !existing.is_empty() {
+            return Err(OpError::Exists { paths: existing });
+        }
+        Ok(ops)
+    }
+}
@@ -1,7 +1,7 @@
 //! Directory scanning and parallel sequence detection.
 //!
 //! This module implements the two-phase scanning algorithm:
-//! 1. **Phase 1**: Discover all subdirectories using jwalk (fast parallel walker)
+//! 1. **Phase 1**: Discover all subdirectories (parallel walk over an [`Fs`])
 //! 2. **Phase 2**: Process folders in parallel using rayon thread pool
 //!
 //! Each worker:
@@ -12,43 +12,326 @@
 //! The mask-based approach handles unpadded sequences correctly:
 //! `img_1.exr` through `img_100.exr` all have mask `img_@` and group together.
 
-use super::file::File;
+use super::archive::{list_archive, member_path, ArchiveKind};
+use super::cache::ScanCache;
+use super::error::{ScanError, ScanWarning};
+use super::file::{File, FrameInfo};
 use super::seq::Seq;
+use super::sort::{sort_seqs, SortKey};
+use super::ScanResult;
 // `indicatif` (progress UI) is a CLI-only dependency: a programmatic library
 // consumer must not pull it or have a bar drawn during a scan.
 #[cfg(feature = "cli")]
 use indicatif::{ProgressBar, ProgressStyle};
-use jwalk::WalkDir;
+use super::vfs::{FileKind, Fs, FsEntry, StdFs};
 use log::{debug, info, warn};
 use rayon::prelude::*;
 use rayon::ThreadPoolBuilder;
+use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicUsize;
-#[cfg(feature = "cli")]
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// Traversal controls shared by [`scan_dirs`], [`scan_files_with`] and [`get_seqs_with`].
+///
+/// Depths are counted like `find -mindepth/-maxdepth`: the root is depth 0 and
+/// the files directly inside it are depth 1, so `max_depth: Some(1)` is exactly
+/// the historic non-recursive scan.
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    /// Deepest file level to report. `None` = unlimited (bounded only by `recursive`).
+    pub max_depth: Option<usize>,
+    /// Shallowest file level to report (`0` and `1` both include the root's own files).
+    pub min_depth: usize,
+    /// Do not descend into directories that live on a different device than the root
+    /// (mount points, auto-mounted archive volumes). Unix only; ignored elsewhere.
+    pub same_file_system: bool,
+    /// Descend into symlinked directories and report symlinked files. Cycles are
+    /// detected by directory identity (device + inode), not by path.
+    pub follow_links: bool,
+    /// Which path a symlinked frame is reported under.
+    pub link_paths: LinkPaths,
+    /// Record size, mtime and inode per frame (see [`Seq::frame_info`]). A
+    /// symlinked frame reuses the stat that resolved it; any other frame costs
+    /// one [`Fs::metadata`] call, unless the listing already carried its
+    /// metadata ([`FsEntry::meta`]: [`MemFs`](super::MemFs) listings do,
+    /// [`StdFs`] ones don't).
+    pub with_metadata: bool,
+    /// Keep a per-root cache file in this directory and regroup only folders whose
+    /// mtime changed since the last scan (see [`ScanCache`]). `None` = no cache.
+    pub cache_dir: Option<PathBuf>,
+    /// Descend into `.zip` / `.tar` files: their members are reported as
+    /// `archive.zip!/member` paths, in place of the archive in sequence scans
+    /// and in addition to it in [`scan_files_with`]. Members count as being at
+    /// the archive's depth.
+    pub archives: bool,
+    /// Filesystem to scan. `None` = the local disk ([`StdFs`]).
+    pub fs: Option<Arc<dyn Fs>>,
+    /// Fail the scan on the first unreadable folder, archive or entry below the
+    /// root, instead of recording it in [`ScanResult::errors`] and moving on.
+    pub strict: bool,
+    /// Order of the returned sequences (see [`sort_seqs`]).
+    pub sort: SortKey,
+    /// Compare paths naturally (`shot2` before `shot10`) when sorting.
+    pub natural_sort: bool,
+}
+
+/// Grouping settings for [`group_paths`], which works on path lists instead of
+/// a walk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupOptions {
+    /// Glob filter, as the `mask` of [`get_seqs`]. `None` = every path.
+    pub mask: Option<String>,
+    /// Sequences with fewer frames are dropped (default 2: lone files are not sequences).
+    pub min_len: usize,
+}
+
+impl Default for GroupOptions {
+    fn default() -> Self {
+        GroupOptions { mask: None, min_len: 2 }
+    }
+}
+
+/// How a frame reached through a symlink is reported.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LinkPaths {
+    /// The path of the link itself (e.g. `/publish/shot/comp.0001.exr`).
+    #[default]
+    Link,
+    /// The fully resolved target (e.g. `/store/v003/comp.0001.exr`). Broken
+    /// links can't be resolved and keep their link path.
+    Target,
+}
+
+impl ScanOptions {
+    /// The filesystem scans go through.
+    pub(crate) fn fs(&self) -> &dyn Fs {
+        self.fs.as_deref().unwrap_or(&StdFs)
+    }
+
+    /// Record a failure below the root, or return it under `strict`.
+    fn fail(&self, errors: &mut Vec<ScanError>, e: ScanError) -> Result<(), ScanError> {
+        if self.strict {
+            return Err(e);
+        }
+        errors.push(e);
+        Ok(())
+    }
+
+    /// Effective max file depth for a scan: `recursive == false` clamps to 1.
+    fn file_depth(&self, recursive: bool) -> Option<usize> {
+        if recursive {
+            self.max_depth
+        } else {
+            Some(self.max_depth.map_or(1, |d| d.min(1)))
+        }
+    }
+}
+
+/// Device id of `path`, used by `same_file_system` to spot mount boundaries.
+fn device_of(fs: &dyn Fs, path: &Path) -> Option<u64> {
+    fs.metadata(path).ok()?.dev
+}
+
+/// Identity of the directory `path` resolves to: `(device, inode)`, or a hash of
+/// the canonical path on backends without inodes.
+fn dir_id(fs: &dyn Fs, path: &Path) -> Option<(u64, u64)> {
+    use std::hash::{Hash, Hasher};
+    let meta = fs.metadata(path).ok()?;
+    if let Some(inode) = meta.inode {
+        return Some((meta.dev.unwrap_or(0), inode));
+    }
+    let canon = fs.canonicalize(path).ok()?;
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    canon.hash(&mut hasher);
+    Some((0, hasher.finish()))
+}
+
+/// Per-directory walk state: identities of the current directory and all its
+/// ancestors. Cloned into every child read, so a symlink pointing back up the
+/// chain is recognised no matter how it is spelled.
+type Ancestors = Vec<(u64, u64)>;
+
+/// Entry found by a [`Walk`]. Symlinks are only reported when following links,
+/// as whatever they resolved to.
+#[derive(Debug)]
+struct WalkEntry {
+    path: PathBuf,
+    depth: usize,
+    is_dir: bool,
+    is_link: bool,
+}
+
+/// Parallel tree walk over an [`Fs`], honouring `max_depth` (entry depth),
+/// `same_file_system` and `follow_links`. Directories on another device are
+/// dropped from the walk entirely: neither yielded nor read. Hidden entries
+/// (dot names) are skipped below the root.
+struct Walk<'a> {
+    fs: &'a dyn Fs,
+    max_depth: Option<usize>,
+    root_dev: Option<u64>,
+    follow_links: bool,
+    strict: bool,
+    /// Directories below the root that could not be read
+    errors: Mutex<Vec<ScanError>>,
+}
+
+impl<'a> Walk<'a> {
+    fn new(root: &Path, max_depth: Option<usize>, opts: &'a ScanOptions) -> Self {
+        let fs = opts.fs();
+        let root_dev = if opts.same_file_system { device_of(fs, root) } else { None };
+        Walk { fs, max_depth, root_dev, follow_links: opts.follow_links, strict: opts.strict, errors: Mutex::new(Vec::new()) }
+    }
+
+    /// Hand every entry below `root` (the root itself excluded) to `visit`,
+    /// which pushes whatever the caller keeps of it; entries are never
+    /// collected, only the kept items are, a batch per directory, in no
+    /// particular order. A missing or unreadable root is an error; directories
+    /// below it and entries that can't be read, and entries `visit` fails on,
+    /// are added to `errors` (or fail the walk under `strict`).
+    fn run<T, F>(self, root: &Path, errors: &mut Vec<ScanError>, visit: F) -> Result<Vec<T>, ScanError>
+    where
+        T: Send,
+        F: Fn(WalkEntry, &mut Vec<T>) -> Result<(), ScanError> + Sync,
+    {
+        if self.max_depth == Some(0) {
+            // Nothing to list; the caller reads the root itself
+            self.fs.metadata(root).map_err(|e| ScanError::io(root, e))?;
+            return Ok(Vec::new());
+        }
+        let ancestors: Ancestors = if self.follow_links { dir_id(self.fs, root).into_iter().collect() } else { Vec::new() };
+        let entries = self.fs.read_dir(root).map_err(|e| ScanError::io(root, e))?;
+        let found = self.expand(entries, 0, &ancestors, &visit);
+        let mut failed = self.errors.into_inner().unwrap_or_else(|e| e.into_inner());
+        if self.strict && !failed.is_empty() {
+            return Err(failed.swap_remove(0));
+        }
+        errors.append(&mut failed);
+        Ok(found)
+    }
+
+    fn read<T, F>(&self, dir: &Path, depth: usize, ancestors: &Ancestors, visit: &F) -> Vec<T>
+    where
+        T: Send,
+        F: Fn(WalkEntry, &mut Vec<T>) -> Result<(), ScanError> + Sync,
+    {
+        if self.max_depth.is_some_and(|max| depth >= max) {
+            return Vec::new();
+        }
+        // Under `strict` one failure fails the walk: stop reading
+        if self.strict && !self.errors.lock().unwrap_or_else(|e| e.into_inner()).is_empty() {
+            return Vec::new();
+        }
+        match self.fs.read_dir(dir) {
+            Ok(entries) => self.expand(entries, depth, ancestors, visit),
+            Err(e) => {
+                warn!("Skipping inaccessible path: {}: {}", dir.display(), e);
+                self.errors.lock().unwrap_or_else(|e| e.into_inner()).push(ScanError::io(dir, e));
+                Vec::new()
+            }
+        }
+    }
+
+    /// What `visit` keeps of a directory's entries at `depth`, plus everything
+    /// below its subdirectories.
+    fn expand<T, F>(&self, entries: Vec<Result<FsEntry, ScanError>>, depth: usize, ancestors: &Ancestors, visit: &F) -> Vec<T>
+    where
+        T: Send,
+        F: Fn(WalkEntry, &mut Vec<T>) -> Result<(), ScanError> + Sync,
+    {
+        let depth = depth + 1;
+        let record = |e: ScanError| self.errors.lock().unwrap_or_else(|e| e.into_inner()).push(e);
+        let keep = |entry: WalkEntry, out: &mut Vec<T>| {
+            if let Err(e) = visit(entry, out) {
+                record(e);
+            }
+        };
+        entries
+            .into_par_iter()
+            .filter_map(|e| match e {
+                Ok(e) => Some(e),
+                Err(e) => {
+                    warn!("Skipping unreadable entry: {}: {}", e.path().unwrap_or(Path::new("?")).display(), e);
+                    record(e);
+                    None
+                }
+            })
+            .filter(|e| !e.name().is_some_and(|n| n.to_string_lossy().starts_with('.')))
+            .flat_map_iter(|e| {
+                let mut out = Vec::new();
+                let (is_dir, is_link) = match e.kind {
+                    FileKind::Dir => (true, false),
+                    FileKind::File => (false, false),
+                    FileKind::Symlink if self.follow_links => match self.fs.metadata(&e.path) {
+                        Ok(m) if m.is_dir() => (true, true),
+                        Ok(m) if m.is_file() => (false, true),
+                        _ => return out, // broken, or a link to something else
+                    },
+                    _ => return out,
+                };
+                if !is_dir {
+                    keep(WalkEntry { path: e.path, depth, is_dir, is_link }, &mut out);
+                    return out;
+                }
+                let mut below = ancestors.clone();
+                if self.follow_links {
+                    if let Some(id) = dir_id(self.fs, &e.path) {
+                        if is_link && ancestors.contains(&id) {
+                            warn!("Skipping symlink cycle: {}", e.path.display());
+                            return out;
+                        }
+                        below.push(id);
+                    }
+                }
+                if let Some(root_dev) = self.root_dev {
+                    if device_of(self.fs, &e.path).is_some_and(|dev| dev != root_dev) {
+                        debug!("Not crossing into other file system: {}", e.path.display());
+                        return out;
+                    }
+                }
+                out = self.read(&e.path, depth, &below, visit);
+                keep(WalkEntry { path: e.path, depth, is_dir, is_link }, &mut out);
+                out
+            })
+            .collect()
+    }
+}
+
+/// Report path for a file entry: the link or its resolved target per `link_paths`.
+fn report_path(fs: &dyn Fs, path: PathBuf, is_link: bool, link_paths: LinkPaths) -> PathBuf {
+    if is_link && link_paths == LinkPaths::Target {
+        fs.canonicalize(&path).unwrap_or(path)
+    } else {
+        path
+    }
+}
 
 /// Scan directory for all subdirectories
 ///
-/// Returns unique sorted list of folder paths
-pub fn scan_dirs<P: AsRef<Path>>(root: P, recursive: bool) -> Result<Vec<PathBuf>, String> {
+/// Returns unique sorted list of folder paths whose files fall inside the depth
+/// window of `opts` (a folder at depth `d` holds files at depth `d + 1`).
+/// Folders below the root that can't be read are added to `errors`.
+pub fn scan_dirs<P: AsRef<Path>>(root: P, recursive: bool, opts: &ScanOptions, errors: &mut Vec<ScanError>) -> Result<Vec<PathBuf>, ScanError> {
     let root = root.as_ref();
     info!("Scanning folders in: {}", root.display());
-    let walker = if recursive { WalkDir::new(root) } else { WalkDir::new(root).max_depth(1) };
-    let mut folders = Vec::new();
-    for entry in walker {
-        match entry {
-            Ok(e) if e.file_type().is_dir() => {
-                let path = e.path();
-                if path != root {
-                    folders.push(path.to_path_buf());
-                }
-            }
-            Ok(_) => {} // Not a directory
-            Err(e) => warn!("Skipping inaccessible path: {}", e),
+    let file_depth = opts.file_depth(recursive);
+    // No file level is reachable at all (`max_depth(0)`, or min above max).
+    if file_depth == Some(0) || file_depth.is_some_and(|d| d < opts.min_depth) {
+        return Ok(Vec::new());
+    }
+    let min_dir_depth = opts.min_depth.saturating_sub(1);
+    // Files are dropped as they are listed: only folders are kept
+    let mut folders: Vec<PathBuf> = Walk::new(root, file_depth.map(|d| d - 1), opts).run(root, errors, |e, out| {
+        if e.is_dir && e.depth >= min_dir_depth {
+            out.push(e.path);
         }
+        Ok(())
+    })?;
+    // Root itself holds depth-1 files
+    if min_dir_depth == 0 {
+        folders.push(root.to_path_buf());
     }
-    // Always include root itself
-    folders.push(root.to_path_buf());
     // Sort and deduplicate
     folders.sort();
     folders.dedup();
@@ -56,8 +339,70 @@ pub fn scan_dirs<P: AsRef<Path>>(root: P, recursive: bool) -> Result<Vec<PathBuf
     Ok(folders)
 }
 
+/// Drop roots whose files another root's scan already reports, so several roots
+/// never yield the same sequence twice.
+///
+/// Roots are compared by their resolved path ([`Fs::canonicalize`]): a root
+/// resolving to the same folder as an earlier one is a [`ScanWarning::DuplicateRoot`],
+/// one inside another root a [`ScanWarning::NestedRoot`] when that root's walk
+/// reaches it, i.e. the scan is recursive without `max_depth`, no hidden folder
+/// lies in between, and with `same_file_system` no mount point either. Roots that
+/// don't resolve (missing, archive members) are only dropped as exact duplicates,
+/// so their scan still reports the failure. Kept roots are lexically normalized
+/// (`/a/./b/` becomes `/a/b`) and stay in their original order.
+pub(crate) fn dedup_roots<P: AsRef<Path>>(roots: &[P], recursive: bool, opts: &ScanOptions) -> (Vec<PathBuf>, Vec<ScanWarning>) {
+    let fs = opts.fs();
+    let normalized: Vec<PathBuf> = roots.iter().map(|r| r.as_ref().components().collect()).collect();
+    let resolved: Vec<Option<PathBuf>> = normalized.iter().map(|r| fs.canonicalize(r).ok()).collect();
+    let key = |i: usize| resolved[i].as_ref().unwrap_or(&normalized[i]);
+    let walks_into = |outer: &Path, inner: &Path| {
+        let Ok(rel) = inner.strip_prefix(outer) else { return false };
+        if rel.as_os_str().is_empty() || rel.components().any(|c| c.as_os_str().to_string_lossy().starts_with('.')) {
+            return false;
+        }
+        if !opts.same_file_system {
+            return true;
+        }
+        let dev = |p: &Path| fs.metadata(p).ok().and_then(|m| m.dev);
+        let outer_dev = dev(outer);
+        outer_dev.is_some() && inner.ancestors().take_while(|a| *a != outer).all(|a| dev(a) == outer_dev)
+    };
+
+    let mut warnings = Vec::new();
+    let mut kept: Vec<usize> = Vec::new();
+    for i in 0..normalized.len() {
+        match kept.iter().find(|&&j| key(j) == key(i)) {
+            Some(&j) => warnings.push(ScanWarning::DuplicateRoot { root: normalized[i].clone(), same_as: normalized[j].clone() }),
+            None => kept.push(i),
+        }
+    }
+    if recursive && opts.max_depth.is_none() {
+        // The outermost covering root is itself kept: covering is transitive
+        let covering: Vec<Option<usize>> = kept
+            .iter()
+            .map(|&i| {
+                let inner = resolved[i].as_deref()?;
+                kept.iter()
+                    .filter_map(|&j| Some((j, resolved[j].as_deref()?)))
+                    .filter(|(_, outer)| walks_into(outer, inner))
+                    .min_by_key(|(_, outer)| outer.components().count())
+                    .map(|(j, _)| j)
+            })
+            .collect();
+        let mut still_kept = Vec::new();
+        for (&i, cover) in kept.iter().zip(covering) {
+            match cover {
+                Some(j) => warnings.push(ScanWarning::NestedRoot { root: normalized[i].clone(), covered_by: normalized[j].clone() }),
+                None => still_kept.push(i),
+            }
+        }
+        kept = still_kept;
+    }
+    (kept.into_iter().map(|i| normalized[i].clone()).collect(), warnings)
+}
+
 /// Scan folder(s) for files matching extensions.
-/// Uses jwalk for parallel recursive scanning.
+/// Walks each root in parallel.
 ///
 /// # Arguments
 /// * `roots` - Directory or directories to scan
@@ -70,7 +415,15 @@ pub fn scan_dirs<P: AsRef<Path>>(root: P, recursive: bool) -> Result<Vec<PathBuf
 /// let images = scan_files(&["/renders"], false, &["exr", "jp*"])?; // jpg, jpeg, jp2...
 /// let all = scan_files(&["/data"], true, &[])?; // all files
 /// ```
-pub fn scan_files<P: AsRef<Path> + Sync>(roots: &[P], recursive: bool, exts: &[&str]) -> Result<Vec<PathBuf>, String> {
+#[allow(dead_code)] // Public API (the CLI uses `scan_files_with`)
+pub fn scan_files<P: AsRef<Path> + Sync>(roots: &[P], recursive: bool, exts: &[&str]) -> Result<Vec<PathBuf>, ScanError> {
+    scan_files_with(roots, recursive, exts, &ScanOptions::default())
+}
+
+/// [`scan_files`] with explicit traversal controls (depth window, same file system,
+/// symlink following). Broken links are never reported as files.
+pub fn scan_files_with<P: AsRef<Path> + Sync>(roots: &[P], recursive: bool, exts: &[&str], opts: &ScanOptions) -> Result<Vec<PathBuf>, ScanError> {
+    let file_depth = opts.file_depth(recursive);
     // Pre-compile glob patterns (only for entries with wildcards)
     let patterns: Vec<Option<glob::Pattern>> = exts
         .iter()
@@ -82,50 +435,54 @@ pub fn scan_files<P: AsRef<Path> + Sync>(roots: &[P], recursive: bool, exts: &[&
             }
         })
         .collect();
+    // Filter by extension if provided
+    let ext_matches = |path: &Path| {
+        if exts.is_empty() {
+            return true;
+        }
+        let Some(ext) = path.extension().and_then(|e| e.to_str()).map(str::to_lowercase) else { return false };
+        exts.iter().zip(patterns.iter()).any(|(e, pat)| {
+            match pat {
+                Some(p) => p.matches(&ext),  // glob match
+                None => e.eq_ignore_ascii_case(&ext),  // exact match
+            }
+        })
+    };
 
-    let files: Vec<PathBuf> = roots
+    let (roots, warnings) = dedup_roots(roots, recursive, opts);
+    for w in &warnings {
+        warn!("Skipping root {}", w);
+    }
+    let files: Vec<Vec<PathBuf>> = roots
         .par_iter()
-        .flat_map(|root| {
-            let walker = if recursive {
-                WalkDir::new(root.as_ref()).follow_links(false)
-            } else {
-                WalkDir::new(root.as_ref()).max_depth(1).follow_links(false)
-            };
-
-            walker
-                .into_iter()
-                .filter_map(|e| match e {
-                    Ok(entry) => Some(entry),
-                    Err(err) => {
-                        warn!("Skipping inaccessible path: {}", err);
-                        None
-                    }
-                })
-                .filter(|e| e.file_type().is_file())
-                .filter_map(|e| {
-                    let path = e.path();
-
-                    // Filter by extension if provided
-                    if !exts.is_empty() {
-                        let ext = path.extension()?.to_str()?.to_lowercase();
-                        let matched = exts.iter().zip(patterns.iter()).any(|(e, pat)| {
-                            match pat {
-                                Some(p) => p.matches(&ext),  // glob match
-                                None => e.eq_ignore_ascii_case(&ext),  // exact match
-                            }
-                        });
-                        if !matched {
-                            return None;
+        .map(|root| {
+            let root = root.as_path();
+            // Failures below the root are only logged here; `strict` turns them into errors
+            let mut errors = Vec::new();
+            Walk::new(root, file_depth, opts).run(root, &mut errors, |e, found| {
+                if e.is_dir || e.depth < opts.min_depth {
+                    return Ok(());
+                }
+                let path = report_path(opts.fs(), e.path, e.is_link, opts.link_paths);
+                let mut listed = Ok(());
+                if opts.archives && ArchiveKind::from_path(&path).is_some() {
+                    match list_archive(opts.fs(), &path) {
+                        Ok(members) => found.extend(members.iter().map(|m| member_path(&path, &m.name)).filter(|p| ext_matches(p))),
+                        Err(err) => {
+                            warn!("Skipping unreadable archive {}", err);
+                            listed = Err(err);
                         }
                     }
-
-                    Some(path.to_path_buf())
-                })
-                .collect::<Vec<_>>()
+                }
+                if ext_matches(&path) {
+                    found.push(path);
+                }
+                listed
+            })
         })
-        .collect();
+        .collect::<Result<_, ScanError>>()?;
 
-    Ok(files)
+    Ok(files.into_iter().flatten().collect())
 }
 
 /// Expand shell-style brace alternation `{a,b,c}` in a glob mask into the concrete
@@ -157,54 +514,204 @@ fn expand_braces(mask: &str) -> Vec<String> {
     out
 }
 
-/// Scan single folder for files with glob mask (internal, used by get_seqs)
-fn scan_files_glob<P: AsRef<Path>>(folder: P, mask: Option<&str>) -> Result<Vec<PathBuf>, String> {
-    let folder = folder.as_ref();
-    let entries = std::fs::read_dir(folder)
-        .map_err(|e| format!("Failed to read dir {}: {}", folder.display(), e))?;
-
-    // A mask filters only when it carries a wildcard OR a brace group; a plain string is
-    // ignored (every file passes), matching the historic behaviour. Brace groups are
-    // expanded HERE because the `glob` crate cannot (see `expand_braces`) — one
-    // `glob::Pattern` per alternative, and a file passes if ANY of them matches.
-    let patterns: Vec<glob::Pattern> = match mask {
+/// Compile a file mask into glob patterns. Empty = no filtering.
+///
+/// A mask filters only when it carries a wildcard OR a brace group; a plain string is
+/// ignored (every file passes), matching the historic behaviour. Brace groups are
+/// expanded HERE because the `glob` crate cannot (see `expand_braces`) — one
+/// `glob::Pattern` per alternative, and a file passes if ANY of them matches.
+fn compile_mask(mask: Option<&str>) -> Result<Vec<glob::Pattern>, ScanError> {
+    match mask {
         Some(m) if m.contains('*') || m.contains('{') => {
-            let mut v = Vec::new();
-            for p in expand_braces(m) {
-                v.push(glob::Pattern::new(&p).map_err(|e| format!("Invalid mask {p:?}: {e}"))?);
-            }
-            v
+            expand_braces(m).iter().map(|p| glob::Pattern::new(p).map_err(|e| ScanError::InvalidMask { mask: p.clone(), source: Arc::new(e) })).collect()
         }
-        _ => Vec::new(),
-    };
+        _ => Ok(Vec::new()),
+    }
+}
 
-    let mut files = Vec::new();
-    for entry in entries.filter_map(|e| e.ok()) {
-        let path = entry.path();
-        if !path.is_file() {
-            continue;
+/// True if the file name of `path` passes a mask compiled by [`compile_mask`].
+fn mask_matches(patterns: &[glob::Pattern], path: &Path) -> bool {
+    if patterns.is_empty() {
+        return true;
+    }
+    match path.file_name() {
+        Some(name) => {
+            let name = name.to_string_lossy();
+            patterns.iter().any(|p| p.matches(&name))
         }
-        if !patterns.is_empty() {
-            if let Some(name) = path.file_name() {
-                let name = name.to_string_lossy();
-                if !patterns.iter().any(|p| p.matches(&name)) {
+        None => true,
+    }
+}
+
+/// Scan single folder for files with glob mask (internal, used by get_seqs).
+///
+/// Symlinked files are always included (reported per `opts.link_paths`); dangling
+/// links are kept and flagged as [`File::broken`] only when `opts.follow_links` is set.
+/// Unreadable archives and entries that can't be listed or stat'ed are added to `errors`.
+fn scan_files_glob<P: AsRef<Path>>(folder: P, mask: Option<&str>, opts: &ScanOptions, errors: &mut Vec<ScanError>) -> Result<Vec<File>, ScanError> {
+    let folder = folder.as_ref();
+    let fs = opts.fs();
+    let entries = fs.read_dir(folder).map_err(|e| ScanError::io(folder, e))?;
+
+    let patterns = compile_mask(mask)?;
+
+    let mut files = Vec::new();
+    for entry in entries {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                opts.fail(errors, e)?;
+                continue;
+            }
+        };
+        let path = entry.path;
+        // Regular files need no stat; a symlink is resolved to decide what it points at.
+        let (is_link, broken, target_meta) = match entry.kind {
+            FileKind::File => (false, false, None),
+            FileKind::Symlink => match fs.metadata(&path) {
+                Ok(m) if m.is_file() => (true, false, Some(m)),
+                Ok(_) => continue, // link to a directory
+                Err(_) if opts.follow_links => (true, true, None),
+                Err(_) => continue,
+            },
+            _ => continue,
+        };
+        if opts.archives && !broken && ArchiveKind::from_path(&path).is_some() {
+            let archive = report_path(fs, path.clone(), is_link, opts.link_paths);
+            match list_archive(fs, &archive) {
+                Ok(members) => {
+                    // The members replace the archive itself
+                    for member in members {
+                        let member_file = member_path(&archive, &member.name);
+                        if mask_matches(&patterns, &member_file) {
+                            let mut file = File::new(member_file);
+                            if opts.with_metadata {
+                                file.meta = Some(FrameInfo { size: member.size, mtime: member.mtime, inode: None });
+                            }
+                            files.push(file);
+                        }
+                    }
                     continue;
                 }
+                Err(e) => {
+                    warn!("Skipping unreadable archive {}", e);
+                    opts.fail(errors, e)?;
+                }
             }
         }
-        files.push(path);
+        if !mask_matches(&patterns, &path) {
+            continue;
+        }
+        let meta = if !opts.with_metadata || broken {
+            None
+        } else if let Some(m) = target_meta.or(entry.meta) {
+            Some(FrameInfo::from(&m))
+        } else {
+            match fs.metadata(&path) {
+                Ok(m) => Some(FrameInfo::from(&m)),
+                Err(e) => {
+                    opts.fail(errors, ScanError::io(&path, e))?;
+                    None
+                }
+            }
+        };
+        let mut file = File::new(report_path(fs, path, is_link && !broken, opts.link_paths));
+        file.broken = broken;
+        file.meta = meta;
+        files.push(file);
     }
     Ok(files)
 }
 
+/// Scan and group a single folder (phase 2 of [`get_seqs_with`] for one folder).
+/// Also used by the watcher to regroup only the folders that changed.
+/// Failures on single entries are added to `errors`; an unreadable folder is an `Err`.
+pub(crate) fn folder_seqs(folder: &Path, mask: Option<&str>, min_len: usize, opts: &ScanOptions, errors: &mut Vec<ScanError>) -> Result<Vec<Seq>, ScanError> {
+    let mut file_objs = scan_files_glob(folder, mask, opts, errors)?;
+    if file_objs.is_empty() {
+        return Ok(Vec::new());
+    }
+    debug!("Processing {} ({} files)", folder.display(), file_objs.len());
+
+    // Group into sequences, filter by min_len
+    let seqs = Seq::group_seqs(&mut file_objs);
+    Ok(seqs.into_iter().filter(|s| s.len() >= min_len).collect())
+}
+
+/// Group a list of paths into sequences without touching the filesystem.
+///
+/// For path lists from render logs, `find` output, database exports or tar
+/// listings. [`GroupOptions`] `mask` and `min_len` behave as in [`get_seqs`];
+/// entries ending in a path separator are taken as directories and skipped, and
+/// a path listed twice counts once. Paths from different folders may be mixed
+/// freely. Sequences come back sorted by path.
+///
+/// # Example
+/// ```ignore
+/// let listing = std::fs::read_to_string("frames.txt")?;
+/// let opts = GroupOptions { mask: Some("*.exr".into()), ..Default::default() };
+/// let seqs = group_paths(listing.lines(), &opts)?;
+/// ```
+#[allow(dead_code)] // Public API
+pub fn group_paths<I, P>(paths: I, opts: &GroupOptions) -> Result<Vec<Seq>, ScanError>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    group_files(paths.into_iter().map(|p| File::new(p.as_ref())), opts.mask.as_deref(), opts.min_len)
+}
+
+/// Grouping behind [`group_paths`] and the inventory readers: mask filter,
+/// directory-entry and duplicate removal, then [`Seq::group_seqs`].
+pub(crate) fn group_files<I: IntoIterator<Item = File>>(files: I, mask: Option<&str>, min_len: usize) -> Result<Vec<Seq>, ScanError> {
+    let patterns = compile_mask(mask)?;
+    let mut seen = std::collections::HashSet::new();
+    let mut files: Vec<File> = files
+        .into_iter()
+        .filter(|f| {
+            let s = f.fpn.as_os_str().to_string_lossy();
+            !s.is_empty() && !s.ends_with('/') && !s.ends_with('\\') && mask_matches(&patterns, &f.fpn)
+        })
+        .filter(|f| seen.insert(f.fpn.clone()))
+        .collect();
+    let mut seqs: Vec<Seq> = Seq::group_seqs(&mut files).into_iter().filter(|s| s.len() >= min_len).collect();
+    sort_seqs(&mut seqs, SortKey::Path, false);
+    Ok(seqs)
+}
+
 /// Main scan and group function
 ///
-/// Returns all sequences found (flattened, not per-folder)
-pub fn get_seqs<P: AsRef<Path>>(root: P, recursive: bool, mask: Option<&str>, min_len: usize) -> Result<Vec<Seq>, String> {
+/// Returns all sequences found (flattened, not per-folder), sorted by path
+pub fn get_seqs<P: AsRef<Path>>(root: P, recursive: bool, mask: Option<&str>, min_len: usize) -> Result<Vec<Seq>, ScanError> {
+    get_seqs_with(root, recursive, mask, min_len, &ScanOptions::default())
+}
+
+/// [`get_seqs`] with explicit traversal controls (depth window, same file system,
+/// symlink following). With `follow_links`, dangling frame links are kept and
+/// flagged in [`Seq::broken`].
+///
+/// Folders and entries below the root that can't be read are logged and skipped;
+/// use [`get_seqs_result`] to get them back, or set [`ScanOptions::strict`].
+pub fn get_seqs_with<P: AsRef<Path>>(root: P, recursive: bool, mask: Option<&str>, min_len: usize, opts: &ScanOptions) -> Result<Vec<Seq>, ScanError> {
+    get_seqs_result(root, recursive, mask, min_len, opts).map(|result| result.seqs)
+}
+
+/// [`get_seqs_with`], also returning every folder or entry below the root that
+/// could not be read in [`ScanResult::errors`] (sorted by path). A missing or
+/// unreadable root, an invalid mask, or any failure under [`ScanOptions::strict`]
+/// is an `Err` instead.
+pub fn get_seqs_result<P: AsRef<Path>>(root: P, recursive: bool, mask: Option<&str>, min_len: usize, opts: &ScanOptions) -> Result<ScanResult, ScanError> {
+    let root = root.as_ref();
     let start = std::time::Instant::now();
+    // A bad mask fails the scan, not each folder
+    compile_mask(mask)?;
     // Phase 1: Discover folders
     info!("Phase 1: Discovering folders...");
-    let folders = scan_dirs(root, recursive)?;
+    let mut errors = Vec::new();
+    let mut folders = scan_dirs(root, recursive, opts, &mut errors)?;
+    // Folders the walk could not list are already reported
+    let unlisted: std::collections::HashSet<PathBuf> = errors.iter().filter_map(|e| e.path()).map(Path::to_path_buf).collect();
+    folders.retain(|f| !unlisted.contains(f));
     info!("Phase 1 complete: {} folders in {:.2}s", folders.len(), start.elapsed().as_secs_f64());
 
     // Phase 2: Process folders in parallel
@@ -220,7 +727,7 @@ pub fn get_seqs<P: AsRef<Path>>(root: P, recursive: bool, mask: Option<&str>, mi
     let pool = ThreadPoolBuilder::new()
         .num_threads(num_threads)
         .build()
-        .map_err(|e| format!("Failed to create thread pool: {}", e))?;
+        .map_err(|e| ScanError::ThreadPool(e.to_string()))?;
 
     let found_seqs = AtomicUsize::new(0);
 
@@ -236,38 +743,51 @@ pub fn get_seqs<P: AsRef<Path>>(root: P, recursive: bool, mask: Option<&str>, mi
             .progress_chars("=>-"),
     );
 
-    let all_seqs: Vec<Seq> = pool.install(|| {
+    // Optional per-root cache: folders whose mtime is unchanged are not regrouped
+    let scan_start = SystemTime::now();
+    let cache = opts.cache_dir.as_deref().map(|dir| {
+        let mut cache = ScanCache::open(dir, root);
+        cache.check_key(mask, min_len, opts);
+        cache
+    });
+    let cache_hits = AtomicUsize::new(0);
+    let failures = Mutex::new(errors);
+
+    let scanned: Vec<(PathBuf, Option<SystemTime>, Vec<Seq>)> = pool.install(|| {
         folders
             .par_iter()
-            .flat_map(|folder| {
-                // Scan files in this folder
-                let files = match scan_files_glob(folder, mask) {
-                    Ok(f) => f,
-                    Err(e) => {
-                        warn!("Error scanning {}: {}", folder.display(), e);
-                        #[cfg(feature = "cli")]
-                        pb.inc(1);
-                        return Vec::new();
+            .filter_map(|folder| -> Option<Result<_, ScanError>> {
+                // Stat before listing, so a change during the listing shows up next scan
+                let mut mtime = cache.as_ref().and_then(|_| opts.fs().metadata(folder).ok()?.mtime);
+                let cached = cache.as_ref().and_then(|c| c.get(folder, mtime));
+                let filtered = match cached {
+                    Some(seqs) => {
+                        cache_hits.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                        seqs.to_vec()
+                    }
+                    None => {
+                        let mut entry_errors = Vec::new();
+                        let seqs = folder_seqs(folder, mask, min_len, opts, &mut entry_errors);
+                        if !entry_errors.is_empty() {
+                            // Don't cache a partial listing: retry it next scan
+                            mtime = None;
+                            failures.lock().unwrap_or_else(|e| e.into_inner()).append(&mut entry_errors);
+                        }
+                        match seqs {
+                            Ok(seqs) => seqs,
+                            // The root's own listing failing fails the scan, like in phase 1
+                            Err(e) if folder == root || opts.strict => return Some(Err(e)),
+                            Err(e) => {
+                                warn!("Error scanning {}: {}", folder.display(), e);
+                                failures.lock().unwrap_or_else(|e| e.into_inner()).push(e);
+                                #[cfg(feature = "cli")]
+                                pb.inc(1);
+                                return None;
+                            }
+                        }
                     }
                 };
 
-                if files.is_empty() {
-                    #[cfg(feature = "cli")]
-                    pb.inc(1);
-                    return Vec::new();
-                }
-
-                debug!("Processing {} ({} files)", folder.display(), files.len());
-
-                // Convert to File objects (move PathBuf instead of clone)
-                let mut file_objs: Vec<File> = files.into_iter().map(File::new).collect();
-
-                // Group into sequences
-                let seqs = Seq::group_seqs(&mut file_objs);
-
-                // Filter by min_len
-                let filtered: Vec<Seq> = seqs.into_iter().filter(|s| s.len() >= min_len).collect();
-
                 if !filtered.is_empty() {
                     let seq_count = filtered.len();
                     // Use fetch_add return value to avoid race condition in message
@@ -284,24 +804,45 @@ pub fn get_seqs<P: AsRef<Path>>(root: P, recursive: bool, mask: Option<&str>, mi
                 #[cfg(feature = "cli")]
                 pb.inc(1);
 
-                filtered
+                Some(Ok((folder.clone(), mtime, filtered)))
             })
-            .collect()
-    });
+            .collect::<Result<_, _>>()
+    })?;
+
+    let mut all_seqs: Vec<Seq> = match cache {
+        Some(mut cache) => {
+            info!("Scan cache: {} of {} folders unchanged", cache_hits.into_inner(), folders.len());
+            cache.replace(scanned, scan_start);
+            if let Err(e) = cache.save() {
+                warn!("Failed to save scan cache: {}", e);
+            }
+            cache.into_seqs()
+        }
+        None => scanned.into_iter().flat_map(|(_, _, seqs)| seqs).collect(),
+    };
 
     #[cfg(feature = "cli")]
     pb.finish_with_message("Complete");
 
+    sort_seqs(&mut all_seqs, opts.sort, opts.natural_sort);
     let total_seqs = all_seqs.len();
     info!("Phase 2 complete: {} sequences in {:.2}s", total_seqs, phase2_start.elapsed().as_secs_f64());
     info!("Total time: {:.2}s", start.elapsed().as_secs_f64());
 
-    Ok(all_seqs)
+    let mut errors = failures.into_inner().unwrap_or_else(|e| e.into_inner());
+    if !errors.is_empty() {
+        warn!("{} folder(s) or entries could not be read", errors.len());
+    }
+    errors.sort_by(|a, b| a.path().cmp(&b.path()));
+    // Both phases list the folders, so an unreadable entry fails in each
+    errors.dedup_by(|a, b| a.path() == b.path() && a.to_string() == b.to_string());
+    Ok(ScanResult { seqs: all_seqs, elapsed_ms: start.elapsed().as_secs_f64() * 1000.0, errors, warnings: Vec::new() })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::MemFs;
     use std::fs;
     use tempfile::tempdir;
 
@@ -430,4 +971,394 @@ mod tests {
         assert_eq!(seqs.len(), 1, "one sequence expected, got {}", seqs.len());
         assert_eq!(seqs[0].len(), 5);
     }
+
+    /// Build `root/a.####.exr`, `root/d1/b.####.exr`, `root/d1/d2/c.####.exr` (2 frames each).
+    fn depth_tree() -> tempfile::TempDir {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let d2 = root.join("d1").join("d2");
+        fs::create_dir_all(&d2).unwrap();
+        for n in 1..=2 {
+            fs::write(root.join(format!("a.{n:04}.exr")), "").unwrap();
+            fs::write(root.join("d1").join(format!("b.{n:04}.exr")), "").unwrap();
+            fs::write(d2.join(format!("c.{n:04}.exr")), "").unwrap();
+        }
+        dir
+    }
+
+    fn names(seqs: &[Seq]) -> Vec<String> {
+        let mut v: Vec<String> = seqs
+            .iter()
+            .map(|s| Path::new(s.pattern()).file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        v.sort();
+        v
+    }
+
+    #[test]
+    fn test_depth_window_get_seqs() {
+        let dir = depth_tree();
+        let root = dir.path();
+        let opts = |min, max| ScanOptions { min_depth: min, max_depth: max, ..Default::default() };
+
+        let all = get_seqs_with(root, true, None, 2, &opts(0, None)).unwrap();
+        assert_eq!(names(&all), vec!["a.####.exr", "b.####.exr", "c.####.exr"]);
+
+        let top = get_seqs_with(root, true, None, 2, &opts(0, Some(1))).unwrap();
+        assert_eq!(names(&top), vec!["a.####.exr"]);
+
+        let two = get_seqs_with(root, true, None, 2, &opts(0, Some(2))).unwrap();
+        assert_eq!(names(&two), vec!["a.####.exr", "b.####.exr"]);
+
+        let deep = get_seqs_with(root, true, None, 2, &opts(2, None)).unwrap();
+        assert_eq!(names(&deep), vec!["b.####.exr", "c.####.exr"]);
+
+        let middle = get_seqs_with(root, true, None, 2, &opts(2, Some(2))).unwrap();
+        assert_eq!(names(&middle), vec!["b.####.exr"]);
+
+        // Nothing reachable: max_depth(0), or min above max.
+        assert!(get_seqs_with(root, true, None, 2, &opts(0, Some(0))).unwrap().is_empty());
+        assert!(get_seqs_with(root, true, None, 2, &opts(3, Some(2))).unwrap().is_empty());
+
+        // recursive=false still clamps to the root's own files.
+        let flat = get_seqs_with(root, false, None, 2, &opts(0, Some(5))).unwrap();
+        assert_eq!(names(&flat), vec!["a.####.exr"]);
+    }
+
+    #[test]
+    fn test_depth_window_scan_files() {
+        let dir = depth_tree();
+        let root = dir.path();
+        let opts = |min, max| ScanOptions { min_depth: min, max_depth: max, ..Default::default() };
+
+        assert_eq!(scan_files_with(&[root], true, &["exr"], &opts(0, Some(2))).unwrap().len(), 4);
+        assert_eq!(scan_files_with(&[root], true, &["exr"], &opts(3, None)).unwrap().len(), 2);
+        assert_eq!(scan_files_with(&[root], true, &["exr"], &opts(2, Some(2))).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_same_file_system_keeps_local_dirs() {
+        // A temp tree lives on one device, so the option must not prune anything here.
+        let dir = depth_tree();
+        let opts = ScanOptions { same_file_system: true, ..Default::default() };
+        assert_eq!(get_seqs_with(dir.path(), true, None, 2, &opts).unwrap().len(), 3);
+        assert_eq!(scan_files_with(&[dir.path()], true, &[], &opts).unwrap().len(), 6);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_follow_links_into_symlink_farm() {
+        use std::os::unix::fs::symlink;
+        let store = tempdir().unwrap();
+        let publish = tempdir().unwrap();
+        let v3 = store.path().join("v003");
+        fs::create_dir(&v3).unwrap();
+        for n in 1..=3 {
+            fs::write(v3.join(format!("comp.{n:04}.exr")), "x").unwrap();
+        }
+        symlink(&v3, publish.path().join("comp")).unwrap();
+
+        // Not followed: the linked directory is invisible.
+        assert!(get_seqs(publish.path(), true, None, 2).unwrap().is_empty());
+        assert!(scan_files(&[publish.path()], true, &[]).unwrap().is_empty());
+
+        let follow = ScanOptions { follow_links: true, ..Default::default() };
+        let seqs = get_seqs_with(publish.path(), true, None, 2, &follow).unwrap();
+        assert_eq!(seqs.len(), 1);
+        assert!(seqs[0].pattern().starts_with(&*publish.path().join("comp").to_string_lossy()));
+        assert_eq!(scan_files_with(&[publish.path()], true, &[], &follow).unwrap().len(), 3);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_follow_links_cycle_terminates() {
+        use std::os::unix::fs::symlink;
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let sub = root.join("sub");
+        fs::create_dir(&sub).unwrap();
+        for n in 1..=2 {
+            fs::write(sub.join(format!("f.{n:04}.exr")), "").unwrap();
+        }
+        // sub/up -> root and sub/self -> sub: both point back up the chain.
+        symlink(root, sub.join("up")).unwrap();
+        symlink(&sub, sub.join("self")).unwrap();
+
+        let follow = ScanOptions { follow_links: true, ..Default::default() };
+        let seqs = get_seqs_with(root, true, None, 2, &follow).unwrap();
+        assert_eq!(seqs.len(), 1);
+        assert_eq!(scan_files_with(&[root], true, &[], &follow).unwrap().len(), 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_link_paths_and_broken_frames() {
+        use std::os::unix::fs::symlink;
+        let store = tempdir().unwrap();
+        let farm = tempdir().unwrap();
+        for n in 1..=3 {
+            let target = store.path().join(format!("plate.{n:04}.dpx"));
+            fs::write(&target, "x").unwrap();
+            symlink(&target, farm.path().join(format!("plate.{n:04}.dpx"))).unwrap();
+        }
+        // Frame 4 points at a file that does not exist.
+        symlink(store.path().join("plate.0004.dpx"), farm.path().join("plate.0004.dpx")).unwrap();
+
+        // Default: link paths reported, dangling link dropped.
+        let seqs = get_seqs(farm.path(), false, None, 2).unwrap();
+        assert_eq!(seqs[0].indices, vec![1, 2, 3]);
+        assert!(seqs[0].broken.is_empty());
+
+        // Following: dangling frame kept and flagged.
+        let follow = ScanOptions { follow_links: true, ..Default::default() };
+        let seqs = get_seqs_with(farm.path(), false, None, 2, &follow).unwrap();
+        assert_eq!(seqs.len(), 1);
+        assert_eq!(seqs[0].indices, vec![1, 2, 3, 4]);
+        assert_eq!(seqs[0].broken, vec![4]);
+
+        // Targets: the intact frames resolve into the store; the broken one cannot.
+        let targets = ScanOptions { link_paths: LinkPaths::Target, ..Default::default() };
+        let seqs = get_seqs_with(farm.path(), false, None, 2, &targets).unwrap();
+        let store_dir = fs::canonicalize(store.path()).unwrap();
+        assert_eq!(seqs.len(), 1);
+        assert!(seqs[0].pattern().starts_with(&*store_dir.to_string_lossy()));
+    }
+
+    #[test]
+    fn test_with_metadata_records_sizes() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        for n in 1..=3 {
+            fs::write(root.join(format!("r.{n:04}.exr")), vec![0u8; n * 100]).unwrap();
+        }
+        let plain = get_seqs(root, false, None, 2).unwrap();
+        assert!(!plain[0].has_metadata());
+
+        let opts = ScanOptions { with_metadata: true, ..Default::default() };
+        let seqs = get_seqs_with(root, false, None, 2, &opts).unwrap();
+        let seq = &seqs[0];
+        assert_eq!(seq.frame_info(2).map(|i| i.size), Some(200));
+        assert_eq!(seq.total_bytes(), 600);
+        assert!(seq.oldest().is_some() && seq.newest().is_some());
+        #[cfg(unix)]
+        assert!(seq.frame_info(1).and_then(|i| i.inode).is_some());
+    }
+
+    #[test]
+    fn test_cache_reuses_unchanged_folders() {
+        let dir = tempdir().unwrap();
+        let cache_dir = tempdir().unwrap();
+        let shot = dir.path().join("shot");
+        fs::create_dir(&shot).unwrap();
+        for n in 1..=3 {
+            fs::write(shot.join(format!("r.{n:04}.exr")), b"x").unwrap();
+        }
+        // Back-date the folders so they are outside the racy window
+        let old = SystemTime::now() - std::time::Duration::from_secs(3600);
+        let backdate = |p: &Path| fs::File::open(p).unwrap().set_modified(old).unwrap();
+        backdate(&shot);
+        backdate(dir.path());
+
+        let opts = ScanOptions { cache_dir: Some(cache_dir.path().to_path_buf()), ..Default::default() };
+        let seqs = get_seqs_with(dir.path(), true, None, 2, &opts).unwrap();
+        assert_eq!(seqs[0].indices, vec![1, 2, 3]);
+        assert_eq!(ScanCache::open(cache_dir.path(), dir.path()).len(), 2);
+
+        // Remove a frame but restore the folder mtime: the cached grouping is reused
+        fs::remove_file(shot.join("r.0002.exr")).unwrap();
+        backdate(&shot);
+        let seqs = get_seqs_with(dir.path(), true, None, 2, &opts).unwrap();
+        assert_eq!(seqs[0].indices, vec![1, 2, 3]);
+
+        // Explicit invalidation forces the regroup
+        let mut cache = ScanCache::open(cache_dir.path(), dir.path());
+        assert_eq!(cache.invalidate(&shot), 1);
+        cache.save().unwrap();
+        let seqs = get_seqs_with(dir.path(), true, None, 2, &opts).unwrap();
+        assert_eq!(seqs[0].indices, vec![1, 3]);
+
+        // Different grouping settings never reuse the cache
+        let seqs = get_seqs_with(dir.path(), true, None, 3, &opts).unwrap();
+        assert!(seqs.is_empty());
+    }
+
+    #[test]
+    fn test_group_paths_from_list() {
+        let listing = "\
+/show/sh010/comp/comp.1001.exr
+/show/sh010/comp/comp.1002.exr
+/show/sh010/comp/comp.1004.exr
+/show/sh010/comp/comp.1001.exr
+/show/sh010/comp/
+/show/sh020/plate/plate_0001.dpx
+/show/sh020/plate/plate_0002.dpx
+/show/sh020/plate/notes.txt
+/show/sh030/single.0001.exr";
+        let mut seqs = group_paths(listing.lines(), &GroupOptions::default()).unwrap();
+        seqs.sort_by(|a, b| a.pattern().cmp(b.pattern()));
+        assert_eq!(names(&seqs), vec!["comp.####.exr", "plate_####.dpx"]);
+        assert_eq!(seqs[0].pattern(), "/show/sh010/comp/comp.####.exr");
+        assert_eq!(seqs[0].indices, vec![1001, 1002, 1004]);
+        assert_eq!(seqs[0].missed, vec![1003]);
+
+        let exr = group_paths(listing.lines(), &GroupOptions { mask: Some("*.exr".into()), ..Default::default() }).unwrap();
+        assert_eq!(names(&exr), vec!["comp.####.exr"]);
+        let three = group_paths(listing.lines(), &GroupOptions { min_len: 3, ..Default::default() }).unwrap();
+        assert_eq!(names(&three), vec!["comp.####.exr"]);
+        assert!(matches!(group_paths(listing.lines(), &GroupOptions { mask: Some("[*.exr".into()), min_len: 2 }), Err(ScanError::InvalidMask { .. })));
+    }
+
+    #[test]
+    fn test_scan_errors_are_typed() {
+        use std::error::Error;
+        use std::io::ErrorKind;
+
+        let dir = tempdir().unwrap();
+        let missing = dir.path().join("nope");
+        for recursive in [true, false] {
+            let err = get_seqs(&missing, recursive, None, 2).unwrap_err();
+            assert_eq!(err.kind(), Some(ErrorKind::NotFound), "{err}");
+            assert_eq!(err.path(), Some(missing.as_path()));
+            assert!(err.source().is_some(), "io error chained");
+            assert_eq!(scan_files(&[dir.path(), &missing], recursive, &[]).unwrap_err().kind(), Some(ErrorKind::NotFound));
+        }
+        assert_eq!(scan_dirs(&missing, true, &ScanOptions::default(), &mut Vec::new()).unwrap_err().kind(), Some(ErrorKind::NotFound));
+
+        // A bad mask fails the scan even when no folder would be listed
+        fs::write(dir.path().join("a.0001.exr"), "").unwrap();
+        let err = get_seqs(dir.path(), true, Some("[*.exr"), 2).unwrap_err();
+        assert!(matches!(&err, ScanError::InvalidMask { mask, .. } if mask == "[*.exr"), "{err}");
+        assert_eq!(err.kind(), None);
+    }
+
+    /// [`MemFs`] that refuses to list the folders in `deny`, and lists the
+    /// entries in `bad` as unreadable.
+    #[derive(Debug)]
+    struct DenyFs {
+        mem: MemFs,
+        deny: Vec<PathBuf>,
+        bad: Vec<PathBuf>,
+    }
+
+    impl Fs for DenyFs {
+        fn read_dir(&self, path: &Path) -> std::io::Result<Vec<Result<crate::core::FsEntry, ScanError>>> {
+            if self.deny.iter().any(|d| d == path) {
+                return Err(std::io::ErrorKind::PermissionDenied.into());
+            }
+            let entries = self.mem.read_dir(path)?.into_iter();
+            Ok(entries
+                .map(|e| match e {
+                    Ok(e) if self.bad.contains(&e.path) => Err(ScanError::io(e.path, std::io::ErrorKind::InvalidData.into())),
+                    e => e,
+                })
+                .collect())
+        }
+
+        fn metadata(&self, path: &Path) -> std::io::Result<crate::core::FsMetadata> {
+            self.mem.metadata(path)
+        }
+
+        fn open(&self, path: &Path) -> std::io::Result<Box<dyn crate::core::ReadSeek>> {
+            self.mem.open(path)
+        }
+    }
+
+    #[test]
+    fn test_unreadable_folders_are_collected() {
+        use std::io::ErrorKind;
+
+        let mem = MemFs::new();
+        for n in 1..=2u64 {
+            for shot in ["a", "b", "c", "d", "b/deep"] {
+                mem.add_file(format!("/r/{shot}/x.{n:04}.exr"), 1);
+            }
+        }
+        // MemFs files have no content: the archive cannot be opened
+        mem.add_file("/r/c/broken.zip", 10);
+        mem.add_file("/r/d/x.0003.exr", 1);
+        let fs = DenyFs { mem, deny: vec![PathBuf::from("/r/b")], bad: vec![PathBuf::from("/r/d/x.0003.exr")] };
+        let opts = ScanOptions { fs: Some(Arc::new(fs)), archives: true, ..Default::default() };
+
+        let result = get_seqs_result("/r", true, None, 2, &opts).unwrap();
+        assert_eq!(result.seqs.len(), 3, "a, c and d");
+        let failed: Vec<_> = result.errors.iter().map(|e| (e.path().map(Path::to_path_buf), e.kind())).collect();
+        assert_eq!(
+            failed,
+            vec![
+                (Some(PathBuf::from("/r/b")), Some(ErrorKind::PermissionDenied)),
+                (Some(PathBuf::from("/r/c/broken.zip")), Some(ErrorKind::Unsupported)),
+                (Some(PathBuf::from("/r/d/x.0003.exr")), Some(ErrorKind::InvalidData)),
+            ],
+            "each failure once, sorted by path"
+        );
+        assert_eq!(get_seqs_with("/r", true, None, 2, &opts).unwrap().len(), 3);
+
+        let strict = ScanOptions { strict: true, ..opts };
+        assert!(get_seqs_result("/r", true, None, 2, &strict).is_err());
+        assert!(scan_files_with(&["/r"], true, &[], &strict).is_err());
+        assert!(get_seqs_result("/r/a", true, None, 2, &strict).is_ok());
+        let err = get_seqs_result("/r/d", false, None, 2, &strict).unwrap_err();
+        assert_eq!(err.path(), Some(Path::new("/r/d/x.0003.exr")), "unreadable entry fails a strict scan");
+    }
+
+    #[test]
+    fn test_scan_mem_fs() {
+        let mem = MemFs::new();
+        for n in 1..=3u64 {
+            mem.add_file(format!("/show/sh010/comp.{n:04}.exr"), n * 100);
+            mem.add_file(format!("/show/sh010/.tmp/comp.{n:04}.exr"), 1);
+            mem.add_file(format!("/store/v2/plate.{n:04}.dpx"), 10);
+        }
+        mem.add_symlink("/show/sh020", "/store/v2").add_symlink("/show/sh020/up", "/show");
+        let opts = ScanOptions { fs: Some(Arc::new(mem)), with_metadata: true, ..Default::default() };
+
+        // Hidden folders are skipped, links are not followed by default
+        let mut seqs = get_seqs_with("/show", true, None, 2, &opts).unwrap();
+        assert_eq!(names(&seqs), vec!["comp.####.exr"]);
+        assert_eq!(seqs[0].total_bytes(), 600);
+        assert_eq!(scan_dirs("/show", true, &opts, &mut Vec::new()).unwrap(), vec![PathBuf::from("/show"), PathBuf::from("/show/sh010")]);
+
+        // Following links reaches the store once; the link back up is a cycle
+        let follow = ScanOptions { follow_links: true, link_paths: LinkPaths::Target, ..opts };
+        seqs = get_seqs_with("/show", true, None, 2, &follow).unwrap();
+        seqs.sort_by(|a, b| a.pattern().cmp(b.pattern()));
+        assert_eq!(seqs.len(), 2);
+        assert_eq!(seqs[1].pattern(), "/show/sh020/plate.####.dpx");
+        let files = scan_files_with(&["/show"], true, &["dpx"], &follow).unwrap();
+        assert_eq!(files.len(), 3);
+        assert!(files.iter().all(|f| f.starts_with("/show/sh020")));
+    }
+
+    #[test]
+    fn test_overlapping_roots() {
+        let mem = MemFs::new();
+        for n in 1..=3u64 {
+            mem.add_file(format!("/show/sh010/comp.{n:04}.exr"), 1);
+            mem.add_file(format!("/show/.cache/sh010/comp.{n:04}.exr"), 1);
+        }
+        mem.add_symlink("/show/latest", "/show/sh010");
+        let opts = ScanOptions { fs: Some(Arc::new(mem)), ..Default::default() };
+        let roots = ["/show/sh010/", "/show", "/show/./latest", "/show/.cache", "/gone", "/gone"];
+
+        let (kept, warnings) = dedup_roots(&roots, true, &opts);
+        assert_eq!(kept, vec![PathBuf::from("/show"), PathBuf::from("/show/.cache"), PathBuf::from("/gone")]);
+        assert_eq!(
+            warnings,
+            vec![
+                ScanWarning::DuplicateRoot { root: "/show/latest".into(), same_as: "/show/sh010".into() },
+                ScanWarning::DuplicateRoot { root: "/gone".into(), same_as: "/gone".into() },
+                ScanWarning::NestedRoot { root: "/show/sh010".into(), covered_by: "/show".into() },
+            ]
+        );
+        // A depth limit or a flat scan doesn't reach into the nested root
+        let shallow = ScanOptions { max_depth: Some(1), ..opts.clone() };
+        assert_eq!(dedup_roots(&roots, true, &shallow).0.len(), 4);
+        assert_eq!(dedup_roots(&roots, false, &opts).0.len(), 4);
+
+        let scanner = crate::core::Scanner::paths(&roots).fs(opts.fs.clone().unwrap()).scan();
+        assert_eq!(scanner.len(), 2, "sh010 once, plus the hidden copy");
+        assert_eq!(scanner.result.warnings.len(), 3);
+        assert_eq!(scanner.result.errors.len(), 1, "missing root reported once");
+        assert_eq!(scan_files_with(&roots[..4], true, &[], &opts).unwrap().len(), 6);
+    }
 }
@@ -6,28 +6,36 @@
 //! 3. Sub-group by "anchor" values (all other digit groups) - moves ownership, no cloning
 //! 4. Create Seq for each sub-group with >= 2 files
 
-use super::file::File;
+use super::error::ScanError;
+use super::file::{File, FrameInfo};
 use super::slice::FrameSlice;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
+use std::time::SystemTime;
 
 /// Maximum gap size to expand into missed frames list (OOM protection)
 const MAX_MISSED_GAP: i64 = 100_000;
 
 /// Sequence of numbered files
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Seq {
     /// Frame numbers actually present
     pub indices: Vec<i64>,
     /// Missing frame numbers
     pub missed: Vec<i64>,
+    /// Present frames whose file is a dangling symlink (scans with `follow_links` only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub broken: Vec<i64>,
     /// First frame
     pub start: i64,
     /// Last frame
     pub end: i64,
     /// Padding (0 = variable/unpadded, >0 = fixed width)
     pub padding: usize,
+    /// Per-frame metadata aligned with `indices` (empty unless scanned `with_metadata`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    frame_info: Vec<Option<FrameInfo>>,
     /// Pattern string (lowercase on Windows for grouping)
     pattern: String,
     /// First file path with original case (for file operations)
@@ -44,21 +52,15 @@ impl Seq {
 
         // Extract frame numbers from each file using its own num_groups positions
         // This handles unpadded sequences where positions vary per file
-        let mut frames: Vec<i64> = files
+        let mut frames: Vec<i64> = files.iter().filter_map(|f| frame_num(f, frame_grp_idx)).collect();
+
+        let mut broken: Vec<i64> = files
             .iter()
-            .filter_map(|f| {
-                if frame_grp_idx >= f.num_groups.len() {
-                    return None;
-                }
-                let (start, len) = f.num_groups[frame_grp_idx];
-                // Bounds check to prevent panic on malformed data
-                let end = start.saturating_add(len);
-                if end > f.name.len() {
-                    return None;
-                }
-                f.name[start..end].parse::<i64>().ok()
-            })
+            .filter(|f| f.broken)
+            .filter_map(|f| frame_num(f, frame_grp_idx))
             .collect();
+        broken.sort_unstable();
+        broken.dedup();
 
         frames.sort_unstable();
         frames.dedup();
@@ -67,6 +69,18 @@ impl Seq {
             return None;
         }
 
+        // Metadata follows the frame order; on duplicate frame numbers
+        // (`img_1` + `img_01`) the first file seen wins.
+        let frame_info = if files.iter().any(|f| f.meta.is_some()) {
+            let mut by_frame: Vec<(i64, Option<FrameInfo>)> =
+                files.iter().filter_map(|f| Some((frame_num(f, frame_grp_idx)?, f.meta))).collect();
+            by_frame.sort_by_key(|&(frame, _)| frame);
+            by_frame.dedup_by_key(|&mut (frame, _)| frame);
+            by_frame.into_iter().map(|(_, info)| info).collect()
+        } else {
+            Vec::new()
+        };
+
         // Safe extraction with pattern matching (no unwrap)
         let (start, end) = match (frames.first(), frames.last()) {
             (Some(&s), Some(&e)) => (s, e),
@@ -93,7 +107,7 @@ impl Seq {
         // Store first file path with original case for file operations
         let first_file_path = files[0].fpn.to_string_lossy().to_string();
 
-        Some(Seq { indices: frames, missed, start, end, padding, pattern, first_file_path })
+        Some(Seq { indices: frames, missed, broken, start, end, padding, frame_info, pattern, first_file_path })
     }
 
     /// Get sequence length (number of files)
@@ -183,17 +197,34 @@ impl Seq {
             self.first_file_path.clone()
         };
 
+        let broken = self.broken.iter().copied().filter(|f| indices.binary_search(f).is_ok()).collect();
+        let frame_info = if self.frame_info.is_empty() {
+            Vec::new()
+        } else {
+            indices.iter().map(|&f| self.frame_info(f).copied()).collect()
+        };
+
         Seq {
             indices,
             missed,
+            broken,
             start,
             end,
             padding: self.padding,
+            frame_info,
             pattern: self.pattern.clone(),
             first_file_path,
         }
     }
 
+    /// The same frames under another pattern and padding (after the files were
+    /// renamed, see [`Seq::repad`]). `first_file_path` is re-derived from the
+    /// new pattern, like [`Seq::with_indices`] does for a new first frame.
+    pub(crate) fn with_pattern(&self, pattern: String, padding: usize) -> Seq {
+        let first_file_path = format_frame(&pattern, padding, self.start);
+        Seq { pattern, padding, first_file_path, ..self.clone() }
+    }
+
     // === Frame-to-path methods (public API for library users) ===
 
     /// Format frame number into full file path using pattern.
@@ -262,7 +293,7 @@ impl Seq {
     /// Includes paths for ALL frames including missing ones.
     ///
     /// # Safety
-    /// Limited to 1M frames to prevent OOM. Returns error if range exceeds limit.
+    /// Limited to 1M frames to prevent OOM: [`ScanError::RangeTooLarge`] beyond that.
     ///
     /// # Example
     /// ```ignore
@@ -270,14 +301,11 @@ impl Seq {
     /// assert_eq!(seq.expand()?, vec!["/img_1.exr", "/img_2.exr", "/img_3.exr"]);
     /// ```
     #[allow(dead_code)] // Public API
-    pub fn expand(&self) -> Result<Vec<String>, String> {
+    pub fn expand(&self) -> Result<Vec<String>, ScanError> {
         const MAX_EXPAND: i64 = 1_000_000;
         let count = self.end.saturating_sub(self.start).saturating_add(1);
         if count > MAX_EXPAND {
-            return Err(format!(
-                "Range too large: {} frames (max {})",
-                count, MAX_EXPAND
-            ));
+            return Err(ScanError::RangeTooLarge { frames: count, max: MAX_EXPAND });
         }
         Ok((self.start..=self.end)
             .map(|f| self.format_frame(f))
@@ -351,6 +379,52 @@ impl Seq {
         self.indices.iter().map(move |&f| (f, PathBuf::from(self.format_frame(f))))
     }
 
+    // === Per-frame metadata (scans with `with_metadata`) ===
+
+    /// Size/mtime/inode recorded for `frame` during the scan. `None` if the
+    /// frame is not present, the scan did not collect metadata, or the frame is
+    /// a broken link.
+    #[must_use]
+    #[allow(dead_code)] // Public API
+    pub fn frame_info(&self, frame: i64) -> Option<&FrameInfo> {
+        let pos = self.indices.binary_search(&frame).ok()?;
+        self.frame_info.get(pos)?.as_ref()
+    }
+
+    /// True if the scan recorded metadata for this sequence.
+    #[must_use]
+    #[allow(dead_code)] // Public API
+    pub fn has_metadata(&self) -> bool {
+        !self.frame_info.is_empty()
+    }
+
+    /// Present frames paired with their metadata, ascending (skips frames without any).
+    #[allow(dead_code)] // Public API
+    pub fn frame_infos(&self) -> impl Iterator<Item = (i64, &FrameInfo)> + '_ {
+        self.indices.iter().zip(&self.frame_info).filter_map(|(&f, info)| Some((f, info.as_ref()?)))
+    }
+
+    /// Disk usage of all present frames in bytes (0 without metadata).
+    #[must_use]
+    #[allow(dead_code)] // Public API
+    pub fn total_bytes(&self) -> u64 {
+        self.frame_infos().map(|(_, info)| info.size).sum()
+    }
+
+    /// Frame with the earliest mtime, and that mtime.
+    #[must_use]
+    #[allow(dead_code)] // Public API
+    pub fn oldest(&self) -> Option<(i64, SystemTime)> {
+        self.frame_infos().filter_map(|(f, info)| Some((f, info.mtime?))).min_by_key(|&(_, t)| t)
+    }
+
+    /// Frame with the latest mtime, and that mtime.
+    #[must_use]
+    #[allow(dead_code)] // Public API
+    pub fn newest(&self) -> Option<(i64, SystemTime)> {
+        self.frame_infos().filter_map(|(f, info)| Some((f, info.mtime?))).max_by_key(|&(_, t)| t)
+    }
+
     /// Get frame count (number of existing frames, not range).
     #[must_use]
     #[allow(dead_code)] // Public API
@@ -437,27 +511,24 @@ impl Seq {
 
         // Find frame group using all matching files (not just target)
         let frame_grp_idx = find_frame_group(&matching);
+        let target_frame = frame_num(target, frame_grp_idx)?;
 
-        // Build sequences from this group
-        let seqs = build_seqs_from_group(matching);
-
-        // Find frame number of target file
-        let target_frame: Option<i64> = target.num_groups.get(frame_grp_idx).and_then(|&(start, len)| {
-            let end = start.saturating_add(len);
-            if end <= target.name.len() {
-                target.name[start..end].parse().ok()
-            } else {
-                None
-            }
-        });
-
-        // Find sequence containing target's frame
-        target_frame.and_then(|frame| {
-            seqs.into_iter().find(|seq| seq.indices.contains(&frame))
-        })
+        // Keep the target's sub-group: same anchors (`A001C003_v2` vs `A001C004_v7`)
+        let anchors = make_anchor_key(target, frame_grp_idx);
+        let siblings: Vec<File> = matching.into_iter().filter(|f| make_anchor_key(f, frame_grp_idx) == anchors).collect();
+        if siblings.len() < 2 {
+            return None;
+        }
+        Seq::from_files(&siblings, frame_grp_idx).filter(|seq| seq.indices.contains(&target_frame))
     }
 }
 
+/// Frame number held by `file`'s digit group `grp_idx` (bounds-checked, never panics).
+fn frame_num(file: &File, grp_idx: usize) -> Option<i64> {
+    let &(start, len) = file.num_groups.get(grp_idx)?;
+    file.name.get(start..start.saturating_add(len))?.parse().ok()
+}
+
 /// Build sequences from files with same sig_hash (shared core logic).
 fn build_seqs_from_group(files: Vec<File>) -> Vec<Seq> {
     if files.len() < 2 {
@@ -616,14 +687,91 @@ fn gen_pattern(file: &File, frame_grp_idx: usize, padding: usize) -> String {
 ///
 /// - padding >= 2: replace #### with zero-padded frame (e.g., 0042)
 /// - padding < 2: replace @ with raw frame number
+///
+/// Only the placeholder in the file name is replaced: `#` and `@` in folder
+/// names are kept (`/tmp/u@h/img_@.png` -> `/tmp/u@h/img_7.png`).
 pub fn format_frame(pattern: &str, padding: usize, frame: i64) -> String {
+    let Some((before, after)) = split_placeholder(pattern, padding) else {
+        return pattern.to_string();
+    };
     if padding >= 2 {
-        let placeholder = "#".repeat(padding);
-        let frame_str = format!("{:0width$}", frame, width = padding);
-        pattern.replace(&placeholder, &frame_str)
+        format!("{}{:0width$}{}", before, frame, after, width = padding)
     } else {
-        pattern.replace('@', &frame.to_string())
+        format!("{}{}{}", before, frame, after)
+    }
+}
+
+/// `pattern` split around its frame placeholder, as written for `padding`
+/// (`####` or `@`): the last one in the file name, so folder names may hold
+/// `#` and `@` freely.
+pub(crate) fn split_placeholder(pattern: &str, padding: usize) -> Option<(&str, &str)> {
+    let placeholder = if padding >= 2 { "#".repeat(padding) } else { "@".to_string() };
+    let name_at = pattern.rfind(['/', '\\']).map_or(0, |i| i + 1);
+    let at = name_at + pattern[name_at..].rfind(&placeholder)?;
+    Some((&pattern[..at], &pattern[at + placeholder.len()..]))
+}
+
+/// Format frame numbers as compact ranges, e.g. `[1, 2, 3, 5, 8, 9]` -> `"1-3,5,8-9"`.
+/// Input is sorted and deduplicated first. The output is the syntax render
+/// farms accept for frame lists, so it can be pasted into a resubmission.
+/// A range with a negative bound is written with a colon (`-5:-2`, `-2:0`),
+/// since `-2-0` would read both ways.
+#[allow(dead_code)] // Public API
+pub fn format_frame_ranges(frames: &[i64]) -> String {
+    let mut frames = frames.to_vec();
+    frames.sort_unstable();
+    frames.dedup();
+
+    let mut parts: Vec<String> = Vec::new();
+    let mut iter = frames.into_iter().peekable();
+    while let Some(start) = iter.next() {
+        let mut end = start;
+        while let Some(next) = end.checked_add(1).filter(|next| iter.peek() == Some(next)) {
+            end = next;
+            iter.next();
+        }
+        parts.push(format_frame_span(start, end));
+    }
+    parts.join(",")
+}
+
+/// One range of [`format_frame_ranges`]: `5`, `1-3`, or `-2:0` when a bound is negative.
+pub(crate) fn format_frame_span(start: i64, end: i64) -> String {
+    if start == end {
+        start.to_string()
+    } else if start < 0 || end < 0 {
+        format!("{}:{}", start, end)
+    } else {
+        format!("{}-{}", start, end)
+    }
+}
+
+/// Parse a frame list in the [`format_frame_ranges`] syntax (`"1-3,5,8-9"`,
+/// `"-5:-2"`) into sorted, deduplicated frame numbers. A dash between
+/// negative bounds (`-5--2`) is accepted too. Limited to 1M frames like
+/// [`Seq::expand`] ([`ScanError::RangeTooLarge`]).
+#[allow(dead_code)] // Public API
+pub fn parse_frame_ranges(spec: &str) -> Result<Vec<i64>, ScanError> {
+    const MAX_FRAMES: i64 = 1_000_000;
+    let mut frames = Vec::new();
+    for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        // Without a colon, the range dash comes after the first character, which may be a minus sign
+        let dash = || part.char_indices().skip(1).find(|&(_, c)| c == '-').map(|(at, _)| (&part[..at], &part[at + 1..]));
+        let (first, last) = part.split_once(':').or_else(dash).unwrap_or((part, part));
+        let bound = |s: &str| s.trim().parse::<i64>().map_err(|_| ScanError::parse(format!("invalid frame range '{}'", part)));
+        let (first, last) = (bound(first)?, bound(last)?);
+        if first > last {
+            return Err(ScanError::parse(format!("invalid frame range '{}' (start after end)", part)));
+        }
+        let count = last.saturating_sub(first).saturating_add(1);
+        if count.saturating_add(frames.len() as i64) > MAX_FRAMES {
+            return Err(ScanError::RangeTooLarge { frames: count, max: MAX_FRAMES });
+        }
+        frames.extend(first..=last);
     }
+    frames.sort_unstable();
+    frames.dedup();
+    Ok(frames)
 }
 
 #[cfg(test)]
@@ -328,3 +328,102 @@ fn test_select_last_fifty_semantics_small() {
     let sub = seq.select(&"-3:".parse().expect("slice"));
     assert_eq!(sub.indices, vec![8, 9, 10]);
 }
+
+// --- Per-frame metadata (frame_info / total_bytes / oldest / newest) ---
+
+/// Frames 1,2,4 with sizes 10,20,40 and mtimes t+frame seconds.
+fn meta_seq() -> Seq {
+    use std::time::{Duration, UNIX_EPOCH};
+    let files: Vec<File> = [1i64, 2, 4]
+        .iter()
+        .map(|&n| {
+            let mut f = File::new(format!("c:/temp/m_{n:04}.exr"));
+            f.meta = Some(FrameInfo {
+                size: n as u64 * 10,
+                mtime: Some(UNIX_EPOCH + Duration::from_secs(1_000 + n as u64)),
+                inode: Some(100 + n as u64),
+            });
+            f
+        })
+        .collect();
+    Seq::from_files(&files, 0).expect("should create sequence")
+}
+
+#[test]
+fn test_frame_info_lookup() {
+    let seq = meta_seq();
+    assert!(seq.has_metadata());
+    assert_eq!(seq.frame_info(2).map(|i| i.size), Some(20));
+    assert_eq!(seq.frame_info(4).and_then(|i| i.inode), Some(104));
+    assert!(seq.frame_info(3).is_none(), "missing frame has no info");
+}
+
+#[test]
+fn test_metadata_aggregates() {
+    use std::time::{Duration, UNIX_EPOCH};
+    let seq = meta_seq();
+    assert_eq!(seq.total_bytes(), 70);
+    assert_eq!(seq.oldest(), Some((1, UNIX_EPOCH + Duration::from_secs(1_001))));
+    assert_eq!(seq.newest(), Some((4, UNIX_EPOCH + Duration::from_secs(1_004))));
+}
+
+#[test]
+fn test_metadata_absent_by_default() {
+    let seq = ten_frame_seq();
+    assert!(!seq.has_metadata());
+    assert_eq!(seq.total_bytes(), 0);
+    assert!(seq.oldest().is_none());
+    assert!(!seq.to_json().contains("frame_info"));
+}
+
+#[test]
+fn test_select_keeps_frame_info_aligned() {
+    let seq = meta_seq();
+    let sub = seq.select(&"1:".parse().expect("slice"));
+    assert_eq!(sub.indices, vec![2, 4]);
+    assert_eq!(sub.frame_info(4).map(|i| i.size), Some(40));
+    assert_eq!(sub.total_bytes(), 60);
+}
+
+#[test]
+fn test_format_frame_ranges() {
+    assert_eq!(format_frame_ranges(&[]), "");
+    assert_eq!(format_frame_ranges(&[7]), "7");
+    assert_eq!(format_frame_ranges(&[1, 2, 3, 5, 8, 9]), "1-3,5,8-9");
+    assert_eq!(format_frame_ranges(&[9, 8, 3, 1, 2, 2]), "1-3,8-9");
+    assert_eq!(format_frame_ranges(&[-2, -1, 0, 4]), "-2:0,4");
+    assert_eq!(format_frame_ranges(&[-5, -4, -1, 3, 4]), "-5:-4,-1,3-4");
+    assert_eq!(format_frame_ranges(&[i64::MAX - 1, i64::MAX, i64::MIN]), format!("{},{}-{}", i64::MIN, i64::MAX - 1, i64::MAX));
+}
+
+#[test]
+fn test_parse_frame_ranges() {
+    assert_eq!(parse_frame_ranges("").unwrap(), Vec::<i64>::new());
+    assert_eq!(parse_frame_ranges("1-3, 5,8-9,2").unwrap(), vec![1, 2, 3, 5, 8, 9]);
+    assert_eq!(parse_frame_ranges("-2-0,4").unwrap(), vec![-2, -1, 0, 4]);
+    assert_eq!(parse_frame_ranges("-5--4").unwrap(), vec![-5, -4]);
+    assert_eq!(parse_frame_ranges("-2:0,-5:-4").unwrap(), vec![-5, -4, -2, -1, 0]);
+    let frames = vec![-7, -6, -3, 0, 1, 2, 9];
+    assert_eq!(parse_frame_ranges(&format_frame_ranges(&frames)).unwrap(), frames);
+    assert!(matches!(parse_frame_ranges("3-1"), Err(ScanError::Parse { .. })));
+    assert!(matches!(parse_frame_ranges("1-x"), Err(ScanError::Parse { .. })));
+    assert!(matches!(parse_frame_ranges("0-99999999"), Err(ScanError::RangeTooLarge { .. })));
+}
+
+#[test]
+fn test_expand_range_too_large() {
+    let files = vec![File::new("/t/img_1.exr"), File::new("/t/img_2000000.exr")];
+    let seq = Seq::from_files(&files, 0).expect("should create sequence");
+    let err = seq.expand().unwrap_err();
+    assert!(matches!(err, ScanError::RangeTooLarge { frames: 2_000_000, max: 1_000_000 }), "{err}");
+    assert_eq!(err.to_string(), "Range too large: 2000000 frames (max 1000000)");
+}
+
+#[test]
+fn test_extract_seq_keeps_anchors() {
+    let names = ["A001C003_v2.0001.dpx", "A001C003_v2.0002.dpx", "A001C004_v7.0001.dpx", "A001C004_v7.0002.dpx", "A001C004_v7.0003.dpx"];
+    let mut files: Vec<File> = names.iter().map(|n| File::new(format!("/in/{n}"))).collect();
+    let seq = Seq::extract_seq(&File::new("/in/A001C003_v2.0001.dpx"), &mut files).expect("sequence");
+    assert_eq!(seq.pattern(), "/in/A001C003_v2.####.dpx");
+    assert_eq!(seq.indices, vec![1, 2]);
+}
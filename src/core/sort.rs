@@ -0,0 +1,135 @@
+//! Deterministic ordering of scan output.
+//!
+//! Grouping goes through hash maps and folders are processed in parallel, so
+//! sequences come out of a scan in no particular order. Every entry point that
+//! returns sequences ([`get_seqs_result`](super::get_seqs_result), [`Scanner`](super::Scanner),
+//! [`group_paths`](super::group_paths), the inventory readers, the watcher) sorts
+//! them with [`sort_seqs`] first, by [`ScanOptions::sort`](super::ScanOptions::sort)
+//! where options apply and by plain path otherwise. Ties always fall back to the
+//! path, then the first frame, so the order is total.
+
+use super::seq::Seq;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// What sequences are ordered by. Every key is ascending, ties broken by path.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortKey {
+    /// Pattern path (`/shots/sh010/comp.####.exr`)
+    #[default]
+    Path,
+    /// Number of present frames
+    FrameCount,
+    /// Latest frame mtime; needs `with_metadata`, sequences without it come first
+    Mtime,
+}
+
+impl FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "path" | "pattern" | "name" => Ok(SortKey::Path),
+            "frames" | "count" | "frame-count" => Ok(SortKey::FrameCount),
+            "mtime" | "time" => Ok(SortKey::Mtime),
+            _ => Err(format!("unknown sort key '{}' (expected path, frames or mtime)", s)),
+        }
+    }
+}
+
+impl fmt::Display for SortKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SortKey::Path => "path",
+            SortKey::FrameCount => "frames",
+            SortKey::Mtime => "mtime",
+        })
+    }
+}
+
+/// Compare strings with digit runs taken as numbers: `shot2` < `shot10`.
+///
+/// Leading zeros don't change a run's value; strings equal by value (`a01`,
+/// `a1`) fall back to plain byte order so the result is still a total order.
+#[must_use]
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut x, mut y) = (a.as_bytes(), b.as_bytes());
+    while let (Some(&cx), Some(&cy)) = (x.first(), y.first()) {
+        if cx.is_ascii_digit() && cy.is_ascii_digit() {
+            let (nx, rest_x) = split_digits(x);
+            let (ny, rest_y) = split_digits(y);
+            let (vx, vy) = (trim_zeros(nx), trim_zeros(ny));
+            let ord = vx.len().cmp(&vy.len()).then_with(|| vx.cmp(vy));
+            if ord != Ordering::Equal {
+                return ord;
+            }
+            (x, y) = (rest_x, rest_y);
+        } else {
+            if cx != cy {
+                return cx.cmp(&cy);
+            }
+            (x, y) = (&x[1..], &y[1..]);
+        }
+    }
+    x.len().cmp(&y.len()).then_with(|| a.cmp(b))
+}
+
+fn split_digits(s: &[u8]) -> (&[u8], &[u8]) {
+    let end = s.iter().position(|b| !b.is_ascii_digit()).unwrap_or(s.len());
+    s.split_at(end)
+}
+
+fn trim_zeros(s: &[u8]) -> &[u8] {
+    let start = s.iter().position(|&b| b != b'0').unwrap_or(s.len());
+    &s[start..]
+}
+
+/// Sort `seqs` by `key`, comparing paths naturally when `natural` is set.
+pub fn sort_seqs(seqs: &mut Vec<Seq>, key: SortKey, natural: bool) {
+    let by_path = |a: &Seq, b: &Seq| {
+        let ord = if natural { natural_cmp(a.pattern(), b.pattern()) } else { a.pattern().cmp(b.pattern()) };
+        ord.then_with(|| a.start.cmp(&b.start))
+    };
+    match key {
+        SortKey::Path => seqs.sort_by(by_path),
+        SortKey::FrameCount => seqs.sort_by(|a, b| a.frame_count().cmp(&b.frame_count()).then_with(|| by_path(a, b))),
+        SortKey::Mtime => {
+            // Newest frame once per sequence, not once per comparison
+            let mut keyed: Vec<_> = seqs.drain(..).map(|s| (s.newest().map(|(_, t)| t), s)).collect();
+            keyed.sort_by(|(ta, a), (tb, b)| ta.cmp(tb).then_with(|| by_path(a, b)));
+            seqs.extend(keyed.into_iter().map(|(_, s)| s));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{group_paths, GroupOptions};
+
+    #[test]
+    fn natural_order() {
+        let mut names = vec!["shot10", "shot2", "shot02", "shot1b", "Shot3", "shot", "shot1"];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(names, vec!["Shot3", "shot", "shot1", "shot1b", "shot02", "shot2", "shot10"]);
+        assert_eq!(natural_cmp("v99999999999999999999", "v100000000000000000000"), Ordering::Less, "no overflow");
+    }
+
+    #[test]
+    fn sort_keys() {
+        let listing = ["/s/sh10/a.0001.exr", "/s/sh10/a.0002.exr", "/s/sh2/a.0001.exr", "/s/sh2/a.0002.exr", "/s/sh2/a.0003.exr"];
+        let mut seqs = group_paths(listing, &GroupOptions::default()).unwrap();
+        let patterns = |seqs: &[Seq]| seqs.iter().map(|s| s.pattern().to_string()).collect::<Vec<_>>();
+        assert_eq!(patterns(&seqs), vec!["/s/sh10/a.####.exr", "/s/sh2/a.####.exr"], "plain path order by default");
+
+        sort_seqs(&mut seqs, SortKey::Path, true);
+        assert_eq!(patterns(&seqs), vec!["/s/sh2/a.####.exr", "/s/sh10/a.####.exr"]);
+        sort_seqs(&mut seqs, SortKey::FrameCount, false);
+        assert_eq!(patterns(&seqs), vec!["/s/sh10/a.####.exr", "/s/sh2/a.####.exr"]);
+        assert_eq!("frames".parse::<SortKey>(), Ok(SortKey::FrameCount));
+        assert!("size".parse::<SortKey>().is_err());
+        assert_eq!(SortKey::Mtime.to_string().parse::<SortKey>(), Ok(SortKey::Mtime));
+    }
+}
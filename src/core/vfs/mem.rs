@@ -0,0 +1,282 @@
+//! [`MemFs`]: an in-memory [`Fs`] for tests, fixtures and dry runs.
+
+use super::{FileKind, Fs, FsEntry, FsMetadata, ScanError};
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::OsString;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::RwLock;
+use std::time::SystemTime;
+
+/// Symlinks followed before a path is declared a loop (Linux's `MAXSYMLINKS`).
+const MAX_LINK_HOPS: usize = 40;
+
+#[derive(Debug, Clone)]
+enum Node {
+    Dir { ino: u64, mtime: SystemTime, children: BTreeSet<OsString> },
+    File { ino: u64, size: u64, mtime: SystemTime },
+    Link { target: PathBuf },
+}
+
+#[derive(Debug, Default)]
+struct MemTree {
+    nodes: BTreeMap<PathBuf, Node>,
+    next_ino: u64,
+}
+
+/// In-memory filesystem: a tree of directories, sized files and symlinks.
+///
+/// Paths are used as given (no normalisation beyond `Path` components), parent
+/// directories are created on demand, and adding or removing an entry bumps its
+/// directory's mtime like a real filesystem would. All mutators take `&self`, so
+/// a tree shared with a scanner can change between scans.
+///
+/// # Example
+/// ```ignore
+/// let fs = MemFs::new();
+/// for n in 1..=3 {
+///     fs.add_file(format!("/shots/sh010/comp.{n:04}.exr"), 1024);
+/// }
+/// let seqs = Scanner::path("/shots").fs(Arc::new(fs)).into_seqs();
+/// ```
+#[derive(Debug, Default)]
+pub struct MemFs {
+    tree: RwLock<MemTree>,
+}
+
+fn not_found(path: &Path) -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, format!("{}: not found", path.display()))
+}
+
+impl MemTree {
+    fn ino(&mut self) -> u64 {
+        self.next_ino += 1;
+        self.next_ino
+    }
+
+    /// Create `path` and its missing ancestors as directories.
+    fn mkdir_all(&mut self, path: &Path, mtime: SystemTime) {
+        if matches!(self.nodes.get(path), Some(Node::Dir { .. })) {
+            return;
+        }
+        let ino = self.ino();
+        self.nodes.insert(path.to_path_buf(), Node::Dir { ino, mtime, children: BTreeSet::new() });
+        self.link_into_parent(path, mtime);
+    }
+
+    /// Register `path` in its parent's children (creating the parent if needed).
+    fn link_into_parent(&mut self, path: &Path, mtime: SystemTime) {
+        let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else { return };
+        self.mkdir_all(parent, mtime);
+        if let Some(Node::Dir { children, mtime: dir_mtime, .. }) = self.nodes.get_mut(parent) {
+            children.insert(name.to_os_string());
+            *dir_mtime = mtime;
+        }
+    }
+
+    /// Replace whatever is at `path` with `node`. A symlinked parent is followed,
+    /// so adding below a link lands in its target directory.
+    fn insert(&mut self, path: &Path, node: Node) {
+        let path = match (path.parent(), path.file_name()) {
+            (Some(parent), Some(name)) => self.resolve(parent).map_or_else(|_| path.to_path_buf(), |p| p.join(name)),
+            _ => path.to_path_buf(),
+        };
+        self.remove(&path);
+        self.nodes.insert(path.clone(), node);
+        self.link_into_parent(&path, SystemTime::now());
+    }
+
+    fn remove(&mut self, path: &Path) -> bool {
+        let Some(node) = self.nodes.remove(path) else { return false };
+        if let Node::Dir { children, .. } = node {
+            for child in children {
+                self.remove(&path.join(child));
+            }
+        }
+        if let (Some(parent), Some(name)) = (path.parent(), path.file_name()) {
+            if let Some(Node::Dir { children, mtime, .. }) = self.nodes.get_mut(parent) {
+                children.remove(name);
+                *mtime = SystemTime::now();
+            }
+        }
+        true
+    }
+
+    /// Resolve every symlink along `path` (the last component too).
+    fn resolve(&self, path: &Path) -> io::Result<PathBuf> {
+        // Components still to walk, last one first
+        let mut todo: Vec<PathBuf> = path.components().rev().map(|c| PathBuf::from(c.as_os_str())).collect();
+        let mut out = PathBuf::new();
+        let mut hops = 0;
+        while let Some(part) = todo.pop() {
+            match part.components().next() {
+                Some(Component::CurDir) | None => {}
+                Some(Component::ParentDir) => {
+                    out.pop();
+                }
+                Some(Component::Normal(_)) => {
+                    out.push(&part);
+                    match self.nodes.get(&out) {
+                        Some(Node::Link { target }) => {
+                            hops += 1;
+                            if hops > MAX_LINK_HOPS {
+                                return Err(io::Error::other(format!("{}: too many levels of symbolic links", path.display())));
+                            }
+                            out.pop();
+                            if target.is_absolute() {
+                                out = PathBuf::new();
+                            }
+                            todo.extend(target.components().rev().map(|c| PathBuf::from(c.as_os_str())));
+                        }
+                        Some(_) => {}
+                        None => return Err(not_found(path)),
+                    }
+                }
+                Some(_) => out.push(&part), // root / prefix
+            }
+        }
+        Ok(out)
+    }
+}
+
+impl MemFs {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a file of `size` bytes modified now.
+    pub fn add_file<P: AsRef<Path>>(&self, path: P, size: u64) -> &Self {
+        self.add_file_with(path, size, SystemTime::now())
+    }
+
+    /// Add a file with an explicit mtime (replaces any existing entry).
+    pub fn add_file_with<P: AsRef<Path>>(&self, path: P, size: u64, mtime: SystemTime) -> &Self {
+        let mut tree = self.tree.write().expect("MemFs lock");
+        let ino = tree.ino();
+        tree.insert(path.as_ref(), Node::File { ino, size, mtime });
+        drop(tree);
+        self
+    }
+
+    /// Add a directory and its missing parents.
+    pub fn add_dir<P: AsRef<Path>>(&self, path: P) -> &Self {
+        self.tree.write().expect("MemFs lock").mkdir_all(path.as_ref(), SystemTime::now());
+        self
+    }
+
+    /// Add a symlink at `link` pointing to `target` (absolute, or relative to the
+    /// link's directory). The target need not exist.
+    pub fn add_symlink<P: AsRef<Path>, T: AsRef<Path>>(&self, link: P, target: T) -> &Self {
+        self.tree.write().expect("MemFs lock").insert(link.as_ref(), Node::Link { target: target.as_ref().to_path_buf() });
+        self
+    }
+
+    /// Set the mtime of an existing entry (not followed through symlinks).
+    pub fn set_mtime<P: AsRef<Path>>(&self, path: P, when: SystemTime) -> io::Result<()> {
+        let path = path.as_ref();
+        match self.tree.write().expect("MemFs lock").nodes.get_mut(path) {
+            Some(Node::Dir { mtime, .. } | Node::File { mtime, .. }) => {
+                *mtime = when;
+                Ok(())
+            }
+            Some(Node::Link { .. }) => Ok(()),
+            None => Err(not_found(path)),
+        }
+    }
+
+    /// Remove an entry (directories with everything below). False if it didn't exist.
+    pub fn remove<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.tree.write().expect("MemFs lock").remove(path.as_ref())
+    }
+}
+
+impl Fs for MemFs {
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<Result<FsEntry, ScanError>>> {
+        let tree = self.tree.read().expect("MemFs lock");
+        let resolved = tree.resolve(path)?;
+        let Some(Node::Dir { children, .. }) = tree.nodes.get(&resolved) else {
+            return Err(io::Error::other(format!("{}: not a directory", path.display())));
+        };
+        Ok(children
+            .iter()
+            .filter_map(|name| {
+                let (kind, meta) = match tree.nodes.get(&resolved.join(name))? {
+                    Node::Link { .. } => (FileKind::Symlink, None),
+                    node => (node_meta(node).kind, Some(node_meta(node))),
+                };
+                Some(Ok(FsEntry { path: path.join(name), kind, meta }))
+            })
+            .collect())
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<FsMetadata> {
+        let tree = self.tree.read().expect("MemFs lock");
+        let resolved = tree.resolve(path)?;
+        tree.nodes.get(&resolved).map(node_meta).ok_or_else(|| not_found(path))
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        self.tree.read().expect("MemFs lock").resolve(path)
+    }
+}
+
+/// Metadata of a resolved (non-link) node.
+fn node_meta(node: &Node) -> FsMetadata {
+    match *node {
+        Node::Dir { ino, mtime, .. } => FsMetadata { kind: FileKind::Dir, size: 0, mtime: Some(mtime), inode: Some(ino), dev: Some(0) },
+        Node::File { ino, size, mtime } => FsMetadata { kind: FileKind::File, size, mtime: Some(mtime), inode: Some(ino), dev: Some(0) },
+        Node::Link { .. } => FsMetadata { kind: FileKind::Symlink, size: 0, mtime: None, inode: None, dev: Some(0) },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn mem_fs_lists_and_stats() {
+        let fs = MemFs::new();
+        fs.add_file("/r/a.0001.exr", 10).add_file("/r/a.0002.exr", 20).add_dir("/r/sub");
+        let mut names: Vec<_> = fs.read_dir(Path::new("/r")).unwrap().into_iter().map(|e| e.unwrap()).map(|e| (e.path, e.kind)).collect();
+        names.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            names,
+            vec![
+                (PathBuf::from("/r/a.0001.exr"), FileKind::File),
+                (PathBuf::from("/r/a.0002.exr"), FileKind::File),
+                (PathBuf::from("/r/sub"), FileKind::Dir),
+            ]
+        );
+        assert_eq!(fs.metadata(Path::new("/r/a.0002.exr")).unwrap().size, 20);
+        assert!(fs.metadata(Path::new("/r/nope")).is_err());
+        assert!(fs.read_dir(Path::new("/r/a.0001.exr")).is_err());
+    }
+
+    #[test]
+    fn mem_fs_resolves_links() {
+        let fs = MemFs::new();
+        fs.add_file("/store/v3/f.0001.exr", 1).add_symlink("/pub/latest", "../store/v3").add_symlink("/pub/gone", "/store/v9");
+        fs.add_symlink("/loop/a", "b").add_symlink("/loop/b", "a");
+
+        assert_eq!(fs.canonicalize(Path::new("/pub/latest/f.0001.exr")).unwrap(), PathBuf::from("/store/v3/f.0001.exr"));
+        assert!(fs.metadata(Path::new("/pub/latest")).unwrap().is_dir());
+        let listed = fs.read_dir(Path::new("/pub/latest")).unwrap();
+        assert_eq!(listed[0].as_ref().unwrap().path, PathBuf::from("/pub/latest/f.0001.exr"));
+        assert_eq!(fs.metadata(Path::new("/pub/gone")).unwrap_err().kind(), io::ErrorKind::NotFound);
+        assert!(fs.metadata(Path::new("/loop/a")).is_err());
+    }
+
+    #[test]
+    fn mem_fs_changes_bump_dir_mtime() {
+        let fs = MemFs::new();
+        fs.add_file("/r/a.0001.exr", 1);
+        let old = SystemTime::UNIX_EPOCH + Duration::from_secs(1);
+        fs.set_mtime("/r", old).unwrap();
+        assert!(fs.remove("/r/a.0001.exr"));
+        assert!(!fs.remove("/r/a.0001.exr"));
+        assert_ne!(fs.metadata(Path::new("/r")).unwrap().mtime, Some(old));
+        assert!(fs.read_dir(Path::new("/r")).unwrap().is_empty());
+    }
+}
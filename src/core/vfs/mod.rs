@@ -0,0 +1,160 @@
+//! Filesystem abstraction used by the scanner.
+//!
+//! Everything the scanner needs from a filesystem is listing a directory,
+//! stat-ing a path and resolving symlinks. [`Fs`] captures exactly that, so a
+//! scan can run against the local disk ([`StdFs`], the default), an in-memory
+//! tree ([`MemFs`], for tests and fixtures) or any remote store that can list
+//! and stat (an asset server, an object store with a path layout).
+//!
+//! Plug an implementation in through [`ScanOptions::fs`](super::ScanOptions::fs)
+//! or [`ScannerBuilder::fs`](super::ScannerBuilder::fs).
+
+// Only library users construct an in-memory tree; the CLI never does.
+#[allow(dead_code)]
+mod mem;
+
+pub use mem::MemFs;
+
+use super::error::ScanError;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// What a directory entry or path is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileKind {
+    File,
+    Dir,
+    Symlink,
+    /// Sockets, devices, FIFOs... never reported as frames
+    Other,
+}
+
+/// The subset of file metadata the scanner uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FsMetadata {
+    pub kind: FileKind,
+    /// Size in bytes (0 for directories if the backend doesn't know better)
+    pub size: u64,
+    pub mtime: Option<SystemTime>,
+    /// Inode or any stable per-file id; with `dev` it identifies directories
+    /// for symlink cycle detection. `None` = fall back to the canonical path.
+    pub inode: Option<u64>,
+    /// Device id, for `same_file_system`. `None` = never treated as a mount boundary.
+    pub dev: Option<u64>,
+}
+
+impl FsMetadata {
+    #[must_use]
+    pub fn is_dir(&self) -> bool {
+        self.kind == FileKind::Dir
+    }
+
+    #[must_use]
+    pub fn is_file(&self) -> bool {
+        self.kind == FileKind::File
+    }
+}
+
+/// One entry of a directory listing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FsEntry {
+    /// Full path: the listed directory joined with the entry name
+    pub path: PathBuf,
+    /// Type of the entry itself (a symlink is [`FileKind::Symlink`], not its target)
+    pub kind: FileKind,
+    /// Metadata, if the listing already carried it. Saves a [`Fs::metadata`]
+    /// round trip per frame on backends that return it with the listing.
+    pub meta: Option<FsMetadata>,
+}
+
+impl FsEntry {
+    /// Entry name (last path component).
+    #[must_use]
+    pub fn name(&self) -> Option<&std::ffi::OsStr> {
+        self.path.file_name()
+    }
+}
+
+/// Filesystem operations used by the scanner. Implementations must be usable
+/// from many threads at once: folders are listed in parallel.
+pub trait Fs: Send + Sync + std::fmt::Debug {
+    /// List the entries of directory `path` (not recursive, any order, without `.`/`..`).
+    /// An entry that can't be read is a [`ScanError::Io`] on its path, so the
+    /// scan can report it; an unreadable directory fails the whole listing.
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<Result<FsEntry, ScanError>>>;
+
+    /// Metadata of `path`, following symlinks.
+    fn metadata(&self, path: &Path) -> io::Result<FsMetadata>;
+
+    /// Absolute path of `path` with all symlinks resolved. The default returns
+    /// the path unchanged, which suits backends without links.
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        Ok(path.to_path_buf())
+    }
+
+    /// Open a file for reading. Only needed to look inside archives and to
+    /// read frames; the default reports [`io::ErrorKind::Unsupported`].
+    fn open(&self, path: &Path) -> io::Result<Box<dyn ReadSeek>> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, format!("{}: filesystem cannot open files", path.display())))
+    }
+}
+
+/// Seekable reader returned by [`Fs::open`].
+pub trait ReadSeek: io::Read + io::Seek + Send {}
+
+impl<T: io::Read + io::Seek + Send> ReadSeek for T {}
+
+/// The local filesystem through `std::fs`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdFs;
+
+fn std_kind(t: std::fs::FileType) -> FileKind {
+    if t.is_symlink() {
+        FileKind::Symlink
+    } else if t.is_dir() {
+        FileKind::Dir
+    } else if t.is_file() {
+        FileKind::File
+    } else {
+        FileKind::Other
+    }
+}
+
+impl From<&std::fs::Metadata> for FsMetadata {
+    fn from(m: &std::fs::Metadata) -> Self {
+        #[cfg(unix)]
+        let (inode, dev) = {
+            use std::os::unix::fs::MetadataExt;
+            (Some(m.ino()), Some(m.dev()))
+        };
+        #[cfg(not(unix))]
+        let (inode, dev) = (None, None);
+        FsMetadata { kind: std_kind(m.file_type()), size: m.len(), mtime: m.modified().ok(), inode, dev }
+    }
+}
+
+impl Fs for StdFs {
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<Result<FsEntry, ScanError>>> {
+        Ok(std::fs::read_dir(path)?
+            .map(|e| {
+                // A failed iteration step has no entry name: blame the directory
+                let e = e.map_err(|err| ScanError::io(path, err))?;
+                let kind = std_kind(e.file_type().map_err(|err| ScanError::io(e.path(), err))?);
+                Ok(FsEntry { path: e.path(), kind, meta: None })
+            })
+            .collect())
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<FsMetadata> {
+        std::fs::metadata(path).map(|m| FsMetadata::from(&m))
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        std::fs::canonicalize(path)
+    }
+
+    fn open(&self, path: &Path) -> io::Result<Box<dyn ReadSeek>> {
+        Ok(Box::new(io::BufReader::new(std::fs::File::open(path)?)))
+    }
+}
@@ -0,0 +1,622 @@
+//! Watch mode: keep a [`Scanner`]'s result current while frames land.
+//!
+//! [`Scanner::watch`] hands the scanner to a [`Watch`], which regroups only the
+//! folders that changed (phase 2 of the scan, per folder) and reports the
+//! difference as [`WatchEvent`]s. Two backends:
+//! - **Native** (feature `watch`): inotify / FSEvents / ReadDirectoryChangesW via
+//!   the `notify` crate. Bursts are debounced into one regroup per folder.
+//! - **Poll**: re-lists the folder tree every interval and regroups folders whose
+//!   mtime changed. Use it on network mounts (NFS/SMB), where change
+//!   notifications from other hosts never arrive.
+//!
+//! The watch is pull-based: call [`Watch::wait`] from a UI loop, or iterate the
+//! `Watch` to block on events one at a time.
+
+use super::error::ScanError;
+use super::scan::{dedup_roots, folder_seqs, scan_dirs};
+use super::sort::sort_seqs;
+use super::vfs::Fs;
+use super::{Scanner, Seq};
+use log::warn;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Change to the scanner's sequences, as reported by [`Watch`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum WatchEvent {
+    /// A new sequence appeared (reached `min_len`).
+    Created { seq: Seq },
+    /// Frames were added to an existing sequence.
+    FramesAdded { pattern: String, frames: Vec<i64> },
+    /// Frames were removed from an existing sequence.
+    FramesRemoved { pattern: String, frames: Vec<i64> },
+    /// A sequence that was growing has had no changes for the settle period and
+    /// has no gaps - the render is most likely done.
+    Completed { seq: Seq },
+    /// A sequence disappeared (deleted, or fell below `min_len`).
+    Removed { pattern: String },
+    /// A folder could not be rescanned, or the native watcher reported an error.
+    Error { message: String },
+}
+
+/// How [`Watch`] learns about changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchMode {
+    /// OS change notifications. Local file systems only.
+    #[cfg(feature = "watch")]
+    Native,
+    /// Re-list the folder tree at this interval and compare folder mtimes.
+    Poll(Duration),
+}
+
+impl Default for WatchMode {
+    /// Native with the `watch` feature, otherwise polling every 2 seconds.
+    fn default() -> Self {
+        #[cfg(feature = "watch")]
+        return WatchMode::Native;
+        #[cfg(not(feature = "watch"))]
+        return WatchMode::Poll(Duration::from_secs(2));
+    }
+}
+
+/// Settings for [`Scanner::watch_with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatchOptions {
+    /// Backend
+    pub mode: WatchMode,
+    /// Quiet period after the last change before a growing sequence is reported
+    /// as [`WatchEvent::Completed`].
+    pub settle: Duration,
+    /// Native mode: how long to keep collecting notifications after the first
+    /// one before regrouping, so a burst of frames costs one regroup. Never
+    /// extends past the timeout of [`Watch::wait`].
+    pub debounce: Duration,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        WatchOptions { mode: WatchMode::default(), settle: Duration::from_secs(10), debounce: Duration::from_millis(200) }
+    }
+}
+
+enum Backend {
+    Poll {
+        interval: Duration,
+        next: Instant,
+    },
+    #[cfg(feature = "watch")]
+    Native {
+        // Dropping the watcher stops notifications, so it lives as long as the Watch
+        _watcher: notify::RecommendedWatcher,
+        rx: std::sync::mpsc::Receiver<notify::Result<notify::Event>>,
+    },
+}
+
+/// A [`Scanner`] being kept up to date. Created by [`Scanner::watch`].
+pub struct Watch {
+    scanner: Scanner,
+    options: WatchOptions,
+    backend: Backend,
+    /// Folders inside the scan window, with their mtime at the last regroup
+    folders: HashMap<PathBuf, Option<SystemTime>>,
+    /// Canonical folder path -> scanned folder path, to map native event paths back
+    canonical: HashMap<PathBuf, PathBuf>,
+    /// Growing sequences (by pattern) and when they last changed
+    pending: HashMap<String, Instant>,
+    queue: VecDeque<WatchEvent>,
+}
+
+/// Folder part of a path or pattern, with `/` separators (as used in patterns).
+fn folder_key(path: &str) -> &str {
+    path.rfind('/').map_or("", |i| &path[..i])
+}
+
+fn dir_mtime(fs: &dyn Fs, path: &Path) -> Option<SystemTime> {
+    fs.metadata(path).ok()?.mtime
+}
+
+/// Frames in `a` but not in `b` (both sorted).
+fn frames_diff(a: &[i64], b: &[i64]) -> Vec<i64> {
+    a.iter().filter(|f| b.binary_search(f).is_err()).copied().collect()
+}
+
+/// Typed error for a watcher failure; I/O failures keep their kind.
+#[cfg(feature = "watch")]
+fn notify_error(path: Option<&Path>, e: notify::Error) -> ScanError {
+    let path = path.map(Path::to_path_buf);
+    match e.kind {
+        notify::ErrorKind::Io(source) => ScanError::Io { path, source: source.into() },
+        notify::ErrorKind::PathNotFound => ScanError::Io { path, source: std::io::Error::from(std::io::ErrorKind::NotFound).into() },
+        _ if path.is_some() => ScanError::Watch { path, message: e.to_string() },
+        _ => ScanError::Watch { path, message: format!("Failed to start watcher: {}", e) },
+    }
+}
+
+impl Scanner {
+    /// Watch the roots with default [`WatchOptions`] (native notifications with
+    /// the `watch` feature, polling otherwise). The current `result` is the
+    /// baseline: only later changes produce events.
+    #[allow(dead_code)] // Public API
+    pub fn watch(self) -> Result<Watch, ScanError> {
+        self.watch_with(WatchOptions::default())
+    }
+
+    /// Watch the roots with explicit [`WatchOptions`].
+    #[allow(dead_code)] // Public API
+    pub fn watch_with(self, options: WatchOptions) -> Result<Watch, ScanError> {
+        let backend = match options.mode {
+            WatchMode::Poll(interval) => Backend::Poll { interval, next: Instant::now() + interval },
+            #[cfg(feature = "watch")]
+            WatchMode::Native => {
+                use notify::Watcher;
+                let (tx, rx) = std::sync::mpsc::channel();
+                let mut watcher = notify::recommended_watcher(tx).map_err(|e| notify_error(None, e))?;
+                let recursive = if self.recursive { notify::RecursiveMode::Recursive } else { notify::RecursiveMode::NonRecursive };
+                for root in &self.roots {
+                    watcher.watch(Path::new(root), recursive).map_err(|e| notify_error(Some(Path::new(root)), e))?;
+                }
+                Backend::Native { _watcher: watcher, rx }
+            }
+        };
+        let mut watch = Watch {
+            scanner: self,
+            options,
+            backend,
+            folders: HashMap::new(),
+            canonical: HashMap::new(),
+            pending: HashMap::new(),
+            queue: VecDeque::new(),
+        };
+        watch.discover();
+        Ok(watch)
+    }
+}
+
+impl Watch {
+    /// The scanner, with `result` reflecting every change reported so far.
+    #[must_use]
+    #[allow(dead_code)] // Public API
+    pub fn scanner(&self) -> &Scanner {
+        &self.scanner
+    }
+
+    /// Stop watching and get the scanner back.
+    #[must_use]
+    #[allow(dead_code)] // Public API
+    pub fn into_scanner(self) -> Scanner {
+        self.scanner
+    }
+
+    /// Wait up to `timeout` for changes and return the resulting events, as soon
+    /// as there are any (empty if nothing happened). Completion of a settled
+    /// sequence is reported once its quiet period has passed.
+    #[allow(dead_code)] // Public API
+    pub fn wait(&mut self, timeout: Duration) -> Vec<WatchEvent> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            self.step(deadline.saturating_duration_since(Instant::now()));
+            if !self.queue.is_empty() || Instant::now() >= deadline {
+                return self.queue.drain(..).collect();
+            }
+        }
+    }
+
+    /// Re-list the folder tree, returning folders that appeared or disappeared.
+    fn discover(&mut self) -> HashSet<PathBuf> {
+        let mut current = HashSet::new();
+        // Overlapping roots were reported by the baseline scan
+        let (roots, _) = dedup_roots(&self.scanner.roots, self.scanner.recursive, &self.scanner.options);
+        for root in &roots {
+            // Unreadable folders below the root are already logged by the walk
+            match scan_dirs(root, self.scanner.recursive, &self.scanner.options, &mut Vec::new()) {
+                Ok(dirs) => current.extend(dirs),
+                Err(e) => warn!("Watch: cannot list {}: {}", root.display(), e),
+            }
+        }
+        let mut changed: HashSet<PathBuf> = self.folders.keys().filter(|f| !current.contains(*f)).cloned().collect();
+        for gone in &changed {
+            self.folders.remove(gone);
+        }
+        for folder in current {
+            if !self.folders.contains_key(&folder) {
+                let mtime = dir_mtime(self.scanner.options.fs(), &folder);
+                if let Ok(canon) = self.scanner.options.fs().canonicalize(&folder) {
+                    self.canonical.insert(canon, folder.clone());
+                }
+                self.folders.insert(folder.clone(), mtime);
+                changed.insert(folder);
+            }
+        }
+        changed
+    }
+
+    /// Map a path reported by the OS to a known folder.
+    #[cfg(feature = "watch")]
+    fn known_folder(&self, path: &Path) -> Option<PathBuf> {
+        if self.folders.contains_key(path) {
+            return Some(path.to_path_buf());
+        }
+        let canon = path.canonicalize().ok()?;
+        self.canonical.get(&canon).cloned()
+    }
+
+    /// Collect changed folders from the backend (waiting up to `timeout`, less if a
+    /// pending sequence settles sooner), regroup them and queue events.
+    fn step(&mut self, timeout: Duration) {
+        // Wake up in time to report sequences that settle while nothing else happens
+        let now = Instant::now();
+        #[cfg(feature = "watch")]
+        let deadline = now + timeout;
+        let timeout = self
+            .pending
+            .values()
+            .map(|&t| t + self.options.settle)
+            .filter(|&due| due > now)
+            .map(|due| due - now)
+            .min()
+            .map_or(timeout, |settle| timeout.min(settle));
+
+        let dirty = match &mut self.backend {
+            Backend::Poll { interval, next } => {
+                let wait = next.saturating_duration_since(Instant::now());
+                if wait > timeout {
+                    std::thread::sleep(timeout);
+                    None
+                } else {
+                    std::thread::sleep(wait);
+                    *next = Instant::now() + *interval;
+                    Some(self.poll_dirty())
+                }
+            }
+            #[cfg(feature = "watch")]
+            Backend::Native { rx, .. } => {
+                let mut events = Vec::new();
+                if let Ok(first) = rx.recv_timeout(timeout) {
+                    events.push(first);
+                    // The rest of the burst, counted from the first notification (a render
+                    // writing frames nonstop would otherwise keep this going forever)
+                    let until = (Instant::now() + self.options.debounce).min(deadline);
+                    while let Some(left) = until.checked_duration_since(Instant::now()).filter(|left| !left.is_zero()) {
+                        match rx.recv_timeout(left) {
+                            Ok(ev) => events.push(ev),
+                            Err(_) => break,
+                        }
+                    }
+                }
+                (!events.is_empty()).then(|| self.native_dirty(events))
+            }
+        };
+
+        if let Some(dirty) = dirty {
+            self.regroup_all(dirty);
+        }
+        self.settle();
+    }
+
+    /// Regroup the `dirty` folders, in path order.
+    fn regroup_all(&mut self, dirty: HashSet<PathBuf>) {
+        let mut dirty: Vec<PathBuf> = dirty.into_iter().collect();
+        dirty.sort();
+        for folder in dirty {
+            self.regroup(&folder);
+        }
+    }
+
+    /// Poll backend: folders whose mtime changed, plus folders that appeared or vanished.
+    fn poll_dirty(&mut self) -> HashSet<PathBuf> {
+        let mut dirty = self.discover();
+        for (folder, mtime) in &self.folders {
+            if !dirty.contains(folder) && dir_mtime(self.scanner.options.fs(), folder) != *mtime {
+                dirty.insert(folder.clone());
+            }
+        }
+        dirty
+    }
+
+    /// Native backend: folders touched by a batch of notifications.
+    #[cfg(feature = "watch")]
+    fn native_dirty(&mut self, events: Vec<notify::Result<notify::Event>>) -> HashSet<PathBuf> {
+        use notify::event::{CreateKind, EventKind, RemoveKind};
+        let mut dirty = HashSet::new();
+        let mut rediscover = false;
+        for ev in events {
+            let ev = match ev {
+                Ok(ev) => ev,
+                Err(e) => {
+                    self.queue.push_back(WatchEvent::Error { message: e.to_string() });
+                    continue;
+                }
+            };
+            log::debug!("Watch: {:?} {:?}", ev.kind, ev.paths);
+            for path in ev.paths {
+                // A folder itself created, removed or renamed: the tree changed
+                let folder_event = matches!(ev.kind, EventKind::Create(CreateKind::Folder) | EventKind::Remove(RemoveKind::Folder));
+                if folder_event || path.is_dir() || self.folders.contains_key(&path) {
+                    rediscover = true;
+                }
+                if let Some(folder) = path.parent().and_then(|p| self.known_folder(p)) {
+                    dirty.insert(folder);
+                }
+            }
+        }
+        if rediscover {
+            dirty.extend(self.discover());
+        }
+        dirty
+    }
+
+    /// Rescan one folder, replace its sequences in the result and queue the differences.
+    fn regroup(&mut self, folder: &Path) {
+        let scanner = &mut self.scanner;
+        let key = folder.to_string_lossy().replace('\\', "/");
+        let key = key.trim_end_matches('/');
+
+        let new_seqs = if self.folders.contains_key(folder) {
+            self.folders.insert(folder.to_path_buf(), dir_mtime(scanner.options.fs(), folder));
+            let mut errors = Vec::new();
+            let seqs = folder_seqs(folder, scanner.mask.as_deref(), scanner.min_len, &scanner.options, &mut errors);
+            self.queue.extend(errors.iter().map(|e| WatchEvent::Error { message: e.to_string() }));
+            match seqs {
+                Ok(seqs) => seqs,
+                Err(e) => {
+                    // Keep the last known state rather than reporting everything removed
+                    self.queue.push_back(WatchEvent::Error { message: e.to_string() });
+                    return;
+                }
+            }
+        } else {
+            Vec::new()
+        };
+
+        let (old_seqs, kept): (Vec<Seq>, Vec<Seq>) = std::mem::take(&mut scanner.result.seqs).into_iter().partition(|s| folder_key(s.pattern()) == key);
+        scanner.result.seqs = kept;
+
+        let now = Instant::now();
+        for new in &new_seqs {
+            match old_seqs.iter().find(|old| old.pattern() == new.pattern()) {
+                None => {
+                    self.pending.insert(new.pattern().to_string(), now);
+                    self.queue.push_back(WatchEvent::Created { seq: new.clone() });
+                }
+                Some(old) => {
+                    let added = frames_diff(&new.indices, &old.indices);
+                    let removed = frames_diff(&old.indices, &new.indices);
+                    if !added.is_empty() || !removed.is_empty() {
+                        self.pending.insert(new.pattern().to_string(), now);
+                    }
+                    if !added.is_empty() {
+                        self.queue.push_back(WatchEvent::FramesAdded { pattern: new.pattern().to_string(), frames: added });
+                    }
+                    if !removed.is_empty() {
+                        self.queue.push_back(WatchEvent::FramesRemoved { pattern: new.pattern().to_string(), frames: removed });
+                    }
+                }
+            }
+        }
+        for old in &old_seqs {
+            if !new_seqs.iter().any(|new| new.pattern() == old.pattern()) {
+                self.pending.remove(old.pattern());
+                self.queue.push_back(WatchEvent::Removed { pattern: old.pattern().to_string() });
+            }
+        }
+        scanner.result.seqs.extend(new_seqs);
+        sort_seqs(&mut scanner.result.seqs, scanner.options.sort, scanner.options.natural_sort);
+    }
+
+    /// Report growing sequences that have been quiet for the settle period.
+    fn settle(&mut self) {
+        let now = Instant::now();
+        let settle = self.options.settle;
+        let seqs = &self.scanner.result.seqs;
+        let queue = &mut self.queue;
+        self.pending.retain(|pattern, &mut changed| {
+            if now.duration_since(changed) < settle {
+                return true;
+            }
+            // Still gaps: keep waiting for the missing frames
+            match seqs.iter().find(|s| s.pattern() == pattern) {
+                Some(seq) if seq.is_complete() => {
+                    queue.push_back(WatchEvent::Completed { seq: seq.clone() });
+                    false
+                }
+                Some(_) => true,
+                None => false,
+            }
+        });
+    }
+}
+
+/// Blocks until the next event. Never ends; drop the iterator to stop watching.
+impl Iterator for Watch {
+    type Item = WatchEvent;
+
+    fn next(&mut self) -> Option<WatchEvent> {
+        loop {
+            if let Some(ev) = self.queue.pop_front() {
+                return Some(ev);
+            }
+            self.step(Duration::from_secs(1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::MemFs;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    #[cfg(feature = "watch")]
+    fn touch(dir: &Path, name: &str) {
+        std::fs::write(dir.join(name), b"x").expect("write frame");
+    }
+
+    /// Poll-mode watch of `root` on an in-memory tree, so tests control
+    /// folder mtimes instead of racing the disk's timestamp granularity.
+    fn mem_watch(mem: &Arc<MemFs>, root: &str, settle: Duration) -> Watch {
+        let scanner = Scanner::path(root).recursive(true).fs(Arc::clone(mem) as Arc<dyn Fs>).scan();
+        let options = WatchOptions { mode: WatchMode::Poll(Duration::from_millis(10)), settle, ..Default::default() };
+        scanner.watch_with(options).expect("watch")
+    }
+
+    /// Give `dir` an mtime no earlier change has had.
+    fn bump(mem: &MemFs, dir: &str) {
+        static NEXT: AtomicU64 = AtomicU64::new(1);
+        let when = SystemTime::UNIX_EPOCH + Duration::from_secs(NEXT.fetch_add(1, Ordering::Relaxed));
+        mem.set_mtime(dir, when).expect("set mtime");
+    }
+
+    fn add_frame(mem: &MemFs, dir: &str, name: &str) {
+        mem.add_file(format!("{dir}/{name}"), 1);
+        bump(mem, dir);
+    }
+
+    /// The manual tick: one poll, now, without waiting for the interval.
+    fn poll(watch: &mut Watch) -> Vec<WatchEvent> {
+        let dirty = watch.poll_dirty();
+        watch.regroup_all(dirty);
+        watch.settle();
+        watch.queue.drain(..).collect()
+    }
+
+    /// Move the settle clock on: every growing sequence last changed `by` earlier.
+    fn age(watch: &mut Watch, by: Duration) {
+        for changed in watch.pending.values_mut() {
+            *changed = changed.checked_sub(by).expect("monotonic clock too young");
+        }
+    }
+
+    #[test]
+    fn poll_reports_created_added_removed() {
+        let mem = Arc::new(MemFs::new());
+        mem.add_dir("/r/shot");
+        let mut watch = mem_watch(&mem, "/r", Duration::from_secs(60));
+        assert!(poll(&mut watch).is_empty());
+
+        // Through `wait` once: returns as soon as the poll thread sees the change
+        add_frame(&mem, "/r/shot", "beauty.0001.exr");
+        add_frame(&mem, "/r/shot", "beauty.0002.exr");
+        let events = watch.wait(Duration::from_secs(10));
+        assert!(matches!(&events[..], [WatchEvent::Created { seq }] if seq.indices == vec![1, 2]), "{events:?}");
+
+        add_frame(&mem, "/r/shot", "beauty.0003.exr");
+        let events = poll(&mut watch);
+        assert!(matches!(&events[..], [WatchEvent::FramesAdded { frames, .. }] if frames == &vec![3]), "{events:?}");
+        assert_eq!(watch.scanner().result.seqs[0].indices, vec![1, 2, 3]);
+
+        assert!(mem.remove("/r/shot/beauty.0001.exr"));
+        bump(&mem, "/r/shot");
+        let events = poll(&mut watch);
+        assert!(matches!(&events[..], [WatchEvent::FramesRemoved { frames, .. }] if frames == &vec![1]), "{events:?}");
+
+        assert!(mem.remove("/r/shot"));
+        let events = poll(&mut watch);
+        assert!(matches!(&events[..], [WatchEvent::Removed { .. }]), "{events:?}");
+        assert!(watch.scanner().result.seqs.is_empty());
+    }
+
+    #[test]
+    fn poll_reports_completed_after_settle() {
+        let settle = Duration::from_secs(60);
+        let mem = Arc::new(MemFs::new());
+        mem.add_dir("/r");
+        let mut watch = mem_watch(&mem, "/r", settle);
+
+        add_frame(&mem, "/r", "fx.0001.exr");
+        add_frame(&mem, "/r", "fx.0003.exr");
+        let events = poll(&mut watch);
+        assert!(matches!(&events[..], [WatchEvent::Created { .. }]), "{events:?}");
+
+        // Gap at 2: settled, but not complete
+        age(&mut watch, settle);
+        assert!(poll(&mut watch).is_empty());
+
+        add_frame(&mem, "/r", "fx.0002.exr");
+        let events = poll(&mut watch);
+        assert!(matches!(&events[..], [WatchEvent::FramesAdded { .. }]), "{events:?}");
+        assert!(poll(&mut watch).is_empty(), "not quiet for the settle period yet");
+        age(&mut watch, settle);
+        let events = poll(&mut watch);
+        assert!(matches!(&events[..], [WatchEvent::Completed { seq }] if seq.indices == vec![1, 2, 3]), "{events:?}");
+    }
+
+    #[test]
+    fn unchanged_folders_keep_their_sequences() {
+        let mem = Arc::new(MemFs::new());
+        for dir in ["/r/a", "/r/b"] {
+            add_frame(&mem, dir, "img.0001.exr");
+            add_frame(&mem, dir, "img.0002.exr");
+        }
+        let mut watch = mem_watch(&mem, "/r", Duration::from_secs(60));
+        assert_eq!(watch.scanner().result.seqs.len(), 2);
+
+        add_frame(&mem, "/r/b", "img.0003.exr");
+        let events = poll(&mut watch);
+        assert_eq!(events.len(), 1, "{events:?}");
+        assert_eq!(watch.scanner().result.seqs.len(), 2);
+        let total: usize = watch.scanner().result.seqs.iter().map(|s| s.len()).sum();
+        assert_eq!(total, 5);
+    }
+
+    #[cfg(feature = "watch")]
+    #[test]
+    fn native_reports_new_frames() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let scanner = Scanner::path(tmp.path()).recursive(true).scan();
+        let options = WatchOptions { mode: WatchMode::Native, debounce: Duration::from_millis(50), ..Default::default() };
+        let mut watch = scanner.watch_with(options).expect("watch");
+
+        touch(tmp.path(), "beauty.0001.exr");
+        touch(tmp.path(), "beauty.0002.exr");
+        let mut events = Vec::new();
+        for _ in 0..20 {
+            events.extend(watch.wait(Duration::from_millis(100)));
+            if !events.is_empty() {
+                break;
+            }
+        }
+        assert!(events.iter().any(|e| matches!(e, WatchEvent::Created { .. })), "{events:?}");
+        assert_eq!(watch.scanner().result.seqs.len(), 1);
+    }
+
+    #[cfg(feature = "watch")]
+    #[test]
+    fn native_wait_returns_during_nonstop_writes() {
+        use std::sync::atomic::AtomicBool;
+
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let scanner = Scanner::path(tmp.path()).recursive(true).scan();
+        let options = WatchOptions { mode: WatchMode::Native, debounce: Duration::from_millis(100), ..Default::default() };
+        let mut watch = scanner.watch_with(options).expect("watch");
+
+        // A render writing a frame every few milliseconds, for longer than the test should take
+        let stop = Arc::new(AtomicBool::new(false));
+        let writer = {
+            let (dir, stop) = (tmp.path().to_path_buf(), Arc::clone(&stop));
+            std::thread::spawn(move || {
+                for f in 1..2000 {
+                    if stop.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    touch(&dir, &format!("beauty.{f:04}.exr"));
+                    std::thread::sleep(Duration::from_millis(2));
+                }
+            })
+        };
+        std::thread::sleep(Duration::from_millis(20));
+        let start = Instant::now();
+        let mut events = Vec::new();
+        while events.is_empty() && start.elapsed() < Duration::from_secs(2) {
+            events.extend(watch.wait(Duration::from_millis(300)));
+        }
+        let elapsed = start.elapsed();
+        stop.store(true, Ordering::Relaxed);
+        writer.join().expect("writer");
+        assert!(elapsed < Duration::from_secs(3), "wait took {elapsed:?}");
+        assert!(!events.is_empty());
+    }
+}
@@ -5,7 +5,7 @@
 //!
 //! # Features
 //!
-//! - **Parallel Scanning**: Uses jwalk for fast directory traversal
+//! - **Parallel Scanning**: Parallel directory traversal over a pluggable filesystem
 //! - **Memory Efficient**: Pre-computed digit groups, mask-based grouping
 //! - **Smart Detection**: Automatically picks longest sequence when files have multiple number groups
 //! - **Missing Frame Tracking**: Identifies gaps in sequences automatically
@@ -127,7 +127,7 @@
 //!
 //! # Algorithm Overview
 //!
-//! 1. **Scan**: Discover directories using jwalk (parallel)
+//! 1. **Scan**: Discover directories (parallel walk, pluggable filesystem)
 //! 2. **Parse**: Extract digit groups from filenames, create masks
 //! 3. **Group**: Hash by mask (e.g., `render_@.exr`), sub-group by anchors
 //! 4. **Detect**: Find frame numbers, compute padding, identify gaps
@@ -136,12 +136,25 @@
 
 pub mod core;
 
+// File operations and their journal also live under `scanseq::ops`
+// (`scanseq::ops::Journal`), like the module they come from.
+pub use core::ops;
+
 // Top-level re-exports of the main public types so Rust consumers can write
 // `scanseq::Seq` / `scanseq::detect` / `scanseq::Scanner` without the `core::`
 // prefix (matches how downstream crates already import them, e.g. exr-view).
 pub use core::{
-    detect, format_frame, frame_of, get_seqs, scan_files, DetectError, FrameSlice, FrameSliceError,
-    ScanResult, ScannerBuilder, Seq,
+    detect, duplicate_frames, format_frame, format_frame_ranges, frame_of, get_seqs, get_seqs_result, get_seqs_with,
+    group_inventory, group_paths, list_archive, natural_cmp, open_path, parse_frame_ranges, read_find_printf,
+    read_inventory, read_inventory_file, read_rclone_lsjson, read_s3_inventory, scan_files, scan_files_with, sort_seqs,
+    split_archive_path, ArchiveIndex, ArchiveKind, ArchiveMember, ChecksumAlgo, ChecksumManifest, DetectError, DuplicateGroup,
+    DuplicateMember, DuplicateOptions, FileKind, FileOp, FillMethod, FillSource, FillStrategy, FrameDigest, FrameInfo,
+    FrameMap, FrameSelection, FrameSlice, FrameSliceError, Fs, FsEntry, FsMetadata, GroupOptions, HeldRun, InventoryEntry,
+    InventoryFormat, Journal, JournalEntry, LinkKind, LinkOptions, LinkPaths, MemFs, NameFields, NameTemplate, OnExists,
+    OpError, OpKind, OpReport, OpStatus, PathOpener, ReadSeek, RemoveMode, Renumber, ScanCache, ScanError, ScanOptions, ScanResult,
+    ScanWarning, ScannerBuilder, Seq, SortKey, StaleRule, StdFs, SuspectFrame, SuspectReason, SuspectThresholds,
+    TransferOptions, VerifyReport, Watch, WatchEvent, WatchMode, WatchOptions, ARCHIVE_SEP, CACHE_VERSION, MANIFEST_VERSION,
+    S3_DEFAULT_SCHEMA,
 };
 // `Scanner` is lifted to the crate root only when the Python extension is NOT
 // built — with `python` the crate root already defines a PyO3 `Scanner` class.
@@ -154,13 +167,72 @@ use pyo3::prelude::*;
 use pyo3::types::PyDict;
 #[cfg(feature = "python")]
 use std::sync::Arc;
-#[cfg(feature = "python")]
-use std::time::Instant;
 
 #[cfg(feature = "python")]
 use core::Seq as CoreSeq;
+
+/// Python exception classes for [`core::ScanError`]: `ScanError` and one
+/// subclass per failure that callers handle differently. Every instance, raised
+/// or listed in `ScanResult.errors`, carries `kind` and `path` attributes.
+#[cfg(feature = "python")]
+mod py_errors {
+    use pyo3::exceptions::{PyException, PyValueError};
+    use pyo3::prelude::*;
+    use pyo3::sync::GILOnceCell;
+    use pyo3::types::{PyDict, PyType};
+
+    pyo3::create_exception!(scanseq, ScanError, PyException, "Scan, listing or expansion failed.");
+    pyo3::create_exception!(scanseq, NotFoundError, ScanError, "A root or file does not exist.");
+    pyo3::create_exception!(scanseq, PermissionDeniedError, ScanError, "A root or file is not readable.");
+    pyo3::create_exception!(scanseq, InvalidMaskError, ScanError, "The file mask is not a valid glob pattern.");
+    pyo3::create_exception!(scanseq, ParseError, ScanError, "Malformed archive or listing.");
+
+    static RANGE_TOO_LARGE: GILOnceCell<Py<PyType>> = GILOnceCell::new();
+
+    /// `RangeTooLargeError(ScanError, ValueError)`: `create_exception!` takes a
+    /// single base, and `expand` raised `ValueError` before it had its own class.
+    pub fn range_too_large(py: Python<'_>) -> PyResult<&Bound<'_, PyType>> {
+        RANGE_TOO_LARGE
+            .get_or_try_init(py, || {
+                let dict = PyDict::new(py);
+                dict.set_item("__module__", "scanseq")?;
+                dict.set_item("__doc__", "Frame range too large to expand.")?;
+                let bases = (py.get_type::<ScanError>(), py.get_type::<PyValueError>());
+                Ok::<_, PyErr>(py.get_type::<PyType>().call1(("RangeTooLargeError", bases, dict))?.downcast_into::<PyType>()?.unbind())
+            })
+            .map(|ty| ty.bind(py))
+    }
+}
+
+/// Exception instance for `e`, of its `py_errors` class, with `kind`
+/// (`"not_found"`, `"permission_denied"`, `"io"`, `"invalid_mask"`,
+/// `"range_too_large"`, `"parse"`, `"watch"` or `"thread_pool"`) and `path`
+/// (`None` when the error has none).
 #[cfg(feature = "python")]
-use rayon::prelude::*;
+fn scan_err_value<'py>(py: Python<'py>, e: &core::ScanError) -> PyResult<Bound<'py, pyo3::exceptions::PyBaseException>> {
+    use py_errors::*;
+    let msg = e.to_string();
+    let (err, kind) = match (e, e.kind()) {
+        (_, Some(std::io::ErrorKind::NotFound)) => (NotFoundError::new_err(msg), "not_found"),
+        (_, Some(std::io::ErrorKind::PermissionDenied)) => (PermissionDeniedError::new_err(msg), "permission_denied"),
+        (core::ScanError::Io { .. }, _) => (ScanError::new_err(msg), "io"),
+        (core::ScanError::InvalidMask { .. }, _) => (InvalidMaskError::new_err(msg), "invalid_mask"),
+        (core::ScanError::RangeTooLarge { .. }, _) => (PyErr::from_type(range_too_large(py)?.clone(), msg), "range_too_large"),
+        (core::ScanError::Parse { .. }, _) => (ParseError::new_err(msg), "parse"),
+        (core::ScanError::Watch { .. }, _) => (ScanError::new_err(msg), "watch"),
+        (core::ScanError::ThreadPool(_), _) => (ScanError::new_err(msg), "thread_pool"),
+    };
+    let value = err.into_value(py).into_bound(py);
+    value.setattr("kind", kind)?;
+    value.setattr("path", e.path().map(|p| p.display().to_string()))?;
+    Ok(value)
+}
+
+/// `e` as a raisable Python exception (see [`scan_err_value`]).
+#[cfg(feature = "python")]
+fn scan_err(e: core::ScanError) -> PyErr {
+    Python::with_gil(|py| scan_err_value(py, &e).map_or_else(|err| err, |value| PyErr::from_value(value.into_any())))
+}
 
 /// Python-facing Seq class wrapping core::Seq
 #[cfg(feature = "python")]
@@ -179,6 +251,9 @@ pub struct PySeq {
     indices: Vec<i64>,
     #[pyo3(get)]
     missed: Vec<i64>,
+    /// Present frames whose file is a dangling symlink
+    #[pyo3(get)]
+    broken: Vec<i64>,
 }
 
 #[cfg(feature = "python")]
@@ -191,6 +266,7 @@ impl From<CoreSeq> for PySeq {
             padding: s.padding,
             indices: s.indices.clone(),
             missed: s.missed.clone(),
+            broken: s.broken.clone(),
         }
     }
 }
@@ -272,9 +348,7 @@ impl PySeq {
         const MAX_EXPAND: i64 = 1_000_000;
         let count = self.end.saturating_sub(self.start).saturating_add(1);
         if count > MAX_EXPAND {
-            return Err(pyo3::exceptions::PyValueError::new_err(
-                format!("Range too large: {} frames (max {})", count, MAX_EXPAND)
-            ));
+            return Err(scan_err(core::ScanError::RangeTooLarge { frames: count, max: MAX_EXPAND }));
         }
         Ok((self.start..=self.end).map(|f| self.format_frame(f)).collect())
     }
@@ -342,6 +416,53 @@ impl PySeq {
             "count": self.indices.len()
         })).unwrap_or_default()
     }
+
+    /// Digest every present frame ("xxh3" or "sha256"), in parallel.
+    /// Returns {frame: digest}; with `manifest`, also writes the manifest file.
+    #[pyo3(signature = (algo="xxh3", manifest=None))]
+    fn checksum(&self, py: Python, algo: &str, manifest: Option<String>) -> PyResult<std::collections::BTreeMap<i64, String>> {
+        let algo = algo.parse::<core::ChecksumAlgo>().map_err(pyo3::exceptions::PyValueError::new_err)?;
+        let result = py.allow_threads(|| {
+            let checksums = core::ChecksumManifest::create(&self.pattern, self.padding, &self.indices, algo, |_| None)?;
+            if let Some(path) = &manifest {
+                checksums.write(path)?;
+            }
+            Ok(checksums)
+        });
+        Ok(result.map_err(scan_err)?.digests.into_iter().map(|(frame, d)| (frame, d.digest)).collect())
+    }
+
+    /// Check the frames of this sequence against a manifest file (see `checksum`).
+    /// Returns a dict: ok, verified, missing, changed, extra, unreadable.
+    fn verify(&self, py: Python, manifest: String) -> PyResult<Py<PyAny>> {
+        let report = py.allow_threads(|| {
+            let manifest = core::ChecksumManifest::read(&manifest)?;
+            Ok(manifest.verify_frames(&self.pattern, self.padding, &self.indices, |_| None))
+        });
+        verify_report_dict(py, &report.map_err(scan_err)?)
+    }
+}
+
+/// Check the sequence a manifest file describes, as found on disk now.
+/// Returns a dict: ok, verified, missing, changed, extra, unreadable.
+#[cfg(feature = "python")]
+#[pyfunction]
+fn verify_manifest(py: Python, manifest: String) -> PyResult<Py<PyAny>> {
+    let report = py.allow_threads(|| core::ChecksumManifest::read(&manifest)?.verify());
+    verify_report_dict(py, &report.map_err(scan_err)?)
+}
+
+#[cfg(feature = "python")]
+fn verify_report_dict(py: Python, report: &core::VerifyReport) -> PyResult<Py<PyAny>> {
+    let dict = PyDict::new(py);
+    dict.set_item("pattern", &report.pattern)?;
+    dict.set_item("ok", report.is_ok())?;
+    dict.set_item("verified", &report.verified)?;
+    dict.set_item("missing", &report.missing)?;
+    dict.set_item("changed", &report.changed)?;
+    dict.set_item("extra", &report.extra)?;
+    dict.set_item("unreadable", &report.unreadable)?;
+    Ok(dict.into_any().unbind())
 }
 
 /// Python-facing ScanResult class wrapping core::ScanResult
@@ -354,9 +475,23 @@ pub struct PyScanResult {
     /// Scan duration in milliseconds
     #[pyo3(get)]
     elapsed_ms: f64,
-    /// Errors encountered during scan
+    /// Errors encountered during scan (exposed as exception instances)
+    errors: Arc<Vec<core::ScanError>>,
+    /// Problems worked around (e.g. roots already covered by another root)
     #[pyo3(get)]
-    errors: Vec<String>,
+    warnings: Vec<String>,
+}
+
+#[cfg(feature = "python")]
+impl From<core::ScanResult> for PyScanResult {
+    fn from(result: core::ScanResult) -> Self {
+        PyScanResult {
+            seqs: Arc::new(result.seqs.into_iter().map(PySeq::from).collect()),
+            elapsed_ms: result.elapsed_ms,
+            errors: Arc::new(result.errors),
+            warnings: result.warnings.iter().map(ToString::to_string).collect(),
+        }
+    }
 }
 
 #[cfg(feature = "python")]
@@ -368,6 +503,13 @@ impl PyScanResult {
         (*self.seqs).clone()
     }
 
+    /// Errors encountered during scan, as `ScanError` subclass instances with
+    /// `kind` and `path` (not raised: the scan went on past them)
+    #[getter]
+    fn errors<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, pyo3::exceptions::PyBaseException>>> {
+        self.errors.iter().map(|e| scan_err_value(py, e)).collect()
+    }
+
     fn __repr__(&self) -> String {
         format!(
             "ScanResult(seqs={}, elapsed={:.2}ms, errors={})",
@@ -404,6 +546,14 @@ pub struct Scanner {
     /// Minimum sequence length
     #[pyo3(get)]
     min_len: usize,
+    /// Fail a root on the first unreadable folder or file below it
+    #[pyo3(get)]
+    strict: bool,
+    /// Output order (exposed as a string by the `sort` getter)
+    sort: core::SortKey,
+    /// Compare paths naturally (shot2 before shot10)
+    #[pyo3(get)]
+    natural: bool,
     /// Scan results (sequences, elapsed_ms, errors)
     #[pyo3(get)]
     result: PyScanResult,
@@ -419,18 +569,40 @@ impl Scanner {
     ///     recursive: Scan subdirectories (default: True)
     ///     mask: File mask/glob pattern (e.g., "*.exr")
     ///     min_len: Minimum sequence length (default: 2)
+    ///     strict: Fail a root on the first unreadable folder or file below it,
+    ///         instead of listing each in result.errors (default: False)
+    ///     sort: Output order, "path", "frames" or "mtime" (default: "path")
+    ///     natural: Compare paths naturally, shot2 before shot10 (default: False)
+    ///
+    /// Raises:
+    ///     ValueError: Unknown sort key
     #[new]
-    #[pyo3(signature = (roots, recursive=true, mask=None, min_len=2))]
-    fn new(py: Python, roots: Vec<String>, recursive: bool, mask: Option<String>, min_len: usize) -> PyResult<Self> {
+    #[pyo3(signature = (roots, recursive=true, mask=None, min_len=2, strict=false, sort="path", natural=false))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        py: Python,
+        roots: Vec<String>,
+        recursive: bool,
+        mask: Option<String>,
+        min_len: usize,
+        strict: bool,
+        sort: &str,
+        natural: bool,
+    ) -> PyResult<Self> {
+        let sort = sort.parse::<core::SortKey>().map_err(pyo3::exceptions::PyValueError::new_err)?;
         let mut scanner = Scanner {
             roots,
             recursive,
             mask,
             min_len,
+            strict,
+            sort,
+            natural,
             result: PyScanResult {
                 seqs: Arc::new(Vec::new()),
                 elapsed_ms: 0.0,
-                errors: Vec::new(),
+                errors: Arc::new(Vec::new()),
+                warnings: Vec::new(),
             },
         };
         scanner.rescan_impl(py)?;
@@ -450,20 +622,8 @@ impl Scanner {
     #[staticmethod]
     #[pyo3(signature = (root, recursive=true, mask=None, min_len=2))]
     fn get_seq(py: Python, root: String, recursive: bool, mask: Option<String>, min_len: usize) -> PyResult<PyScanResult> {
-        let start = Instant::now();
-
-        let (seqs, errors) = py.allow_threads(|| {
-            match core::get_seqs(&root, recursive, mask.as_deref(), min_len) {
-                Ok(s) => (s, Vec::new()),
-                Err(e) => (Vec::new(), vec![e]),
-            }
-        });
-
-        Ok(PyScanResult {
-            seqs: Arc::new(seqs.into_iter().map(PySeq::from).collect()),
-            elapsed_ms: start.elapsed().as_secs_f64() * 1000.0,
-            errors,
-        })
+        let result = py.allow_threads(|| core::Scanner::get_seq(&root, recursive, mask.as_deref(), min_len));
+        Ok(result.into())
     }
 
     /// Scan multiple paths in parallel (static method).
@@ -479,33 +639,9 @@ impl Scanner {
     #[staticmethod]
     #[pyo3(signature = (roots, recursive=true, mask=None, min_len=2))]
     fn get_seqs(py: Python, roots: Vec<String>, recursive: bool, mask: Option<String>, min_len: usize) -> PyResult<PyScanResult> {
-        let start = Instant::now();
-
-        // Scan roots in parallel
-        let (seqs, errors) = py.allow_threads(|| {
-            let results: Vec<_> = roots.par_iter().map(|root| {
-                match core::get_seqs(root, recursive, mask.as_deref(), min_len) {
-                    Ok(s) => (s, None),
-                    Err(e) => (Vec::new(), Some(format!("{}: {}", root, e))),
-                }
-            }).collect();
-
-            let mut all_seqs = Vec::new();
-            let mut all_errors = Vec::new();
-            for (seqs, err) in results {
-                all_seqs.extend(seqs);
-                if let Some(e) = err {
-                    all_errors.push(e);
-                }
-            }
-            (all_seqs, all_errors)
-        });
-
-        Ok(PyScanResult {
-            seqs: Arc::new(seqs.into_iter().map(PySeq::from).collect()),
-            elapsed_ms: start.elapsed().as_secs_f64() * 1000.0,
-            errors,
-        })
+        // Scan roots in parallel, GIL released
+        let result = py.allow_threads(|| core::Scanner::get_seqs(&roots, recursive, mask.as_deref(), min_len));
+        Ok(result.into())
     }
 
     /// Find sequence containing the given file.
@@ -524,6 +660,12 @@ impl Scanner {
         })
     }
 
+    /// Output order: "path", "frames" or "mtime"
+    #[getter]
+    fn sort(&self) -> String {
+        self.sort.to_string()
+    }
+
     /// Re-scan all roots with current settings.
     /// Updates result with new sequences, elapsed_ms, and errors.
     fn rescan(&mut self, py: Python) -> PyResult<()> {
@@ -565,52 +707,35 @@ impl Scanner {
     #[pyo3(signature = (roots, recursive=true, exts=vec![]))]
     fn scan_files(py: Python, roots: Vec<String>, recursive: bool, exts: Vec<String>) -> PyResult<Vec<String>> {
         let ext_refs: Vec<&str> = exts.iter().map(|s| s.as_str()).collect();
-        py.allow_threads(|| {
-            core::scan_files(&roots, recursive, &ext_refs)
-                .map(|files| files.iter().map(|p| p.display().to_string()).collect())
-                .map_err(pyo3::exceptions::PyRuntimeError::new_err)
-        })
+        let files = py.allow_threads(|| core::scan_files(&roots, recursive, &ext_refs)).map_err(scan_err)?;
+        Ok(files.iter().map(|p| p.display().to_string()).collect())
     }
 }
 
 #[cfg(feature = "python")]
 impl Scanner {
     fn rescan_impl(&mut self, py: Python) -> PyResult<()> {
-        let start = Instant::now();
-
         // Clone config for GIL-free scanning
-        let roots = self.roots.clone();
-        let recursive = self.recursive;
-        let mask = self.mask.clone();
-        let min_len = self.min_len;
+        let mut scanner = core::Scanner {
+            roots: self.roots.clone(),
+            recursive: self.recursive,
+            mask: self.mask.clone(),
+            min_len: self.min_len,
+            options: core::ScanOptions {
+                strict: self.strict,
+                sort: self.sort,
+                natural_sort: self.natural,
+                with_metadata: self.sort == core::SortKey::Mtime,
+                ..Default::default()
+            },
+            result: Default::default(),
+        };
 
         // Release GIL during parallel Rust file scanning
-        let (seqs, errors) = py.allow_threads(|| {
-            let results: Vec<_> = roots.par_iter().map(|root| {
-                match core::get_seqs(root, recursive, mask.as_deref(), min_len) {
-                    Ok(s) => (s, None),
-                    Err(e) => (Vec::new(), Some(format!("{}: {}", root, e))),
-                }
-            }).collect();
-
-            let mut all_seqs = Vec::new();
-            let mut all_errors = Vec::new();
-            for (seqs, err) in results {
-                all_seqs.extend(seqs);
-                if let Some(e) = err {
-                    all_errors.push(e);
-                }
-            }
-            (all_seqs, all_errors)
-        });
+        py.allow_threads(|| scanner.rescan());
 
         // Update result (GIL held again)
-        self.result = PyScanResult {
-            seqs: Arc::new(seqs.into_iter().map(PySeq::from).collect()),
-            elapsed_ms: start.elapsed().as_secs_f64() * 1000.0,
-            errors,
-        };
-
+        self.result = scanner.result.into();
         Ok(())
     }
 }
@@ -647,6 +772,14 @@ fn scanseq(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<Scanner>()?;
     m.add_class::<PyScanResult>()?;
     m.add_class::<PySeq>()?;
+    m.add_function(wrap_pyfunction!(verify_manifest, m)?)?;
+    let py = m.py();
+    m.add("ScanError", py.get_type::<py_errors::ScanError>())?;
+    m.add("NotFoundError", py.get_type::<py_errors::NotFoundError>())?;
+    m.add("PermissionDeniedError", py.get_type::<py_errors::PermissionDeniedError>())?;
+    m.add("InvalidMaskError", py.get_type::<py_errors::InvalidMaskError>())?;
+    m.add("RangeTooLargeError", py_errors::range_too_large(py)?)?;
+    m.add("ParseError", py.get_type::<py_errors::ParseError>())?;
     // Export extension constants
     m.add("IMAGE_EXTS", core::VFX_IMAGE_EXTS)?;
     m.add("VIDEO_EXTS", core::VIDEO_EXTS)?;
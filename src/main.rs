@@ -17,8 +17,63 @@
 //! # Filter by mask
 //! scanseq-cli -p /renders -m "*.exr" -o
 //!
+//! # At most two levels deep, without crossing mount points
+//! scanseq-cli -p /mnt --max-depth 2 -x -o
+//!
+//! # Group a path list (find output, render log, tar listing) without scanning
+//! tar tf plates.tar | scanseq-cli --from-list - -o
+//!
+//! # Sequences (with sizes/mtimes) from an S3 inventory or a find listing
+//! zcat inventory.csv.gz | scanseq-cli --inventory - --inventory-format s3 --check -o
+//! find /archive -type f -printf '%s %T@ %p\n' > archive.txt; scanseq-cli --inventory archive.txt -o
+//!
+//! # Daily rescan of a large archive: only changed folders are regrouped
+//! scanseq-cli -p /archive -r --cache ~/.cache/scanseq -o
+//!
+//! # Keep watching and print sequence changes as frames land (poll on NFS)
+//! scanseq-cli -p /renders -r -w
+//! scanseq-cli -p /mnt/farm -r -w --poll 5
+//!
 //! # JSON output
 //! scanseq-cli -p /renders -oj
+//!
+//! # Copy a sequence with new padding (plan first), move one into a folder
+//! scanseq-cli cp /renders/comp.####.exr /publish/comp.#####.exr --dry-run
+//! scanseq-cli mv /renders/comp.0001.exr /archive/ --on-exists skip
+//!
+//! # Publish view: relative symlinks renumbered to 1001
+//! scanseq-cli ln /renders/comp.####.exr /publish/shot010/comp/v003/comp.####.exr --relative --start 1001
+//!
+//! # Rebase a plate to 1001, shift another one down by 24
+//! scanseq-cli renumber /plates/plate.####.dpx --start 1001
+//! scanseq-cli renumber /plates/bg.0025.dpx --offset -24
+//!
+//! # Pad a vendor delivery (img_1.png ... img_240.png) to ####
+//! scanseq-cli repad /vendor/img_1.png 4
+//!
+//! # Conform a delivery to the show naming (check the table first)
+//! scanseq-cli rename /vendor/ --match "{roll}_plate_v{version}" --set show=abc --set shot=010 \
+//!     --to "{show}_{shot}_plate_v{version:03}.{frame:04}.{ext}" -n
+//!
+//! # Trash every other frame of an old version, delete frames 1-10 of another
+//! scanseq-cli rm /renders/comp_v1.####.exr --slice ::2 --trash /renders/.trash
+//! scanseq-cli rm /renders/comp_v2.0001.exr --frames 1-10 --yes
+//!
+//! # Hold frames for the gaps of a partial render (symlinks), or a slate
+//! scanseq-cli fill /renders/comp.####.exr --method symlink
+//! scanseq-cli fill /renders/comp.####.exr --placeholder /lib/missing.exr
+//!
+//! # Journal a batch, then roll all of it back (also after a crash mid-batch)
+//! scanseq-cli mv /renders/comp.####.exr /archive/ --journal /tmp/batch.journal
+//! scanseq-cli renumber /archive/comp.####.exr --start 1001 --journal /tmp/batch.journal
+//! scanseq-cli undo /tmp/batch.journal
+//!
+//! # Checksum manifest for a delivery, verified at the other end
+//! scanseq-cli checksum /delivery/plate.####.dpx --algo sha256 -m /delivery/plate.sha256.json
+//! scanseq-cli verify /ingest/plate.sha256.json
+//!
+//! # Held frames (identical consecutive frames) and frames shared between sequences
+//! scanseq-cli -p /renders/sh010 -r --held --dupes
 //! ```
 //!
 //! # Architecture
@@ -40,18 +95,30 @@
 //! - `core`: Core sequence detection algorithm
 //! - Python bindings available when built with `--features python`
 
-mod core;
+use scanseq::core;
 
 use clap::Parser;
-use core::{format_frame, scan_files, Scanner, Seq};
+use core::{
+    duplicate_frames, format_frame, format_frame_ranges, get_seqs, group_inventory, group_paths, parse_frame_ranges, read_inventory, read_s3_inventory, scan_files_with, sort_seqs,
+    ChecksumAlgo, ChecksumManifest, DuplicateGroup, DuplicateOptions, FillMethod, FillSource, FillStrategy, FrameMap, FrameSelection, GroupOptions, HeldRun, InventoryFormat, Journal,
+    LinkKind, LinkOptions, LinkPaths, NameFields, NameTemplate, OnExists, OpError, OpReport, RemoveMode, Renumber, ScanError, ScanOptions, ScanResult, Scanner, Seq, SortKey, StaleRule,
+    SuspectFrame, SuspectThresholds, TransferOptions, WatchEvent, WatchMode, WatchOptions, S3_DEFAULT_SCHEMA,
+};
+use std::io::BufRead;
 use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
 
 use log::{debug, info};
 
 #[derive(Parser)]
 #[command(name = "scanseq-cli")]
 #[command(about = "Fast file sequence scanner for VFX/animation pipelines", long_about = None)]
+#[command(args_conflicts_with_subcommands = true)]
 struct Args {
+    /// File operation on one sequence instead of a scan
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Paths to scan (can specify multiple: -p /path1 -p /path2)
     #[arg(short = 'p', long = "path")]
     paths: Vec<PathBuf>,
@@ -72,6 +139,126 @@ struct Args {
     #[arg(short = 'n', long = "min", default_value = "2")]
     min_len: usize,
 
+    /// Descend at most N levels (1 = files directly in the paths). Implies -r
+    #[arg(long = "max-depth", value_name = "N")]
+    max_depth: Option<usize>,
+
+    /// Skip files shallower than N levels
+    #[arg(long = "min-depth", value_name = "N", default_value = "0")]
+    min_depth: usize,
+
+    /// Stay on the file system of each path (don't cross mount points)
+    #[arg(short = 'x', long = "one-file-system")]
+    one_file_system: bool,
+
+    /// Follow symbolic links (cycle-safe); dangling frame links are reported as broken
+    #[arg(short = 'L', long = "follow-links")]
+    follow_links: bool,
+
+    /// Report symlinked frames by their resolved target instead of the link path
+    #[arg(long = "resolve-links")]
+    resolve_links: bool,
+
+    /// Look inside .zip and .tar files (members print as archive.zip!/path)
+    #[arg(long)]
+    archives: bool,
+
+    /// Fail a path's scan on the first unreadable folder or file below it
+    #[arg(long)]
+    strict: bool,
+
+    /// Output order: path | frames | mtime (mtime implies --stat)
+    #[arg(long, value_name = "KEY", default_value = "path")]
+    sort: SortKey,
+
+    /// Sort paths naturally (shot2 before shot10)
+    #[arg(long)]
+    natural: bool,
+
+    /// Collect per-frame size/mtime/inode (adds disk usage to output, frame_info to JSON)
+    #[arg(long = "stat")]
+    stat: bool,
+
+    /// Report empty, truncated and size-drop frames (implies --stat). Exits 2 if any are found
+    #[arg(long = "check")]
+    check: bool,
+
+    /// --check: flag frames smaller than this fraction of their neighbours' median size
+    #[arg(long = "check-ratio", value_name = "F", default_value = "0.5", value_parser = parse_fraction)]
+    check_ratio: f64,
+
+    /// --check: flag frames that shrink by more than this fraction from the previous frame
+    #[arg(long = "check-drop", value_name = "F", default_value = "0.5", value_parser = parse_fraction)]
+    check_drop: f64,
+
+    /// --check: neighbours on each side used for the running median
+    #[arg(long = "check-window", value_name = "N", default_value = "3")]
+    check_window: usize,
+
+    /// Report frames older than the majority of the sequence, as frame ranges (implies --stat). Exits 2 if any are found
+    #[arg(long = "stale")]
+    stale: bool,
+
+    /// --stale: mtime gap that separates two render passes (e.g. 90s, 30m, 1h, 2d)
+    #[arg(long = "stale-gap", value_name = "DUR", default_value = "1h", value_parser = parse_duration)]
+    stale_gap: Duration,
+
+    /// Report frames modified before WHEN instead: a reference file, epoch seconds, or an age like 2h (implies --stale)
+    #[arg(long = "stale-before", value_name = "WHEN", value_parser = parse_when)]
+    stale_before: Option<SystemTime>,
+
+    /// Report runs of byte-identical consecutive frames: holds, stuck renders (implies --stat). Exits 2 if any are found
+    #[arg(long = "held")]
+    held: bool,
+
+    /// Report frames with identical content in different sequences (implies --stat). Exits 2 if any are found
+    #[arg(long = "dupes")]
+    dupes: bool,
+
+    /// --held/--dupes: digest used to compare frames (xxh3 | sha256)
+    #[arg(long = "dupes-algo", value_name = "ALGO", default_value = "xxh3")]
+    dupes_algo: ChecksumAlgo,
+
+    /// --held/--dupes: read every frame, not only those whose size matches a candidate
+    #[arg(long = "no-size-filter")]
+    no_size_filter: bool,
+
+    /// Cache grouped folders in DIR and regroup only folders whose mtime changed since the last run
+    #[arg(long = "cache", value_name = "DIR")]
+    cache: Option<PathBuf>,
+
+    /// Drop PATH (and everything below it) from the cache before scanning (with --cache)
+    #[arg(long = "invalidate", value_name = "PATH", requires = "cache")]
+    invalidate: Vec<PathBuf>,
+
+    /// After scanning, keep watching the paths and print sequence changes (Ctrl+C to stop)
+    #[arg(short = 'w', long = "watch")]
+    watch: bool,
+
+    /// --watch: poll every SECS seconds instead of using change notifications (for network mounts)
+    #[arg(long = "poll", value_name = "SECS")]
+    poll: Option<u64>,
+
+    /// --watch: quiet period before a growing sequence is reported complete (e.g. 10s, 2m)
+    #[arg(long = "settle", value_name = "DUR", default_value = "10s", value_parser = parse_duration)]
+    settle: Duration,
+
+    /// Group paths listed in FILE (one per line, `-` = stdin) instead of scanning; the filesystem is not touched
+    #[arg(long = "from-list", value_name = "FILE", conflicts_with_all = ["scan_exts", "watch", "cache"])]
+    from_list: Option<PathBuf>,
+
+    /// Group an inventory listing (S3 inventory CSV, find -printf '%s %T@ %p', rclone lsjson; `-` = stdin) with per-frame size/mtime
+    #[arg(long = "inventory", value_name = "FILE", conflicts_with_all = ["scan_exts", "watch", "cache", "from_list"])]
+    inventory: Option<PathBuf>,
+
+    /// --inventory: listing format, s3 | find | rclone (default: guessed from the extension)
+    #[arg(long = "inventory-format", value_name = "FMT")]
+    inventory_format: Option<InventoryFormat>,
+
+    /// --inventory: S3 column layout, the fileSchema of the inventory manifest
+    #[arg(long = "s3-schema", value_name = "COLUMNS", default_value = S3_DEFAULT_SCHEMA)]
+    s3_schema: String,
+
     /// Print sequences to stdout (default: off)
     #[arg(short = 'o', long = "out")]
     out: bool,
@@ -81,14 +268,343 @@ struct Args {
     json: bool,
 }
 
+/// File operations on a single sequence
+#[derive(clap::Subcommand)]
+enum Command {
+    /// Copy a sequence to a destination pattern or folder
+    Cp(TransferArgs),
+    /// Move a sequence to a destination pattern or folder
+    Mv(TransferArgs),
+    /// Link a sequence into a destination pattern or folder (symlink, hardlink or reflink)
+    Ln(LinkArgs),
+    /// Shift the frame numbers of a sequence in place
+    Renumber(RenumberArgs),
+    /// Change the frame padding of a sequence in place
+    Repad(RepadArgs),
+    /// Rename sequences through a naming template
+    Rename(RenameArgs),
+    /// Delete frames of a sequence, or move them to a trash folder
+    Rm(RmArgs),
+    /// Create the missing frames of a sequence from its other frames or a placeholder
+    Fill(FillArgs),
+    /// Reverse the file operations recorded in a journal (--journal), last first
+    Undo(UndoArgs),
+    /// Write a checksum manifest of a sequence (per-frame xxh3 or sha256 digests)
+    Checksum(ChecksumArgs),
+    /// Check a sequence against a checksum manifest: missing, changed and extra frames
+    Verify(VerifyArgs),
+}
+
+impl Command {
+    /// The `--journal` file of the command.
+    fn journal(&self) -> Option<&std::path::Path> {
+        match self {
+            Command::Cp(t) | Command::Mv(t) => t.journal.as_deref(),
+            Command::Ln(l) => l.journal.as_deref(),
+            Command::Renumber(r) => r.journal.as_deref(),
+            Command::Repad(r) => r.journal.as_deref(),
+            Command::Rename(r) => r.journal.as_deref(),
+            Command::Rm(r) => r.journal.as_deref(),
+            Command::Fill(f) => f.journal.as_deref(),
+            Command::Undo(_) | Command::Checksum(_) | Command::Verify(_) => None,
+        }
+    }
+}
+
+#[derive(clap::Args)]
+struct TransferArgs {
+    /// Source sequence: any of its frames, or its pattern (/renders/comp.####.exr)
+    src: String,
+
+    /// Destination pattern (/publish/comp.####.exr, the placeholder sets the padding) or folder ending in /
+    dest: String,
+
+    /// Print what would be done without touching any file
+    #[arg(short = 'n', long = "dry-run")]
+    dry_run: bool,
+
+    /// Existing destination frames: fail (before anything is done) | skip | overwrite
+    #[arg(long = "on-exists", value_name = "POLICY", default_value = "fail")]
+    on_exists: OnExists,
+
+    /// Record every file operation in FILE (appended to), so `undo FILE` can reverse them
+    #[arg(long, value_name = "FILE")]
+    journal: Option<PathBuf>,
+
+    /// Print the report as JSON
+    #[arg(short = 'j', long)]
+    json: bool,
+}
+
+#[derive(clap::Args)]
+#[command(group(clap::ArgGroup::new("shift").args(["offset", "start"])))]
+struct LinkArgs {
+    /// Source sequence: any of its frames, or its pattern (/renders/comp.####.exr)
+    src: String,
+
+    /// Destination pattern (/publish/comp.####.exr, the placeholder sets the padding) or folder ending in /
+    dest: String,
+
+    /// Link kind: sym | hard | reflink
+    #[arg(short = 'k', long, value_name = "KIND", default_value = "sym")]
+    kind: LinkKind,
+
+    /// Symlinks point at the frames by a relative path
+    #[arg(short = 'r', long)]
+    relative: bool,
+
+    /// Reflinks: fail instead of copying where the file system can't clone
+    #[arg(long = "no-fallback")]
+    no_fallback: bool,
+
+    /// Number the links with every frame shifted by N
+    #[arg(long, value_name = "N", allow_negative_numbers = true)]
+    offset: Option<i64>,
+
+    /// Number the links so the first frame becomes N (1001)
+    #[arg(long, value_name = "N")]
+    start: Option<i64>,
+
+    /// Print what would be done without touching any file
+    #[arg(short = 'n', long = "dry-run")]
+    dry_run: bool,
+
+    /// Existing destination frames: fail (before anything is done) | skip | overwrite
+    #[arg(long = "on-exists", value_name = "POLICY", default_value = "fail")]
+    on_exists: OnExists,
+
+    /// Record every file operation in FILE (appended to), so `undo FILE` can reverse them
+    #[arg(long, value_name = "FILE")]
+    journal: Option<PathBuf>,
+
+    /// Print the report as JSON
+    #[arg(short = 'j', long)]
+    json: bool,
+}
+
+#[derive(clap::Args)]
+#[command(group(clap::ArgGroup::new("shift").required(true).args(["offset", "start"])))]
+struct RenumberArgs {
+    /// Sequence: any of its frames, or its pattern (/renders/plate.####.exr)
+    src: String,
+
+    /// Shift every frame by N (negative shifts down)
+    #[arg(long, value_name = "N", allow_negative_numbers = true)]
+    offset: Option<i64>,
+
+    /// Shift so the first frame becomes N (1001)
+    #[arg(long, value_name = "N")]
+    start: Option<i64>,
+
+    /// Print what would be done without touching any file
+    #[arg(short = 'n', long = "dry-run")]
+    dry_run: bool,
+
+    /// Existing files on the new frame numbers: fail (before anything is done) | skip | overwrite
+    #[arg(long = "on-exists", value_name = "POLICY", default_value = "fail")]
+    on_exists: OnExists,
+
+    /// Record every file operation in FILE (appended to), so `undo FILE` can reverse them
+    #[arg(long, value_name = "FILE")]
+    journal: Option<PathBuf>,
+
+    /// Print the report as JSON
+    #[arg(short = 'j', long)]
+    json: bool,
+}
+
+#[derive(clap::Args)]
+struct RepadArgs {
+    /// Sequence: any of its frames, or its pattern (/vendor/img_@.png)
+    src: String,
+
+    /// New padding (4 = ####, 0 = unpadded)
+    padding: usize,
+
+    /// Print what would be done without touching any file
+    #[arg(short = 'n', long = "dry-run")]
+    dry_run: bool,
+
+    /// Existing files on the new names: fail (before anything is done) | skip | overwrite
+    #[arg(long = "on-exists", value_name = "POLICY", default_value = "fail")]
+    on_exists: OnExists,
+
+    /// Record every file operation in FILE (appended to), so `undo FILE` can reverse them
+    #[arg(long, value_name = "FILE")]
+    journal: Option<PathBuf>,
+
+    /// Print the report as JSON
+    #[arg(short = 'j', long)]
+    json: bool,
+}
+
+#[derive(clap::Args)]
+struct RenameArgs {
+    /// Sequences: frames, patterns, or folders (every sequence in them)
+    #[arg(required = true)]
+    src: Vec<String>,
+
+    /// New name: {prefix} {sep} {suffix} {ext} {anchor1}.. {frame} and captured or --set fields; {field:04} zero-pads
+    #[arg(long, value_name = "TEMPLATE")]
+    to: String,
+
+    /// Capture fields from the name before the frame number ("{roll}_plate_v{version}")
+    #[arg(long = "match", value_name = "TEMPLATE")]
+    matcher: Option<String>,
+
+    /// Set a field (show=abc); repeatable
+    #[arg(long = "set", value_name = "FIELD=VALUE", value_parser = parse_field)]
+    set: Vec<(String, String)>,
+
+    /// Print the rename table without touching any file
+    #[arg(short = 'n', long = "dry-run")]
+    dry_run: bool,
+
+    /// Existing files on the new names: fail (before anything is done) | skip | overwrite
+    #[arg(long = "on-exists", value_name = "POLICY", default_value = "fail")]
+    on_exists: OnExists,
+
+    /// Record every file operation in FILE (appended to), so `undo FILE` can reverse them
+    #[arg(long, value_name = "FILE")]
+    journal: Option<PathBuf>,
+
+    /// Print the reports as JSON
+    #[arg(short = 'j', long)]
+    json: bool,
+}
+
+#[derive(clap::Args)]
+#[command(group(clap::ArgGroup::new("selection").args(["frames", "slice"])))]
+struct RmArgs {
+    /// Sequence: any of its frames, or its pattern (/renders/comp_v1.####.exr)
+    src: String,
+
+    /// Only these frames (1-10,15); all of them must exist
+    #[arg(long, value_name = "RANGES")]
+    frames: Option<String>,
+
+    /// Only the present frames this slice picks by position (::2, -10:)
+    #[arg(long, value_name = "SLICE", allow_hyphen_values = true)]
+    slice: Option<String>,
+
+    /// Move the frames into DIR instead of deleting them
+    #[arg(long, value_name = "DIR")]
+    trash: Option<PathBuf>,
+
+    /// Don't ask for confirmation
+    #[arg(short = 'y', long)]
+    yes: bool,
+
+    /// Print what would be removed without touching any file
+    #[arg(short = 'n', long = "dry-run")]
+    dry_run: bool,
+
+    /// Names already in the trash folder: fail (before anything is done) | skip | overwrite
+    #[arg(long = "on-exists", value_name = "POLICY", default_value = "fail")]
+    on_exists: OnExists,
+
+    /// Record every file operation in FILE (appended to), so `undo FILE` can reverse them
+    #[arg(long, value_name = "FILE")]
+    journal: Option<PathBuf>,
+
+    /// Print the report as JSON
+    #[arg(short = 'j', long)]
+    json: bool,
+}
+
+#[derive(clap::Args)]
+struct FillArgs {
+    /// Sequence: any of its frames, or its pattern (/renders/comp.####.exr)
+    src: String,
+
+    /// Fill from the previous frame (hold), the next one, or the nearest one
+    #[arg(long, value_name = "FRAME", default_value = "previous", conflicts_with = "placeholder")]
+    from: FillSource,
+
+    /// Fill every gap with this file instead
+    #[arg(long, value_name = "FILE")]
+    placeholder: Option<PathBuf>,
+
+    /// How fill frames are made: copy | hardlink | symlink
+    #[arg(long, value_name = "METHOD", default_value = "copy")]
+    method: FillMethod,
+
+    /// Print what would be created without touching any file
+    #[arg(short = 'n', long = "dry-run")]
+    dry_run: bool,
+
+    /// Gap files that exist by now: fail (before anything is done) | skip | overwrite
+    #[arg(long = "on-exists", value_name = "POLICY", default_value = "fail")]
+    on_exists: OnExists,
+
+    /// Record every file operation in FILE (appended to), so `undo FILE` can reverse them
+    #[arg(long, value_name = "FILE")]
+    journal: Option<PathBuf>,
+
+    /// Print the report as JSON
+    #[arg(short = 'j', long)]
+    json: bool,
+}
+
+#[derive(clap::Args)]
+struct UndoArgs {
+    /// Journal file written with --journal
+    journal: PathBuf,
+
+    /// Print what would be reversed without touching any file
+    #[arg(short = 'n', long = "dry-run")]
+    dry_run: bool,
+
+    /// Print the report as JSON
+    #[arg(short = 'j', long)]
+    json: bool,
+}
+
+#[derive(clap::Args)]
+struct ChecksumArgs {
+    /// Sequence: any of its frames, or its pattern (/delivery/plate.####.dpx)
+    src: String,
+
+    /// Digest algorithm: xxh3 | sha256
+    #[arg(short = 'a', long, value_name = "ALGO", default_value = "xxh3")]
+    algo: ChecksumAlgo,
+
+    /// Write the manifest to FILE instead of printing it
+    #[arg(short = 'm', long, value_name = "FILE")]
+    manifest: Option<PathBuf>,
+}
+
+#[derive(clap::Args)]
+struct VerifyArgs {
+    /// Manifest file written by `checksum`
+    manifest: PathBuf,
+
+    /// Sequence to check instead of the manifest's own (a copy or delivery of it)
+    src: Option<String>,
+
+    /// Print the report as JSON
+    #[arg(short = 'j', long)]
+    json: bool,
+}
+
+/// `FIELD=VALUE` of `rename --set`.
+fn parse_field(s: &str) -> Result<(String, String), String> {
+    s.split_once('=').map(|(k, v)| (k.trim().to_string(), v.to_string())).ok_or_else(|| format!("expected FIELD=VALUE, got '{}'", s))
+}
+
 fn main() {
     // Initialize logger - respect RUST_LOG, default to Info
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
 
     let args = Args::parse();
 
+    if let Some(command) = &args.command {
+        run_command(command);
+        return;
+    }
+
     // Show help if no paths specified
-    if args.paths.is_empty() {
+    if args.paths.is_empty() && args.from_list.is_none() && args.inventory.is_none() {
         use clap::CommandFactory;
         if let Err(e) = Args::command().print_help() {
             eprintln!("Failed to print help: {}", e);
@@ -104,11 +620,27 @@ fn main() {
         debug!("Scanning: {}", path.display());
     }
 
+    let recursive = args.recursive || args.max_depth.is_some();
+    let options = ScanOptions {
+        max_depth: args.max_depth,
+        min_depth: args.min_depth,
+        same_file_system: args.one_file_system,
+        follow_links: args.follow_links,
+        link_paths: if args.resolve_links { LinkPaths::Target } else { LinkPaths::Link },
+        with_metadata: args.stat || args.check || args.stale || args.stale_before.is_some() || args.held || args.dupes || args.sort == SortKey::Mtime,
+        cache_dir: args.cache.clone(),
+        archives: args.archives,
+        fs: None,
+        strict: args.strict,
+        sort: args.sort,
+        natural_sort: args.natural,
+    };
+
     // Mode: scan files by extension OR detect sequences
     if let Some(exts) = &args.scan_exts {
         // File scanning mode
         let ext_refs: Vec<&str> = exts.iter().map(|s| s.as_str()).collect();
-        match scan_files(&args.paths, args.recursive, &ext_refs) {
+        match scan_files_with(&args.paths, recursive, &ext_refs, &options) {
             Ok(files) => {
                 if args.out {
                     if args.json {
@@ -145,38 +677,95 @@ fn main() {
     }
 
     // Sequence detection mode
-    let result = Scanner::get_seqs(&args.paths, args.recursive, args.mask.as_deref(), args.min_len);
+    let mut scanner = Scanner {
+        roots: args.paths.iter().map(|p| p.to_string_lossy().to_string()).collect(),
+        recursive,
+        mask: args.mask.clone(),
+        min_len: args.min_len,
+        options,
+        result: Default::default(),
+    };
+    let result = if let Some(list) = &args.from_list {
+        group_list(list, args.mask.as_deref(), args.min_len)
+    } else if let Some(listing) = &args.inventory {
+        let format = args.inventory_format.unwrap_or_else(|| InventoryFormat::guess(listing));
+        group_listing(listing, format, &args.s3_schema, args.mask.as_deref(), args.min_len)
+    } else {
+        for path in &args.invalidate {
+            match scanner.invalidate_cache(path) {
+                Ok(n) => info!("Invalidated {} cached folder(s) under {}", n, path.display()),
+                Err(e) => eprintln!("Error: {}", e),
+            }
+        }
+        scanner.rescan();
+        // Watch mode keeps the scan as its baseline
+        if args.watch { scanner.result.clone() } else { std::mem::take(&mut scanner.result) }
+    };
 
     // Report errors
     for err in &result.errors {
         eprintln!("Error: {}", err);
     }
+    for warning in &result.warnings {
+        eprintln!("Warning: {}", warning);
+    }
 
-    // Sort sequences by pattern
+    // Listings are grouped without scan options: apply the requested order to all modes
     let mut all_seqs: Vec<Seq> = result.seqs;
-    all_seqs.sort_by(|a, b| a.pattern().cmp(b.pattern()));
+    sort_seqs(&mut all_seqs, args.sort, args.natural);
 
     let total_files: usize = all_seqs.iter().map(|s| s.len()).sum();
 
     let has_errors = !result.errors.is_empty();
 
+    // Suspect frames, computed up front so JSON output can carry them
+    let checks: Option<Vec<CheckEntry>> = args.check.then(|| {
+        let thresholds = SuspectThresholds { window: args.check_window, min_ratio: args.check_ratio, max_drop: args.check_drop };
+        check_seqs(&all_seqs, &thresholds)
+    });
+    let stale: Option<Vec<StaleEntry>> = (args.stale || args.stale_before.is_some()).then(|| {
+        let rule = match args.stale_before {
+            Some(cutoff) => StaleRule::Cutoff(cutoff),
+            None => StaleRule::Majority { gap: args.stale_gap },
+        };
+        stale_seqs(&all_seqs, &rule)
+    });
+    let dupe_opts = DuplicateOptions { algo: args.dupes_algo, size_prefilter: !args.no_size_filter, ..Default::default() };
+    let held: Option<Vec<HeldEntry>> = args.held.then(|| held_seqs(&all_seqs, &dupe_opts));
+    let dupes: Option<Vec<DuplicateGroup>> = args.dupes.then(|| duplicate_frames(&all_seqs, &dupe_opts));
+
     // Output only if --out is specified
     if args.out {
         if args.json {
             // JSON output
             #[derive(serde::Serialize)]
-            struct Output {
-                sequences: Vec<Seq>,
+            struct Output<'a> {
+                sequences: &'a [Seq],
                 total_sequences: usize,
                 total_files: usize,
                 errors: Vec<String>,
+                #[serde(skip_serializing_if = "Vec::is_empty")]
+                warnings: Vec<String>,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                check: Option<&'a [CheckEntry<'a>]>,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                stale: Option<&'a [StaleEntry<'a>]>,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                held: Option<&'a [HeldEntry<'a>]>,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                dupes: Option<&'a [DuplicateGroup]>,
             }
 
             let output = Output {
                 total_sequences: all_seqs.len(),
-                sequences: all_seqs,
+                sequences: &all_seqs,
                 total_files,
-                errors: result.errors.clone(),
+                errors: result.errors.iter().map(ToString::to_string).collect(),
+                warnings: result.warnings.iter().map(ToString::to_string).collect(),
+                check: checks.as_deref(),
+                stale: stale.as_deref(),
+                held: held.as_deref(),
+                dupes: dupes.as_deref(),
             };
 
             match serde_json::to_string_pretty(&output) {
@@ -200,6 +789,12 @@ fn main() {
                     } else {
                         println!("  {} [{}-{}] ({} files, {} missed)", pattern, seq.start, seq.end, seq.len(), seq.missed.len());
                     }
+                    if seq.has_metadata() {
+                        println!("    {}", human_bytes(seq.total_bytes()));
+                    }
+                    if !seq.broken.is_empty() {
+                        println!("    {} broken links: {:?}", seq.broken.len(), seq.broken);
+                    }
                     debug!("    First: {}", first_file);
                 }
 
@@ -208,8 +803,567 @@ fn main() {
         }
     }
 
+    // Check/stale/held/dupes reports (already part of the document for -oj)
+    if !(args.out && args.json) {
+        if let Some(checks) = &checks {
+            print_check_report(checks, all_seqs.len(), args.json);
+        }
+        if let Some(stale) = &stale {
+            print_stale_report(stale, all_seqs.len(), args.json);
+        }
+        if let Some(held) = &held {
+            print_held_report(held, all_seqs.len(), args.json);
+        }
+        if let Some(dupes) = &dupes {
+            print_dupes_report(dupes, all_seqs.len(), args.json);
+        }
+    }
+
+    if args.watch {
+        let mode = match args.poll {
+            Some(secs) => WatchMode::Poll(Duration::from_secs(secs.max(1))),
+            None => WatchMode::Native,
+        };
+        let options = WatchOptions { mode, settle: args.settle, ..Default::default() };
+        match scanner.watch_with(options) {
+            Ok(watch) => {
+                info!("Watching {} path(s), Ctrl+C to stop", args.paths.len());
+                for event in watch {
+                    print_watch_event(&event, args.json);
+                }
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
     // Exit with error if any path failed
     if has_errors {
         std::process::exit(1);
     }
+    if checks.is_some_and(|c| !c.is_empty()) || stale.is_some_and(|s| !s.is_empty()) || held.is_some_and(|h| !h.is_empty()) || dupes.is_some_and(|d| !d.is_empty()) {
+        std::process::exit(2);
+    }
+}
+
+/// Suspect frames of one sequence (`--check`)
+#[derive(serde::Serialize)]
+struct CheckEntry<'a> {
+    pattern: &'a str,
+    suspects: Vec<SuspectFrame>,
+}
+
+/// Run [`Seq::suspect_frames`] over all sequences, keeping only those with findings.
+fn check_seqs<'a>(seqs: &'a [Seq], thresholds: &SuspectThresholds) -> Vec<CheckEntry<'a>> {
+    seqs.iter()
+        .map(|seq| CheckEntry { pattern: seq.pattern(), suspects: seq.suspect_frames(thresholds) })
+        .filter(|entry| !entry.suspects.is_empty())
+        .collect()
+}
+
+/// Print the `--check` report on its own (without `-o`, or in human-readable mode).
+fn print_check_report(checks: &[CheckEntry], total_seqs: usize, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(checks) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("JSON error: {}", e),
+        }
+    } else if checks.is_empty() {
+        println!("Check: no suspect frames in {} sequences.", total_seqs);
+    } else {
+        println!("Check:");
+        for entry in checks {
+            println!("  {} ({} suspect)", entry.pattern, entry.suspects.len());
+            for suspect in &entry.suspects {
+                println!("    {}", suspect);
+            }
+        }
+    }
+}
+
+/// Run a file operation subcommand. Exits with 1 if it can't run or any frame fails.
+fn run_command(command: &Command) {
+    let journal = match command.journal().map(Journal::open).transpose() {
+        Ok(journal) => journal,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let (result, json) = match command {
+        Command::Undo(u) => (run_undo(u), u.json),
+        Command::Checksum(c) => match run_checksum(c) {
+            Ok(()) => return,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Command::Verify(v) => match run_verify(v) {
+            Ok(true) => return,
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Command::Ln(l) => {
+            let map = match (l.offset, l.start) {
+                (Some(offset), _) => FrameMap::Renumber(Renumber::Offset(offset)),
+                (None, Some(start)) => FrameMap::Renumber(Renumber::Start(start)),
+                (None, None) => FrameMap::Keep,
+            };
+            let opts = LinkOptions { relative: l.relative, reflink_fallback: !l.no_fallback, on_exists: l.on_exists, dry_run: l.dry_run, journal };
+            (open_seq(&l.src).and_then(|seq| seq.link_to(&l.dest, l.kind, &map, &opts).map_err(|e| e.to_string())), l.json)
+        }
+        Command::Rm(r) => (run_rm(r, journal), r.json),
+        Command::Fill(f) => {
+            let source = f.placeholder.clone().map_or_else(|| f.from.clone(), FillSource::Placeholder);
+            let strategy = FillStrategy { source, method: f.method };
+            let opts = TransferOptions { on_exists: f.on_exists, dry_run: f.dry_run, journal };
+            (open_seq(&f.src).and_then(|seq| seq.fill_gaps(&strategy, &opts).map_err(|e| e.to_string())), f.json)
+        }
+        Command::Rename(r) => match run_rename(r, journal) {
+            Ok(true) => return,
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Command::Cp(t) | Command::Mv(t) => {
+            let opts = TransferOptions { on_exists: t.on_exists, dry_run: t.dry_run, journal };
+            let result = open_seq(&t.src).and_then(|seq| {
+                let report = if matches!(command, Command::Cp(_)) { seq.copy_to(&t.dest, &opts) } else { seq.move_to(&t.dest, &opts) };
+                report.map_err(|e| e.to_string())
+            });
+            (result, t.json)
+        }
+        Command::Renumber(r) => {
+            let opts = TransferOptions { on_exists: r.on_exists, dry_run: r.dry_run, journal };
+            let how = match (r.offset, r.start) {
+                (Some(offset), _) => Renumber::Offset(offset),
+                (None, start) => Renumber::Start(start.unwrap_or_default()),
+            };
+            let result = open_seq(&r.src).and_then(|seq| seq.renumber(how, &opts).map_err(|e| e.to_string()));
+            // A half-renumbered sequence is worse than none: put the renamed frames back
+            if let Some(report) = result.as_ref().ok().filter(|report| !report.is_ok()) {
+                print_op_report(report, r.json);
+                let undo = report.undo_with(opts.journal.as_ref());
+                eprintln!("Renumber failed, {} renamed frames restored", undo.succeeded().len());
+                if !undo.is_ok() {
+                    print_op_report(&undo, r.json);
+                }
+                std::process::exit(1);
+            }
+            (result, r.json)
+        }
+        Command::Repad(r) => {
+            let opts = TransferOptions { on_exists: r.on_exists, dry_run: r.dry_run, journal };
+            let result = open_seq(&r.src).and_then(|seq| seq.repad(r.padding, &opts).map_err(|e| e.to_string()));
+            let result = result.map(|(seq, report)| {
+                if !r.json {
+                    println!("{}", seq.pattern());
+                }
+                report
+            });
+            (result, r.json)
+        }
+    };
+    match result {
+        Ok(report) => {
+            print_op_report(&report, json);
+            if !report.is_ok() {
+                std::process::exit(1);
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// `rename`: plan every sequence first (nothing runs if any plan fails or two
+/// sequences would get the same name), print the table, then rename one
+/// sequence at a time. `Ok(false)` if any frame failed.
+fn run_rename(r: &RenameArgs, journal: Option<Journal>) -> Result<bool, String> {
+    let to: NameTemplate = r.to.parse().map_err(|e: OpError| e.to_string())?;
+    let matcher: Option<NameTemplate> = r.matcher.as_deref().map(str::parse).transpose().map_err(|e: OpError| e.to_string())?;
+    let mut seqs = Vec::new();
+    for spec in &r.src {
+        seqs.extend(open_seqs(spec)?);
+    }
+
+    let opts = TransferOptions { on_exists: r.on_exists, dry_run: true, journal };
+    let mut plans = Vec::with_capacity(seqs.len());
+    let mut targets = std::collections::HashMap::new();
+    for seq in &seqs {
+        let mut fields = NameFields::new();
+        if let Some(matcher) = &matcher {
+            let prefix = seq.name_fields().remove("prefix").unwrap_or_default();
+            fields = matcher.capture(&prefix).ok_or_else(|| format!("{}: '{}' does not match {}", seq.pattern(), prefix, matcher))?;
+        }
+        fields.extend(r.set.iter().cloned());
+        let dest = seq.rendered_pattern(&to, &fields).map_err(|e| format!("{}: {}", seq.pattern(), e))?;
+        if let Some(other) = targets.insert(dest.clone(), seq.pattern()) {
+            return Err(format!("{} and {} would both be renamed to {}", other, seq.pattern(), dest));
+        }
+        let plan = seq.rename_with(&to, &fields, &opts).map_err(|e| format!("{}: {}", seq.pattern(), e))?;
+        plans.push((seq, fields, dest, plan));
+    }
+
+    let mut all_ok = true;
+    let width = plans.iter().map(|(seq, ..)| seq.pattern().len()).max().unwrap_or(0);
+    let mut results = Vec::with_capacity(plans.len());
+    for (seq, fields, dest, plan) in plans {
+        let report = if r.dry_run { plan } else { seq.rename_with(&to, &fields, &TransferOptions { dry_run: false, ..opts.clone() }).map_err(|e| format!("{}: {}", seq.pattern(), e))? };
+        all_ok &= report.is_ok();
+        if !r.json {
+            let status = match (report.dry_run, report.failed().len(), report.skipped().len()) {
+                (true, _, 0) => format!("{} frames", report.ops.len()),
+                (true, _, skipped) => format!("{} frames, {} skipped", report.ops.len() - skipped, skipped),
+                (false, 0, 0) => format!("{} done", report.succeeded().len()),
+                (false, failed, skipped) => format!("{} done, {} skipped, {} failed", report.succeeded().len(), skipped, failed),
+            };
+            println!("  {:<width$}  ->  {}  ({})", seq.pattern(), dest, status, width = width);
+        }
+        results.push(serde_json::json!({ "from": seq.pattern(), "to": dest, "report": report }));
+    }
+    if r.json {
+        println!("{}", serde_json::to_string_pretty(&results).map_err(|e| e.to_string())?);
+    } else if r.dry_run {
+        println!("Dry run: {} sequences", results.len());
+    } else {
+        println!("{} sequences renamed{}", results.len(), if all_ok { "" } else { ", with failed frames" });
+    }
+    Ok(all_ok)
+}
+
+/// `rm`: plan, ask unless `--yes` (stdin not answering counts as no), then
+/// remove. The removal checks the folder again, so frames that appeared while
+/// asking still stop it.
+fn run_rm(r: &RmArgs, journal: Option<Journal>) -> Result<OpReport, String> {
+    let seq = open_seq(&r.src)?;
+    let selection = match (&r.frames, &r.slice) {
+        (Some(frames), _) => FrameSelection::Frames(parse_frame_ranges(frames).map_err(|e| e.to_string())?),
+        (None, Some(slice)) => FrameSelection::Slice(slice.parse().map_err(|e| format!("--slice {}: {}", slice, e))?),
+        (None, None) => FrameSelection::All,
+    };
+    let mode = r.trash.clone().map_or(RemoveMode::Delete, RemoveMode::Trash);
+    let opts = TransferOptions { on_exists: r.on_exists, dry_run: true, journal };
+    let plan = seq.remove(&selection, &mode, &opts).map_err(|e| e.to_string())?;
+    if r.dry_run || plan.ops.is_empty() {
+        return Ok(plan);
+    }
+    if !r.yes {
+        let what = if r.trash.is_some() { "Move to trash" } else { "Delete" };
+        eprint!("{} {} frames ({}) of {}? [y/N] ", what, plan.ops.len(), format_frame_ranges(&plan.succeeded()), seq.pattern());
+        let mut answer = String::new();
+        let _ = std::io::stdin().read_line(&mut answer);
+        if !matches!(answer.trim().to_ascii_lowercase().as_str(), "y" | "yes") {
+            return Err("aborted, nothing removed".into());
+        }
+    }
+    seq.remove(&selection, &mode, &TransferOptions { dry_run: false, ..opts.clone() }).map_err(|e| e.to_string())
+}
+
+/// `undo`: roll back a journal. Ops an interruption left open are settled
+/// from the disk first (see [`Journal::replay`]).
+fn run_undo(u: &UndoArgs) -> Result<OpReport, String> {
+    if !u.journal.is_file() {
+        return Err(format!("{}: no such journal", u.journal.display()));
+    }
+    let journal = Journal::open(&u.journal).map_err(|e| e.to_string())?;
+    let interrupted = journal.replay().map_err(|e| e.to_string())?.iter().filter(|e| e.interrupted && !e.undone).count();
+    if interrupted > 0 {
+        eprintln!("{}: {} operations were interrupted, settled from the files on disk", u.journal.display(), interrupted);
+    }
+    journal.rollback(u.dry_run).map_err(|e| e.to_string())
+}
+
+/// `checksum`: digest the sequence and write the manifest (or print it).
+fn run_checksum(c: &ChecksumArgs) -> Result<(), String> {
+    let seq = open_seq(&c.src)?;
+    let manifest = seq.checksum(c.algo).map_err(|e| e.to_string())?;
+    match &c.manifest {
+        Some(path) => {
+            manifest.write(path).map_err(|e| e.to_string())?;
+            println!("{}: {} frames ({}) -> {}", seq.pattern(), manifest.digests.len(), manifest.algo, path.display());
+        }
+        None => println!("{}", serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?),
+    }
+    Ok(())
+}
+
+/// `verify`: check the manifest's sequence, or `src`. `Ok(false)` if anything differs.
+fn run_verify(v: &VerifyArgs) -> Result<bool, String> {
+    let manifest = ChecksumManifest::read(&v.manifest).map_err(|e| e.to_string())?;
+    let report = match &v.src {
+        Some(src) => open_seq(src)?.verify(&manifest),
+        None => manifest.verify().map_err(|e| e.to_string())?,
+    };
+    if v.json {
+        println!("{}", serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?);
+    } else {
+        println!("{}", report);
+    }
+    Ok(report.is_ok())
+}
+
+/// Every sequence in folder `spec`, or the one sequence it names (see [`open_seq`]).
+fn open_seqs(spec: &str) -> Result<Vec<Seq>, String> {
+    let path = std::path::Path::new(spec);
+    if path.is_dir() {
+        return get_seqs(path, false, None, 2).map_err(|e| e.to_string());
+    }
+    open_seq(spec).map(|seq| vec![seq])
+}
+
+/// The sequence `spec` names: one of its frames, or its pattern (`comp.####.exr`, `comp.@.exr`).
+fn open_seq(spec: &str) -> Result<Seq, String> {
+    let path = std::path::Path::new(spec);
+    let dir = path.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(std::path::Path::new("."));
+    if path.is_file() {
+        return Scanner::from_file(dir.join(path.file_name().unwrap_or_default())).ok_or_else(|| format!("{}: not part of a sequence", spec));
+    }
+    let seqs = get_seqs(dir, false, None, 2).map_err(|e| e.to_string())?;
+    seqs.into_iter()
+        .find(|seq| std::path::Path::new(seq.pattern()).file_name() == path.file_name())
+        .ok_or_else(|| format!("{}: no such sequence", spec))
+}
+
+/// Print a file operation report: one line per frame, then totals.
+fn print_op_report(report: &OpReport, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(report) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("JSON error: {}", e),
+        }
+        return;
+    }
+    for op in &report.ops {
+        println!("  {}", op);
+    }
+    let (done, skipped, failed) = (report.succeeded().len(), report.skipped().len(), report.failed().len());
+    if report.dry_run {
+        println!("Dry run: {} planned, {} skipped", done, skipped);
+    } else {
+        println!("{} done, {} skipped, {} failed", done, skipped, failed);
+    }
+}
+
+/// Open FILE, or stdin for `-`.
+fn open_input(path: &std::path::Path) -> Result<Box<dyn BufRead>, ScanError> {
+    if path.as_os_str() == "-" {
+        Ok(Box::new(std::io::stdin().lock()))
+    } else {
+        let file = std::fs::File::open(path).map_err(|e| ScanError::io(path, e))?;
+        Ok(Box::new(std::io::BufReader::new(file)))
+    }
+}
+
+/// Group the paths listed in `list` (`-` = stdin), one per line (`--from-list`).
+/// Only line endings are stripped: spaces are part of the path.
+fn group_list(list: &std::path::Path, mask: Option<&str>, min_len: usize) -> ScanResult {
+    let start = std::time::Instant::now();
+    let grouped = open_input(list).and_then(|reader| {
+        let lines: Vec<String> = reader.lines().collect::<Result<_, _>>().map_err(|e| ScanError::io(list, e))?;
+        let opts = GroupOptions { mask: mask.map(str::to_string), min_len };
+        group_paths(lines.iter().map(|l| l.trim_end_matches(['\r', '\n'])).filter(|l| !l.is_empty()), &opts)
+    });
+    let mut result = ScanResult::default();
+    match grouped {
+        Ok(seqs) => result.seqs = seqs,
+        Err(e) => result.errors.push(e),
+    }
+    result.elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+    result
+}
+
+/// Read and group an inventory listing (`--inventory`).
+fn group_listing(listing: &std::path::Path, format: InventoryFormat, s3_schema: &str, mask: Option<&str>, min_len: usize) -> ScanResult {
+    let start = std::time::Instant::now();
+    let grouped = open_input(listing).and_then(|reader| {
+        let entries = match format {
+            InventoryFormat::S3Csv => read_s3_inventory(reader, Some(s3_schema)),
+            _ => read_inventory(reader, format),
+        }
+        .map_err(|e| e.with_path(listing))?;
+        info!("Read {} entries from {}", entries.len(), listing.display());
+        group_inventory(entries, &GroupOptions { mask: mask.map(str::to_string), min_len })
+    });
+    let mut result = ScanResult::default();
+    match grouped {
+        Ok(seqs) => result.seqs = seqs,
+        Err(e) => result.errors.push(e),
+    }
+    result.elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+    result
+}
+
+/// Print one `--watch` event: a JSON line with `-j`, otherwise a short marked line.
+fn print_watch_event(event: &WatchEvent, json: bool) {
+    if json {
+        match serde_json::to_string(event) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("JSON error: {}", e),
+        }
+        return;
+    }
+    match event {
+        WatchEvent::Created { seq } => println!("+ {} [{}-{}] ({} files)", seq.pattern(), seq.start, seq.end, seq.len()),
+        WatchEvent::FramesAdded { pattern, frames } => println!("+ {}: {}", pattern, format_frame_ranges(frames)),
+        WatchEvent::FramesRemoved { pattern, frames } => println!("- {}: {}", pattern, format_frame_ranges(frames)),
+        WatchEvent::Completed { seq } => println!("= {} [{}-{}] complete ({} files)", seq.pattern(), seq.start, seq.end, seq.len()),
+        WatchEvent::Removed { pattern } => println!("- {} (removed)", pattern),
+        WatchEvent::Error { message } => eprintln!("Error: {}", message),
+    }
+}
+
+/// Stale frames of one sequence (`--stale`)
+#[derive(serde::Serialize)]
+struct StaleEntry<'a> {
+    pattern: &'a str,
+    /// Compact ranges for resubmission, e.g. `1-4,7`
+    ranges: String,
+    frames: Vec<i64>,
+}
+
+/// Run [`Seq::stale_frames`] over all sequences, keeping only those with findings.
+fn stale_seqs<'a>(seqs: &'a [Seq], rule: &StaleRule) -> Vec<StaleEntry<'a>> {
+    seqs.iter()
+        .map(|seq| {
+            let frames = seq.stale_frames(rule);
+            StaleEntry { pattern: seq.pattern(), ranges: format_frame_ranges(&frames), frames }
+        })
+        .filter(|entry| !entry.frames.is_empty())
+        .collect()
+}
+
+/// Print the `--stale` report on its own (without `-o`, or in human-readable mode).
+fn print_stale_report(stale: &[StaleEntry], total_seqs: usize, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(stale) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("JSON error: {}", e),
+        }
+    } else if stale.is_empty() {
+        println!("Stale: no stale frames in {} sequences.", total_seqs);
+    } else {
+        println!("Stale:");
+        for entry in stale {
+            println!("  {} ({} stale)", entry.pattern, entry.frames.len());
+            println!("    {}", entry.ranges);
+        }
+    }
+}
+
+/// Held frames of one sequence (`--held`)
+#[derive(serde::Serialize)]
+struct HeldEntry<'a> {
+    pattern: &'a str,
+    runs: Vec<HeldRun>,
+}
+
+/// Run [`Seq::held_frames`] over all sequences, keeping only those with findings.
+fn held_seqs<'a>(seqs: &'a [Seq], opts: &DuplicateOptions) -> Vec<HeldEntry<'a>> {
+    seqs.iter()
+        .map(|seq| HeldEntry { pattern: seq.pattern(), runs: seq.held_frames(opts) })
+        .filter(|entry| !entry.runs.is_empty())
+        .collect()
+}
+
+/// Print the `--held` report on its own (without `-o`, or in human-readable mode).
+fn print_held_report(held: &[HeldEntry], total_seqs: usize, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(held) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("JSON error: {}", e),
+        }
+    } else if held.is_empty() {
+        println!("Held: no identical consecutive frames in {} sequences.", total_seqs);
+    } else {
+        println!("Held:");
+        for entry in held {
+            println!("  {} ({} runs)", entry.pattern, entry.runs.len());
+            for run in &entry.runs {
+                println!("    {}", run);
+            }
+        }
+    }
+}
+
+/// Print the `--dupes` report on its own (without `-o`, or in human-readable mode).
+fn print_dupes_report(dupes: &[DuplicateGroup], total_seqs: usize, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(dupes) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("JSON error: {}", e),
+        }
+    } else if dupes.is_empty() {
+        println!("Dupes: no frames shared between {} sequences.", total_seqs);
+    } else {
+        println!("Dupes:");
+        for group in dupes {
+            println!("  {}", group);
+        }
+    }
+}
+
+/// Parse a duration like `90`, `90s`, `30m`, `1h` or `2d` (bare numbers are seconds).
+fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let (num, unit) = match s.find(|c: char| !c.is_ascii_digit()) {
+        Some(pos) => s.split_at(pos),
+        None => (s, "s"),
+    };
+    let n: u64 = num.parse().map_err(|_| format!("invalid duration '{}'", s))?;
+    let secs = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86_400,
+        _ => return Err(format!("invalid duration unit in '{}' (use s, m, h or d)", s)),
+    };
+    n.checked_mul(secs).map(Duration::from_secs).ok_or_else(|| format!("duration '{}' is out of range", s))
+}
+
+/// Parse a fraction from 0 to 1 (`--check-ratio`, `--check-drop`).
+fn parse_fraction(s: &str) -> Result<f64, String> {
+    match s.trim().parse::<f64>() {
+        Ok(f) if (0.0..=1.0).contains(&f) => Ok(f),
+        _ => Err(format!("'{}' is not a fraction from 0 to 1", s)),
+    }
+}
+
+/// Parse a point in time: the mtime of an existing file, epoch seconds, or an
+/// age relative to now (`2h` = two hours ago).
+fn parse_when(s: &str) -> Result<SystemTime, String> {
+    if let Ok(meta) = std::fs::metadata(s) {
+        return meta.modified().map_err(|e| format!("{}: {}", s, e));
+    }
+    if let Ok(secs) = s.parse::<u64>() {
+        return SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(secs)).ok_or_else(|| format!("epoch seconds '{}' are out of range", s));
+    }
+    let age = parse_duration(s).map_err(|_| format!("'{}' is not a file, epoch seconds or an age like 2h", s))?;
+    SystemTime::now().checked_sub(age).ok_or_else(|| format!("age '{}' is out of range", s))
+}
+
+/// Format a byte count with a binary unit suffix (e.g. `1.5 GiB`).
+fn human_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
 }